@@ -0,0 +1,185 @@
+/// The number of columns `l` of the Anemoi state: the state is `l` elements
+/// `x` followed by `l` elements `y`
+pub const NUM_COLUMNS: usize = 4;
+
+/// The size of the state
+pub const STATE_SIZE: usize = 2 * NUM_COLUMNS;
+
+/// The number of elements of a digest; Jive compresses two digests into one
+pub const DIGEST_SIZE: usize = NUM_COLUMNS;
+
+/// The number of rounds for 128-bit security with `l = 4` and `alpha = 7`,
+/// from the table of the Anemoi paper
+pub const NUM_ROUNDS: usize = 12;
+
+/// The generator `g` of the multiplicative group, the `beta` of the Flystel
+pub const GENERATOR: u64 = 7;
+
+/// The `delta` of the Flystel, `1 / g`
+pub const GENERATOR_INV: u64 = 2_635_249_152_773_512_046;
+
+/// The Flystel power map degree
+pub const ALPHA: u64 = 7;
+
+/// The inverse power map exponent, `1 / 7 mod (p - 1)`
+pub const INV_ALPHA: u64 = 10_540_996_611_094_048_183;
+
+/// The matrix `M_x` of the linear layer for `l = 4`. `M_y` is the same matrix
+/// applied to the `y` elements rotated by one.
+pub const MDS: [[u64; NUM_COLUMNS]; NUM_COLUMNS] =
+    [[1, 8, 7, 7], [49, 56, 8, 15], [49, 49, 1, 8], [8, 15, 7, 8]];
+
+/// The round constants added to the `x` elements,
+/// `g * pi_0^(2r) + (pi_0^r + pi_1^i)^alpha` with the digits of pi
+/// `pi_0 = 1415926535` and `pi_1 = 8979323846`
+pub const ROUND_CONSTANTS_C: [[u64; NUM_COLUMNS]; NUM_ROUNDS] = [
+    [
+        135,
+        12_616_817_002_499_211_280,
+        10_322_855_620_882_634_095,
+        11_515_394_017_209_471_433,
+    ],
+    [
+        4_589_240_930_789_969_862,
+        16_618_325_948_943_106_831,
+        18_200_144_109_916_626_466,
+        10_404_409_773_164_030_300,
+    ],
+    [
+        1_056_806_635_938_363_454,
+        12_032_802_661_514_536_002,
+        10_002_665_097_632_248_002,
+        6_412_252_127_398_148_956,
+    ],
+    [
+        2_033_339_464_122_141_606,
+        16_651_387_481_995_968_952,
+        4_985_598_636_326_750_382,
+        16_478_276_872_291_823_677,
+    ],
+    [
+        17_998_547_505_995_654_386,
+        6_416_007_608_347_979_543,
+        3_759_814_826_981_602_401,
+        13_568_539_573_461_218_236,
+    ],
+    [
+        6_248_231_951_858_815_273,
+        10_075_988_513_384_689_706,
+        8_639_551_545_635_388_466,
+        6_349_442_195_028_952_653,
+    ],
+    [
+        6_373_743_027_521_678_262,
+        15_640_457_269_987_185_203,
+        7_059_777_190_151_104_864,
+        16_234_292_380_744_713_453,
+    ],
+    [
+        9_496_862_436_573_416_816,
+        8_021_320_825_921_566_955,
+        10_011_109_434_848_134_153,
+        15_157_571_183_736_724_529,
+    ],
+    [
+        4_953_972_297_419_589_317,
+        15_709_647_264_193_197_289,
+        1_063_287_896_505_862_446,
+        1_284_682_883_009_552_102,
+    ],
+    [
+        12_577_582_626_570_549_900,
+        2_955_264_990_711_345_269,
+        8_561_611_934_416_731_227,
+        5_067_004_547_994_152_727,
+    ],
+    [
+        5_581_374_028_569_421_360,
+        15_114_421_678_379_478_793,
+        1_019_269_691_093_785_138,
+        13_699_130_382_728_479_215,
+    ],
+    [
+        5_672_788_376_636_630_092,
+        784_399_856_111_131_110,
+        8_161_190_177_550_306_304,
+        12_680_577_234_628_258_312,
+    ],
+];
+
+/// The round constants added to the `y` elements,
+/// `g * pi_1^(2i) + (pi_0^r + pi_1^i)^alpha + 1 / g`
+pub const ROUND_CONSTANTS_D: [[u64; NUM_COLUMNS]; NUM_ROUNDS] = [
+    [
+        2_635_249_152_773_512_181,
+        7_800_797_122_830_231_380,
+        9_653_037_688_956_642_187,
+        3_310_491_136_445_819_131,
+    ],
+    [
+        11_637_298_485_358_322_661,
+        16_215_114_471_068_967_684,
+        3_496_390_510_370_890_990,
+        6_612_315_294_195_218_751,
+    ],
+    [
+        9_725_267_137_655_892_445,
+        13_249_994_130_789_573_047,
+        15_366_058_514_650_273_039,
+        4_240_560_595_578_513_599,
+    ],
+    [
+        15_778_423_628_933_702_095,
+        4_498_458_544_950_453_174,
+        15_425_615_716_438_806_917,
+        936_464_934_151_635_497,
+    ],
+    [
+        15_842_802_120_403_555_692,
+        15_255_737_259_727_973_224,
+        16_745_746_426_104_584_074,
+        572_642_154_331_955_194,
+    ],
+    [
+        1_174_110_789_228_001_021,
+        15_997_342_387_725_967_829,
+        260_363_298_305_070_260,
+        8_881_913_068_275_558_374,
+    ],
+    [
+        14_790_259_923_157_830_657,
+        16_605_705_133_180_845_652,
+        12_171_227_001_087_753_305,
+        13_810_657_242_843_701_500,
+    ],
+    [
+        331_279_510_332_425_244,
+        9_851_212_936_652_667_758,
+        15_987_203_493_322_222_948,
+        13_598_580_293_373_152_930,
+    ],
+    [
+        16_113_664_442_313_208_359,
+        971_326_307_229_740_064,
+        8_917_912_956_699_977_534,
+        1_604_222_994_366_006_796,
+    ],
+    [
+        7_461_308_516_502_810_908,
+        8_834_465_917_615_698_652,
+        140_270_739_649_488_281,
+        7_557_322_473_803_833_708,
+    ],
+    [
+        351_274_238_924_496_356,
+        2_433_052_856_292_061_843,
+        10_930_846_886_163_940_501,
+        16_075_622_628_960_974_184,
+    ],
+    [
+        11_326_755_175_014_858_375,
+        17_433_841_691_461_451_768,
+        10_510_089_891_229_030_633,
+        7_494_391_999_469_322_247,
+    ],
+];
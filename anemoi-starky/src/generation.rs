@@ -0,0 +1,278 @@
+use crate::columns::{
+    col_round_state_start, COL_DIGEST_START, COL_INPUT_START, COL_OUTPUT_START, NUM_COLS,
+};
+use crate::constants::{
+    DIGEST_SIZE, GENERATOR, GENERATOR_INV, INV_ALPHA, MDS, NUM_COLUMNS, NUM_ROUNDS,
+    ROUND_CONSTANTS_C, ROUND_CONSTANTS_D, STATE_SIZE,
+};
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+
+// Represent a row of the preimage
+#[derive(Debug, Clone, Default)]
+pub struct Row<Field: RichField> {
+    pub preimage: [Field; STATE_SIZE],
+}
+
+/// Pad the trace to a power of 2.
+#[must_use]
+fn pad_trace<F: RichField>(mut trace: Vec<Vec<F>>) -> Vec<Vec<F>> {
+    let ext_trace_len = trace[0].len().next_power_of_two();
+
+    // All columns have their last value duplicated.
+    for row in &mut trace {
+        row.resize(ext_trace_len, *row.last().unwrap());
+    }
+
+    trace
+}
+
+fn mds<F: Field>(column: &[F]) -> [F; NUM_COLUMNS] {
+    std::array::from_fn(|i| {
+        (0..NUM_COLUMNS).fold(F::ZERO, |acc, j| {
+            acc + column[j] * F::from_canonical_u64(MDS[i][j])
+        })
+    })
+}
+
+/// The linear layer: `M_x` on the `x` elements, `M_y` on the `y` elements and
+/// the pseudo-Hadamard transform mixing them
+pub(crate) fn linear_layer<F: Field>(state: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    let x = mds(&state[..NUM_COLUMNS]);
+    let rotated_y: [F; NUM_COLUMNS] =
+        std::array::from_fn(|i| state[NUM_COLUMNS + (i + 1) % NUM_COLUMNS]);
+    let y = mds(&rotated_y);
+
+    std::array::from_fn(|i| {
+        let j = i % NUM_COLUMNS;
+        if i < NUM_COLUMNS {
+            x[j].double() + y[j]
+        } else {
+            x[j] + y[j]
+        }
+    })
+}
+
+/// The open Flystel, mapping `(x, y)` to `(u, v)` with
+/// `v = y - (x - g * y^2)^(1/alpha)` and `u = x - g * y^2 + g * v^2 + 1 / g`
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn flystel<F: Field>(x: F, y: F) -> (F, F) {
+    let g = F::from_canonical_u64(GENERATOR);
+    let t = x - g * y.square();
+    let v = y - t.exp_u64(INV_ALPHA);
+    let u = t + g * v.square() + F::from_canonical_u64(GENERATOR_INV);
+    (u, v)
+}
+
+/// The states at the end of each round
+fn generate_round_states<F: RichField>(preimage: &[F; STATE_SIZE]) -> Vec<[F; STATE_SIZE]> {
+    let mut state = *preimage;
+    (0..NUM_ROUNDS)
+        .map(|r| {
+            for i in 0..NUM_COLUMNS {
+                state[i] += F::from_canonical_u64(ROUND_CONSTANTS_C[r][i]);
+                state[NUM_COLUMNS + i] += F::from_canonical_u64(ROUND_CONSTANTS_D[r][i]);
+            }
+            state = linear_layer(&state);
+            for i in 0..NUM_COLUMNS {
+                (state[i], state[NUM_COLUMNS + i]) = flystel(state[i], state[NUM_COLUMNS + i]);
+            }
+            state
+        })
+        .collect()
+}
+
+/// The Anemoi permutation: the rounds followed by a final linear layer
+#[must_use]
+pub fn permute<F: RichField>(preimage: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    linear_layer(generate_round_states(preimage).last().unwrap())
+}
+
+/// The Jive mode: the sum of the two halves of the input and of its image
+fn jive<F: Field>(input: &[F; STATE_SIZE], output: &[F; STATE_SIZE]) -> [F; DIGEST_SIZE] {
+    std::array::from_fn(|i| input[i] + input[DIGEST_SIZE + i] + output[i] + output[DIGEST_SIZE + i])
+}
+
+/// Compress two digests into one with Anemoi in the Jive mode
+#[must_use]
+pub fn compress<F: RichField>(
+    left: &[F; DIGEST_SIZE],
+    right: &[F; DIGEST_SIZE],
+) -> [F; DIGEST_SIZE] {
+    let preimage = std::array::from_fn(|i| {
+        if i < DIGEST_SIZE {
+            left[i]
+        } else {
+            right[i - DIGEST_SIZE]
+        }
+    });
+    jive(&preimage, &permute(&preimage))
+}
+
+/// Write the permutation columns of a single row into a column-major trace
+fn fill_permutation_row<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    preimage: &[F; STATE_SIZE],
+) {
+    for (j, &value) in preimage.iter().enumerate() {
+        trace[COL_INPUT_START + j][row] = value;
+    }
+    let round_states = generate_round_states(preimage);
+    for (r, state) in round_states.iter().enumerate() {
+        for (j, &value) in state.iter().enumerate() {
+            trace[col_round_state_start(r) + j][row] = value;
+        }
+    }
+    let output = linear_layer(round_states.last().unwrap());
+    for (j, &value) in output.iter().enumerate() {
+        trace[COL_OUTPUT_START + j][row] = value;
+    }
+    for (j, value) in jive(preimage, &output).into_iter().enumerate() {
+        trace[COL_DIGEST_START + j][row] = value;
+    }
+}
+
+/// Function to generate the Anemoi trace: one row per permutation, padded to
+/// a power of 2 by repeating the last row.
+#[must_use]
+pub fn generate_anemoi_trace<F: RichField>(step_rows: &[Row<F>]) -> [Vec<F>; NUM_COLS] {
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; step_rows.len()]; NUM_COLS];
+    for (i, row) in step_rows.iter().enumerate() {
+        fill_permutation_row(&mut trace, i, &row.preimage);
+    }
+
+    pad_trace(trace).try_into().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::columns::{COL_DIGEST_START, COL_OUTPUT_START};
+    use crate::constants::{
+        ALPHA, DIGEST_SIZE, GENERATOR, GENERATOR_INV, NUM_COLUMNS, NUM_ROUNDS, ROUND_CONSTANTS_C,
+        ROUND_CONSTANTS_D, STATE_SIZE,
+    };
+    use crate::generation::{compress, flystel, generate_anemoi_trace, permute, Row};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::ops::Square;
+    use plonky2::field::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn round_constants() {
+        let g = F::MULTIPLICATIVE_GROUP_GENERATOR;
+        assert_eq!(g, F::from_canonical_u64(GENERATOR));
+        assert_eq!(g.inverse(), F::from_canonical_u64(GENERATOR_INV));
+
+        let pi_0 = F::from_canonical_u64(1_415_926_535);
+        let pi_1 = F::from_canonical_u64(8_979_323_846);
+        for r in 0..NUM_ROUNDS {
+            for i in 0..NUM_COLUMNS {
+                let pi_0_r = pi_0.exp_u64(r as u64);
+                let pi_1_i = pi_1.exp_u64(i as u64);
+                let power = (pi_0_r + pi_1_i).exp_u64(ALPHA);
+                assert_eq!(
+                    F::from_canonical_u64(ROUND_CONSTANTS_C[r][i]),
+                    g * pi_0_r.square() + power
+                );
+                assert_eq!(
+                    F::from_canonical_u64(ROUND_CONSTANTS_D[r][i]),
+                    g * pi_1_i.square() + power + g.inverse()
+                );
+            }
+        }
+    }
+
+    /// The open Flystel satisfies the closed-form relations checked by the
+    /// table
+    #[test]
+    #[allow(clippy::many_single_char_names)]
+    fn closed_flystel() {
+        let g = F::from_canonical_u64(GENERATOR);
+        let (x, y) = (F::rand(), F::rand());
+        let (u, v) = flystel(x, y);
+        assert_eq!((y - v).exp_u64(ALPHA), x - g * y.square());
+        assert_eq!(
+            u,
+            x - g * y.square() + g * v.square() + F::from_canonical_u64(GENERATOR_INV)
+        );
+    }
+
+    /// The image and the Jive digest of `0, 1, ..., 7`. There is no published
+    /// Anemoi instance over Goldilocks, so this is a self-generated regression
+    /// vector, produced by this implementation.
+    #[test]
+    fn regression_vector() {
+        let preimage = std::array::from_fn(F::from_canonical_usize);
+        let expected_output: [u64; STATE_SIZE] = [
+            11_515_609_917_801_390_184,
+            1_713_463_962_434_055_162,
+            3_678_644_482_602_860_764,
+            14_297_019_700_173_281_080,
+            15_560_913_519_839_017_137,
+            15_713_578_937_035_233_663,
+            12_010_100_151_171_867_056,
+            11_277_591_278_900_525_120,
+        ];
+        let expected_digest: [u64; DIGEST_SIZE] = [
+            8_629_779_368_225_823_004,
+            17_427_042_899_469_288_831,
+            15_688_744_633_774_727_828,
+            7_127_866_909_659_221_889,
+        ];
+
+        assert_eq!(
+            permute(&preimage),
+            expected_output.map(F::from_canonical_u64)
+        );
+        let (left, right) = preimage.split_at(DIGEST_SIZE);
+        assert_eq!(
+            compress(left.try_into().unwrap(), right.try_into().unwrap()),
+            expected_digest.map(F::from_canonical_u64)
+        );
+    }
+
+    #[test]
+    fn compress_is_jive() {
+        let left: [F; DIGEST_SIZE] = F::rand_array();
+        let right: [F; DIGEST_SIZE] = F::rand_array();
+        let mut preimage = [F::ZERO; STATE_SIZE];
+        preimage[..DIGEST_SIZE].copy_from_slice(&left);
+        preimage[DIGEST_SIZE..].copy_from_slice(&right);
+        let output = permute(&preimage);
+
+        let digest = compress(&left, &right);
+        for i in 0..DIGEST_SIZE {
+            assert_eq!(
+                digest[i],
+                left[i] + right[i] + output[i] + output[DIGEST_SIZE + i]
+            );
+        }
+    }
+
+    #[test]
+    fn generate_anemoi_trace_outputs() {
+        let step_rows: Vec<Row<F>> = (0..5)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let trace = generate_anemoi_trace(&step_rows);
+        assert_eq!(trace[0].len(), 8);
+        for (i, row) in step_rows.iter().enumerate() {
+            let expected = permute(&row.preimage);
+            for j in 0..STATE_SIZE {
+                assert_eq!(trace[COL_OUTPUT_START + j][i], expected[j]);
+            }
+            let (left, right) = row.preimage.split_at(DIGEST_SIZE);
+            let digest = compress(left.try_into().unwrap(), right.try_into().unwrap());
+            for j in 0..DIGEST_SIZE {
+                assert_eq!(trace[COL_DIGEST_START + j][i], digest[j]);
+            }
+        }
+        // the padding rows repeat the last row
+        assert_eq!(trace[COL_OUTPUT_START][7], trace[COL_OUTPUT_START][4]);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::columns::{
+    col_round_state_start, COL_DIGEST_START, COL_INPUT_START, COL_OUTPUT_START, NUM_COLS,
+};
+use crate::constants::{
+    ALPHA, DIGEST_SIZE, GENERATOR, GENERATOR_INV, MDS, NUM_COLUMNS, NUM_ROUNDS, ROUND_CONSTANTS_C,
+    ROUND_CONSTANTS_D, STATE_SIZE,
+};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+fn mds_constraints<P: PackedField>(column: &[P]) -> [P; NUM_COLUMNS] {
+    std::array::from_fn(|i| {
+        (0..NUM_COLUMNS).fold(P::ZEROS, |acc, j| {
+            acc + column[j] * P::Scalar::from_canonical_u64(MDS[i][j])
+        })
+    })
+}
+
+// linear layer (degree = 1)
+fn linear_layer_constraints<P: PackedField>(state: &[P; STATE_SIZE]) -> [P; STATE_SIZE] {
+    let x = mds_constraints(&state[..NUM_COLUMNS]);
+    let rotated_y: [P; NUM_COLUMNS] =
+        std::array::from_fn(|i| state[NUM_COLUMNS + (i + 1) % NUM_COLUMNS]);
+    let y = mds_constraints(&rotated_y);
+
+    std::array::from_fn(|i| {
+        let j = i % NUM_COLUMNS;
+        if i < NUM_COLUMNS {
+            x[j] + x[j] + y[j]
+        } else {
+            x[j] + y[j]
+        }
+    })
+}
+
+// degree: 1
+fn add_rc_constraints<P: PackedField>(state: &[P; STATE_SIZE], r: usize) -> [P; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        let constant = if i < NUM_COLUMNS {
+            ROUND_CONSTANTS_C[r][i]
+        } else {
+            ROUND_CONSTANTS_D[r][i - NUM_COLUMNS]
+        };
+        state[i] + P::Scalar::from_canonical_u64(constant)
+    })
+}
+
+// degree: 7
+fn sbox_constraints<P: PackedField>(x: P) -> P {
+    (0..ALPHA).fold(P::ONES, |acc, _| acc * x)
+}
+
+/// Constraints of the closed Flystel: `(x, y)` maps to `(u, v)` if and only
+/// if `(y - v)^alpha = x - g * y^2` and `u = x - g * y^2 + g * v^2 + 1 / g`
+#[allow(clippy::many_single_char_names)]
+fn flystel_constraints<P: PackedField>(
+    (x, y): (P, P),
+    (u, v): (P, P),
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let g = P::Scalar::from_canonical_u64(GENERATOR);
+    let t = x - y.square() * g;
+    yield_constr.constraint(sbox_constraints(y - v) - t);
+    yield_constr.constraint(t + v.square() * g + P::Scalar::from_canonical_u64(GENERATOR_INV) - u);
+}
+
+fn state_at<P: PackedField>(lv: &[P], start: usize) -> [P; STATE_SIZE] {
+    lv[start..start + STATE_SIZE].try_into().unwrap()
+}
+
+/// Constraints of a single Anemoi permutation and its Jive compression laid
+/// out as in `columns.rs`, starting at the first column of `lv`
+pub(crate) fn eval_permutation<P: PackedField>(lv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    let input = state_at(lv, COL_INPUT_START);
+    let mut state = input;
+
+    for r in 0..NUM_ROUNDS {
+        let flystel_input = linear_layer_constraints(&add_rc_constraints(&state, r));
+        let round_state = state_at(lv, col_round_state_start(r));
+        for i in 0..NUM_COLUMNS {
+            flystel_constraints(
+                (flystel_input[i], flystel_input[NUM_COLUMNS + i]),
+                (round_state[i], round_state[NUM_COLUMNS + i]),
+                yield_constr,
+            );
+        }
+        state = round_state;
+    }
+
+    let output = state_at(lv, COL_OUTPUT_START);
+    for (&value, &expected) in output.iter().zip(&linear_layer_constraints(&state)) {
+        yield_constr.constraint(value - expected);
+    }
+
+    // Jive: the digest is the sum of the halves of the input and the output
+    for i in 0..DIGEST_SIZE {
+        yield_constr.constraint(
+            lv[COL_DIGEST_START + i]
+                - input[i]
+                - input[DIGEST_SIZE + i]
+                - output[i]
+                - output[DIGEST_SIZE + i],
+        );
+    }
+}
+
+/// The Anemoi permutation table, one row per permutation.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct AnemoiStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for AnemoiStark<F, D> {
+    const COLUMNS: usize = NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_permutation(vars.local_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::{generate_anemoi_trace, Row};
+    use crate::stark::{trace_to_poly_values, AnemoiStark};
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = AnemoiStark<F, D>;
+
+    #[test]
+    fn anemoi_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let step_rows: Vec<Row<F>> = (0..12)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let stark = S::default();
+        let trace = generate_anemoi_trace(&step_rows);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn anemoi_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
@@ -0,0 +1,80 @@
+use crate::constants::{BLOCK_WORDS, CV_WORDS, G_STATE_WORDS, NUM_G, NUM_ROUNDS, STATE_WORDS};
+
+/// The starting point of the round flags: column `COL_STEP_START + i` is set
+/// on the row of round `i`
+pub(crate) const COL_STEP_START: usize = 0;
+
+/// The starting point of the input chaining value, repeated on each row of
+/// the compression
+pub(crate) const COL_CV_START: usize = COL_STEP_START + NUM_ROUNDS; // 7
+
+/// The low and high words of the counter, the block length and the flags,
+/// repeated on each row of the compression
+pub(crate) const COL_COUNTER_LO: usize = COL_CV_START + CV_WORDS; // 15
+pub(crate) const COL_COUNTER_HI: usize = COL_COUNTER_LO + 1; // 16
+pub(crate) const COL_BLOCK_LEN: usize = COL_COUNTER_HI + 1; // 17
+pub(crate) const COL_FLAGS: usize = COL_BLOCK_LEN + 1; // 18
+
+/// The starting point of the bits of the message words in the order used by
+/// the round, little-endian per word
+const COL_MESSAGE_BITS_START: usize = COL_FLAGS + 1; // 19
+
+/// The starting point of the bits of the state at the start of the round
+const COL_STATE_BITS_START: usize = COL_MESSAGE_BITS_START + BLOCK_WORDS * 32; // 531
+
+/// The starting point of the columns of the G functions of the round
+const COL_G_START: usize = COL_STATE_BITS_START + STATE_WORDS * 32; // 1043
+
+/// The offsets within the columns of a G function of the bits of the
+/// intermediate `a` and `c` and of the outputs, and of the carries of the
+/// additions modulo 2^32
+pub(crate) const G_A1: usize = 0;
+pub(crate) const G_A1_CARRY: usize = G_A1 + 32; // 2 bits
+pub(crate) const G_C1: usize = G_A1_CARRY + 2;
+pub(crate) const G_C1_CARRY: usize = G_C1 + 32; // 1 bit
+pub(crate) const G_A2: usize = G_C1_CARRY + 1;
+pub(crate) const G_A2_CARRY: usize = G_A2 + 32; // 2 bits
+pub(crate) const G_C2: usize = G_A2_CARRY + 2;
+pub(crate) const G_C2_CARRY: usize = G_C2 + 32; // 1 bit
+pub(crate) const G_B2: usize = G_C2_CARRY + 1;
+pub(crate) const G_D2: usize = G_B2 + 32;
+const G_NUM_COLS: usize = G_D2 + 32; // 198
+
+/// The starting point of the output chaining value, in the row of the last
+/// round
+pub(crate) const COL_OUTPUT_START: usize = COL_G_START + NUM_G * G_NUM_COLS; // 2627
+
+/// The total number of columns
+pub const NUM_COLS: usize = COL_OUTPUT_START + CV_WORDS; // 2635
+
+/// The bits of message word `i`
+pub(crate) const fn col_message_bits(i: usize) -> usize {
+    COL_MESSAGE_BITS_START + 32 * i
+}
+
+/// The bits of state word `i` at the start of the round
+pub(crate) const fn col_state_bits(i: usize) -> usize {
+    COL_STATE_BITS_START + 32 * i
+}
+
+/// The columns of G function `g`
+pub(crate) const fn col_g(g: usize) -> usize {
+    COL_G_START + g * G_NUM_COLS
+}
+
+/// The range of the columns that are bits: the message, the state and the
+/// G functions
+pub(crate) const BIT_COLS: std::ops::Range<usize> = COL_MESSAGE_BITS_START..COL_OUTPUT_START;
+
+/// The bits of each state word before G function `g` of the round; with
+/// `g = NUM_G` these are the bits at the end of the round
+pub(crate) fn col_state_bits_before_g(g: usize) -> [usize; STATE_WORDS] {
+    let mut bits = std::array::from_fn(col_state_bits);
+    for (i, [a, b, c, d]) in G_STATE_WORDS.into_iter().take(g).enumerate() {
+        bits[a] = col_g(i) + G_A2;
+        bits[b] = col_g(i) + G_B2;
+        bits[c] = col_g(i) + G_C2;
+        bits[d] = col_g(i) + G_D2;
+    }
+    bits
+}
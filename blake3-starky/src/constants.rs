@@ -0,0 +1,56 @@
+/// The number of rounds of the compression function; each round takes one row
+pub const NUM_ROUNDS: usize = 7;
+
+/// The number of 32-bit words of the state
+pub const STATE_WORDS: usize = 16;
+
+/// The number of 32-bit words of a message block
+pub const BLOCK_WORDS: usize = 16;
+
+/// The number of 32-bit words of a chaining value
+pub const CV_WORDS: usize = 8;
+
+/// The number of bytes of a message block
+pub const BLOCK_LEN: usize = 64;
+
+/// The number of bytes of a chunk
+pub const CHUNK_LEN: usize = 1024;
+
+/// The number of G functions of a round, 4 on the columns and 4 on the
+/// diagonals of the state
+pub const NUM_G: usize = 8;
+
+/// The initial chaining value, shared with SHA-256
+pub const IV: [u32; CV_WORDS] = [
+    0x6a09_e667,
+    0xbb67_ae85,
+    0x3c6e_f372,
+    0xa54f_f53a,
+    0x510e_527f,
+    0x9b05_688c,
+    0x1f83_d9ab,
+    0x5be0_cd19,
+];
+
+/// The permutation of the message words applied after each round
+pub const MSG_PERMUTATION: [usize; BLOCK_WORDS] =
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// The state words `(a, b, c, d)` of each G function of a round. G function
+/// `g` mixes the message words `2 * g` and `2 * g + 1`.
+pub const G_STATE_WORDS: [[usize; 4]; NUM_G] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+/// The domain flags
+pub const CHUNK_START: u32 = 1 << 0;
+pub const CHUNK_END: u32 = 1 << 1;
+pub const PARENT: u32 = 1 << 2;
+pub const ROOT: u32 = 1 << 3;
@@ -0,0 +1,225 @@
+use crate::columns::{
+    col_g, col_message_bits, col_state_bits, COL_BLOCK_LEN, COL_COUNTER_HI, COL_COUNTER_LO,
+    COL_CV_START, COL_FLAGS, COL_OUTPUT_START, COL_STEP_START, G_A1, G_A1_CARRY, G_A2, G_A2_CARRY,
+    G_B2, G_C1, G_C1_CARRY, G_C2, G_C2_CARRY, G_D2, NUM_COLS,
+};
+use crate::constants::{
+    BLOCK_WORDS, CV_WORDS, G_STATE_WORDS, IV, MSG_PERMUTATION, NUM_G, NUM_ROUNDS, STATE_WORDS,
+};
+use plonky2::hash::hash_types::RichField;
+
+/// The inputs of one call of the compression function
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Compression {
+    pub cv: [u32; CV_WORDS],
+    pub block: [u32; BLOCK_WORDS],
+    pub counter: u64,
+    pub block_len: u32,
+    pub flags: u32,
+}
+
+/// The intermediate values of a G function. The sums are kept before the
+/// reduction modulo 2^32.
+struct GValues {
+    a1: u64,
+    c1: u64,
+    a2: u64,
+    c2: u64,
+    b2: u32,
+    d2: u32,
+}
+
+/// The G function on the state words `(a, b, c, d)` with the message words
+/// `mx` and `my`
+#[allow(clippy::cast_possible_truncation, clippy::many_single_char_names)]
+fn g(state: &mut [u32; STATE_WORDS], [a, b, c, d]: [usize; 4], mx: u32, my: u32) -> GValues {
+    let a1 = u64::from(state[a]) + u64::from(state[b]) + u64::from(mx);
+    let d1 = (state[d] ^ a1 as u32).rotate_right(16);
+    let c1 = u64::from(state[c]) + u64::from(d1);
+    let b1 = (state[b] ^ c1 as u32).rotate_right(12);
+    let a2 = u64::from(a1 as u32) + u64::from(b1) + u64::from(my);
+    let d2 = (d1 ^ a2 as u32).rotate_right(8);
+    let c2 = u64::from(c1 as u32) + u64::from(d2);
+    let b2 = (b1 ^ c2 as u32).rotate_right(7);
+
+    state[a] = a2 as u32;
+    state[b] = b2;
+    state[c] = c2 as u32;
+    state[d] = d2;
+    GValues {
+        a1,
+        c1,
+        a2,
+        c2,
+        b2,
+        d2,
+    }
+}
+
+/// One round: the G functions on the columns and then on the diagonals
+fn round(state: &mut [u32; STATE_WORDS], message: &[u32; BLOCK_WORDS]) -> [GValues; NUM_G] {
+    std::array::from_fn(|i| g(state, G_STATE_WORDS[i], message[2 * i], message[2 * i + 1]))
+}
+
+fn permute(message: &[u32; BLOCK_WORDS]) -> [u32; BLOCK_WORDS] {
+    MSG_PERMUTATION.map(|i| message[i])
+}
+
+impl Compression {
+    /// The state at the start of the first round
+    #[allow(clippy::cast_possible_truncation)]
+    fn initial_state(&self) -> [u32; STATE_WORDS] {
+        let mut state = [0; STATE_WORDS];
+        state[..CV_WORDS].copy_from_slice(&self.cv);
+        state[CV_WORDS..CV_WORDS + 4].copy_from_slice(&IV[..4]);
+        state[12] = self.counter as u32;
+        state[13] = (self.counter >> 32) as u32;
+        state[14] = self.block_len;
+        state[15] = self.flags;
+        state
+    }
+
+    /// The state at the end of the last round
+    fn final_state(&self) -> [u32; STATE_WORDS] {
+        let mut state = self.initial_state();
+        let mut message = self.block;
+        for _ in 0..NUM_ROUNDS {
+            round(&mut state, &message);
+            message = permute(&message);
+        }
+        state
+    }
+
+    /// The output of the compression function: the output chaining value
+    /// followed by the words of the extended output
+    #[must_use]
+    pub fn output(&self) -> [u32; STATE_WORDS] {
+        let state = self.final_state();
+        std::array::from_fn(|i| {
+            if i < CV_WORDS {
+                state[i] ^ state[i + CV_WORDS]
+            } else {
+                state[i] ^ self.cv[i - CV_WORDS]
+            }
+        })
+    }
+
+    /// The output chaining value
+    #[must_use]
+    pub fn output_cv(&self) -> [u32; CV_WORDS] {
+        self.output()[..CV_WORDS].try_into().unwrap()
+    }
+}
+
+fn fill_bits<F: RichField>(trace: &mut [Vec<F>], col: usize, row: usize, value: u64, bits: usize) {
+    for z in 0..bits {
+        trace[col + z][row] = F::from_bool(value >> z & 1 == 1);
+    }
+}
+
+/// Fill the 7 rows of a compression starting at `row`
+fn fill_compression_rows<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    compression: &Compression,
+) {
+    let mut state = compression.initial_state();
+    let (counter_lo, counter_hi) = (state[12], state[13]);
+    let mut message = compression.block;
+
+    for r in 0..NUM_ROUNDS {
+        let row = row + r;
+        trace[COL_STEP_START + r][row] = F::ONE;
+        for (i, &word) in compression.cv.iter().enumerate() {
+            trace[COL_CV_START + i][row] = F::from_canonical_u32(word);
+        }
+        trace[COL_COUNTER_LO][row] = F::from_canonical_u32(counter_lo);
+        trace[COL_COUNTER_HI][row] = F::from_canonical_u32(counter_hi);
+        trace[COL_BLOCK_LEN][row] = F::from_canonical_u32(compression.block_len);
+        trace[COL_FLAGS][row] = F::from_canonical_u32(compression.flags);
+
+        for (i, &word) in message.iter().enumerate() {
+            fill_bits(trace, col_message_bits(i), row, word.into(), 32);
+        }
+        for (i, &word) in state.iter().enumerate() {
+            fill_bits(trace, col_state_bits(i), row, word.into(), 32);
+        }
+        for (i, values) in round(&mut state, &message).into_iter().enumerate() {
+            let col = col_g(i);
+            fill_bits(trace, col + G_A1, row, values.a1, 32);
+            fill_bits(trace, col + G_A1_CARRY, row, values.a1 >> 32, 2);
+            fill_bits(trace, col + G_C1, row, values.c1, 32);
+            fill_bits(trace, col + G_C1_CARRY, row, values.c1 >> 32, 1);
+            fill_bits(trace, col + G_A2, row, values.a2, 32);
+            fill_bits(trace, col + G_A2_CARRY, row, values.a2 >> 32, 2);
+            fill_bits(trace, col + G_C2, row, values.c2, 32);
+            fill_bits(trace, col + G_C2_CARRY, row, values.c2 >> 32, 1);
+            fill_bits(trace, col + G_B2, row, values.b2.into(), 32);
+            fill_bits(trace, col + G_D2, row, values.d2.into(), 32);
+        }
+        message = permute(&message);
+    }
+
+    let row = row + NUM_ROUNDS - 1;
+    for (i, word) in compression.output_cv().into_iter().enumerate() {
+        trace[COL_OUTPUT_START + i][row] = F::from_canonical_u32(word);
+    }
+}
+
+/// Function to generate the BLAKE3 compression trace: 7 rows per
+/// compression, padded with all-zero rows to a power of 2.
+#[must_use]
+pub fn generate_blake3_trace<F: RichField>(compressions: &[Compression]) -> [Vec<F>; NUM_COLS] {
+    let trace_len = (compressions.len() * NUM_ROUNDS).next_power_of_two();
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; NUM_COLS];
+
+    for (i, compression) in compressions.iter().enumerate() {
+        fill_compression_rows(&mut trace, i * NUM_ROUNDS, compression);
+    }
+
+    trace.try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::{COL_OUTPUT_START, COL_STEP_START};
+    use crate::constants::{CV_WORDS, NUM_ROUNDS};
+    use crate::generation::{generate_blake3_trace, Compression};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, PrimeField64};
+
+    type F = GoldilocksField;
+
+    fn pseudo_random_compression(seed: u32) -> Compression {
+        let word = |i: usize| {
+            (seed + u32::try_from(i).unwrap())
+                .wrapping_mul(0x9E37_79B9)
+                .rotate_left(11)
+        };
+        Compression {
+            cv: std::array::from_fn(word),
+            block: std::array::from_fn(|i| word(i + 8)),
+            counter: u64::from(word(24)) << 32 | u64::from(word(25)),
+            block_len: word(26) % 65,
+            flags: word(27) % 16,
+        }
+    }
+
+    #[test]
+    fn generate_blake3_trace_outputs() {
+        let compressions: Vec<_> = (0..3).map(pseudo_random_compression).collect();
+        let trace = generate_blake3_trace::<F>(&compressions);
+        assert_eq!(trace[0].len(), 32);
+
+        for (i, compression) in compressions.iter().enumerate() {
+            let row = i * NUM_ROUNDS + NUM_ROUNDS - 1;
+            assert_eq!(trace[COL_STEP_START + NUM_ROUNDS - 1][row], F::ONE);
+            let output: Vec<u32> = (0..CV_WORDS)
+                .map(|k| {
+                    u32::try_from(trace[COL_OUTPUT_START + k][row].to_canonical_u64()).unwrap()
+                })
+                .collect();
+            assert_eq!(output, compression.output_cv());
+        }
+    }
+}
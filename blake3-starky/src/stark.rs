@@ -0,0 +1,276 @@
+use crate::columns::{
+    col_g, col_message_bits, col_state_bits, col_state_bits_before_g, BIT_COLS, COL_BLOCK_LEN,
+    COL_COUNTER_HI, COL_COUNTER_LO, COL_CV_START, COL_FLAGS, COL_OUTPUT_START, COL_STEP_START,
+    G_A1, G_A1_CARRY, G_A2, G_A2_CARRY, G_B2, G_C1, G_C1_CARRY, G_C2, G_C2_CARRY, G_D2, NUM_COLS,
+};
+use crate::constants::{CV_WORDS, G_STATE_WORDS, IV, MSG_PERMUTATION, NUM_G, NUM_ROUNDS};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+// degree: 2
+fn xor<P: PackedField>(a: P, b: P) -> P {
+    a + b - a * b * P::Scalar::TWO
+}
+
+/// Recompose the little-endian bits `get_bit(0)..get_bit(31)` into a word
+fn word<P: PackedField>(get_bit: impl Fn(usize) -> P) -> P {
+    (0..32)
+        .rev()
+        .fold(P::ZEROS, |acc, z| acc * P::Scalar::TWO + get_bit(z))
+}
+
+/// The word whose bits start at column `col`
+fn word_at<P: PackedField>(lv: &[P], col: usize) -> P {
+    word(|z| lv[col + z])
+}
+
+/// The carry whose `bits` bits start at column `col`, scaled by 2^32
+fn carry_at<P: PackedField>(lv: &[P], col: usize, bits: usize) -> P {
+    let carry = (0..bits)
+        .rev()
+        .fold(P::ZEROS, |acc, i| acc * P::Scalar::TWO + lv[col + i]);
+    carry * P::Scalar::from_canonical_u64(1 << 32)
+}
+
+/// Constraints of G function `g` on the state words whose bits start at
+/// `a`, `b`, `c` and `d` and the message words whose bits start at `mx` and
+/// `my`. The intermediate `d` and `b` are xors of bits and have no columns of
+/// their own, and the outputs are compared as words: both sides are made of
+/// boolean bits, so this compares them bitwise.
+#[allow(clippy::many_single_char_names)]
+fn eval_g<P: PackedField>(
+    lv: &[P],
+    g: usize,
+    [a, b, c, d]: [usize; 4],
+    [mx, my]: [usize; 2],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let col = col_g(g);
+    let bit = |start: usize, z: usize| lv[start + z];
+    let rotr = |z: usize, n: usize| (z + n) % 32;
+
+    // a1 = a + b + mx
+    yield_constr.constraint(
+        word_at(lv, col + G_A1) + carry_at(lv, col + G_A1_CARRY, 2)
+            - word_at(lv, a)
+            - word_at(lv, b)
+            - word_at(lv, mx),
+    );
+    // c1 = c + d1 with d1 = rotr(d ^ a1, 16), of degree 2
+    let d1 = |z| xor(bit(d, rotr(z, 16)), bit(col + G_A1, rotr(z, 16)));
+    yield_constr.constraint(
+        word_at(lv, col + G_C1) + carry_at(lv, col + G_C1_CARRY, 1) - word_at(lv, c) - word(d1),
+    );
+    // a2 = a1 + b1 + my with b1 = rotr(b ^ c1, 12), of degree 2
+    let b1 = |z| xor(bit(b, rotr(z, 12)), bit(col + G_C1, rotr(z, 12)));
+    yield_constr.constraint(
+        word_at(lv, col + G_A2) + carry_at(lv, col + G_A2_CARRY, 2)
+            - word_at(lv, col + G_A1)
+            - word(b1)
+            - word_at(lv, my),
+    );
+    // d2 = rotr(d1 ^ a2, 8)
+    yield_constr.constraint(
+        word_at(lv, col + G_D2) - word(|z| xor(d1(rotr(z, 8)), bit(col + G_A2, rotr(z, 8)))),
+    );
+    // c2 = c1 + d2
+    yield_constr.constraint(
+        word_at(lv, col + G_C2) + carry_at(lv, col + G_C2_CARRY, 1)
+            - word_at(lv, col + G_C1)
+            - word_at(lv, col + G_D2),
+    );
+    // b2 = rotr(b1 ^ c2, 7)
+    yield_constr.constraint(
+        word_at(lv, col + G_B2) - word(|z| xor(b1(rotr(z, 7)), bit(col + G_C2, rotr(z, 7)))),
+    );
+}
+
+/// Constraints of the round flags and of the inputs of the compression
+fn eval_rounds_and_inputs<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+) -> P {
+    // the round flags are one-hot or all zero on padding rows, start with
+    // round 0 and step through the rounds in order
+    let mut flag_sum = P::ZEROS;
+    for round in 0..NUM_ROUNDS {
+        let flag = lv[COL_STEP_START + round];
+        yield_constr.constraint(flag * (flag - P::ONES));
+        flag_sum += flag;
+        if round > 0 {
+            yield_constr.constraint_first_row(flag);
+        }
+        if round < NUM_ROUNDS - 1 {
+            yield_constr.constraint_transition(nv[COL_STEP_START + round + 1] - flag);
+        }
+    }
+    yield_constr.constraint(flag_sum * (flag_sum - P::ONES));
+    let not_last_round = flag_sum - lv[COL_STEP_START + NUM_ROUNDS - 1];
+
+    // the inputs are carried to the last round, and the first round starts
+    // from the chaining value, the IV, the counter, the block length and the
+    // flags
+    let inputs: Vec<usize> = (COL_CV_START..COL_CV_START + CV_WORDS)
+        .chain([COL_COUNTER_LO, COL_COUNTER_HI, COL_BLOCK_LEN, COL_FLAGS])
+        .collect();
+    for &col in &inputs {
+        yield_constr.constraint_transition(not_last_round * (nv[col] - lv[col]));
+    }
+    let initial_state = inputs[..CV_WORDS]
+        .iter()
+        .map(|&col| lv[col])
+        .chain(
+            IV[..4]
+                .iter()
+                .map(|&iv| P::from(P::Scalar::from_canonical_u32(iv))),
+        )
+        .chain(inputs[CV_WORDS..].iter().map(|&col| lv[col]));
+    let step0 = lv[COL_STEP_START];
+    for (i, value) in initial_state.enumerate() {
+        yield_constr.constraint(step0 * (word_at(lv, col_state_bits(i)) - value));
+    }
+
+    not_last_round
+}
+
+/// Constraints of the BLAKE3 compression table laid out as in `columns.rs`
+fn eval_blake3_compression<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    for &bit in &lv[BIT_COLS] {
+        yield_constr.constraint(bit * (bit - P::ONES));
+    }
+    let not_last_round = eval_rounds_and_inputs(lv, nv, yield_constr);
+
+    // the G functions of the round, on the state left by the previous ones
+    for (g, words) in G_STATE_WORDS.into_iter().enumerate() {
+        let state = col_state_bits_before_g(g);
+        let [a, b, c, d] = words.map(|i| state[i]);
+        let message = [col_message_bits(2 * g), col_message_bits(2 * g + 1)];
+        eval_g(lv, g, [a, b, c, d], message, yield_constr);
+    }
+
+    // the next round starts from the state at the end of this round, with
+    // the message words permuted
+    let final_state = col_state_bits_before_g(NUM_G);
+    for (i, &bits) in final_state.iter().enumerate() {
+        yield_constr.constraint_transition(
+            not_last_round * (word_at(nv, col_state_bits(i)) - word_at(lv, bits)),
+        );
+    }
+    for (i, j) in MSG_PERMUTATION.into_iter().enumerate() {
+        yield_constr.constraint_transition(
+            not_last_round * (word_at(nv, col_message_bits(i)) - word_at(lv, col_message_bits(j))),
+        );
+    }
+
+    // the output chaining value xors the two halves of the final state
+    let last_round = lv[COL_STEP_START + NUM_ROUNDS - 1];
+    for i in 0..CV_WORDS {
+        let (low, high) = (final_state[i], final_state[i + CV_WORDS]);
+        let output = word(|z| xor(lv[low + z], lv[high + z]));
+        yield_constr.constraint(last_round * (lv[COL_OUTPUT_START + i] - output));
+    }
+}
+
+/// The BLAKE3 compression table, one row per round and 7 rows per
+/// compression.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Blake3Stark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Blake3Stark<F, D> {
+    const COLUMNS: usize = NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_blake3_compression(vars.local_values, vars.next_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::generate_blake3_trace;
+    use crate::stark::{trace_to_poly_values, Blake3Stark};
+    use crate::tree::compressions;
+    use anyhow::Result;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Blake3Stark<F, D>;
+
+    #[test]
+    fn blake3_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+
+        // two chunks and their parent
+        let input: Vec<u8> = (0..1100u32).map(|i| i.to_le_bytes()[0]).collect();
+        let compressions = compressions(&input);
+
+        let stark = S::default();
+        let trace = generate_blake3_trace(&compressions);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn blake3_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
@@ -0,0 +1,144 @@
+//! The BLAKE3 tree mode: the input is split into chunks of 1024 bytes, each
+//! chunk is compressed block by block, and the chaining values of the chunks
+//! are merged pairwise by parent nodes up to the root. The left subtree of a
+//! node always holds the largest power of two of chunks that leaves at least
+//! one chunk to the right.
+
+use crate::constants::{
+    BLOCK_LEN, BLOCK_WORDS, CHUNK_END, CHUNK_LEN, CHUNK_START, CV_WORDS, IV, PARENT, ROOT,
+};
+use crate::generation::Compression;
+
+/// A block of up to 64 bytes as little-endian words, padded with zeros
+fn block_words(block: &[u8]) -> [u32; BLOCK_WORDS] {
+    let mut bytes = [0; BLOCK_LEN];
+    bytes[..block.len()].copy_from_slice(block);
+    std::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+/// Push the compressions of a chunk and return its chaining value
+fn chunk(
+    chunk: &[u8],
+    chunk_counter: u64,
+    is_root: bool,
+    compressions: &mut Vec<Compression>,
+) -> [u32; CV_WORDS] {
+    // the empty input is a single chunk with a single empty block
+    let blocks: Vec<&[u8]> = if chunk.is_empty() {
+        vec![&[]]
+    } else {
+        chunk.chunks(BLOCK_LEN).collect()
+    };
+
+    let mut cv = IV;
+    for (i, block) in blocks.iter().enumerate() {
+        let mut flags = 0;
+        if i == 0 {
+            flags |= CHUNK_START;
+        }
+        if i == blocks.len() - 1 {
+            flags |= CHUNK_END;
+            if is_root {
+                flags |= ROOT;
+            }
+        }
+        let compression = Compression {
+            cv,
+            block: block_words(block),
+            counter: chunk_counter,
+            block_len: u32::try_from(block.len()).unwrap(),
+            flags,
+        };
+        cv = compression.output_cv();
+        compressions.push(compression);
+    }
+    cv
+}
+
+/// Push the compressions of the subtree over `chunks`, the first of which is
+/// chunk number `first_chunk` of the input, and return its chaining value
+fn subtree(
+    chunks: &[&[u8]],
+    first_chunk: u64,
+    is_root: bool,
+    compressions: &mut Vec<Compression>,
+) -> [u32; CV_WORDS] {
+    if let [single] = chunks {
+        return chunk(single, first_chunk, is_root, compressions);
+    }
+
+    let left_len = 1 << (chunks.len() - 1).ilog2();
+    let left = subtree(&chunks[..left_len], first_chunk, false, compressions);
+    let right = subtree(
+        &chunks[left_len..],
+        first_chunk + left_len as u64,
+        false,
+        compressions,
+    );
+
+    let mut block = [0; BLOCK_WORDS];
+    block[..CV_WORDS].copy_from_slice(&left);
+    block[CV_WORDS..].copy_from_slice(&right);
+    let compression = Compression {
+        cv: IV,
+        block,
+        counter: 0,
+        block_len: u32::try_from(BLOCK_LEN).unwrap(),
+        flags: if is_root { PARENT | ROOT } else { PARENT },
+    };
+    compressions.push(compression);
+    compression.output_cv()
+}
+
+/// The compressions of hashing `input`, with every chunk before its parent
+/// and the root compression last
+#[must_use]
+pub fn compressions(input: &[u8]) -> Vec<Compression> {
+    let chunks: Vec<&[u8]> = if input.is_empty() {
+        vec![&[]]
+    } else {
+        input.chunks(CHUNK_LEN).collect()
+    };
+    let mut compressions = Vec::new();
+    subtree(&chunks, 0, true, &mut compressions);
+    compressions
+}
+
+/// The 32-byte BLAKE3 hash of `input`
+#[must_use]
+pub fn hash(input: &[u8]) -> [u8; 32] {
+    let root = compressions(input).pop().unwrap();
+    let mut digest = [0; 32];
+    for (bytes, word) in digest.chunks_mut(4).zip(root.output_cv()) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::{CHUNK_END, CHUNK_START, PARENT, ROOT};
+    use crate::tree::compressions;
+
+    #[test]
+    fn tree_shape() {
+        // a single block is both chunk start, chunk end and root
+        let single = compressions(b"abc");
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].flags, CHUNK_START | CHUNK_END | ROOT);
+        assert_eq!(single[0].block_len, 3);
+
+        // three chunks: ((c0, c1), c2)
+        let three = compressions(&[0; 2049]);
+        let flags: Vec<u32> = three.iter().map(|c| c.flags).collect();
+        assert_eq!(three.len(), 16 + 16 + 1 + 2);
+        assert_eq!(flags[0], CHUNK_START);
+        assert_eq!(flags[15], CHUNK_END);
+        assert_eq!(three[16].counter, 1);
+        assert_eq!(flags[32], PARENT);
+        assert_eq!(flags[33], CHUNK_START | CHUNK_END);
+        assert_eq!(three[33].counter, 2);
+        assert_eq!(three[33].block_len, 1);
+        assert_eq!(flags[34], PARENT | ROOT);
+    }
+}
@@ -0,0 +1,87 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use griffin_starky::generation::{generate_griffin_trace, Row};
+use griffin_starky::stark::{trace_to_poly_values, GriffinStark};
+use plonky2::field::types::Sample;
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::util::timing::TimingTree;
+use poseidon2_starky::generation::generate_poseidon2_trace;
+use poseidon2_starky::stark::Poseidon2Stark;
+use starky::config::StarkConfig;
+use starky::prover::prove;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// Prove the same number of permutations with Griffin and with Poseidon2.
+/// Both tables have degree 7 and share the same config.
+fn bench_griffin_vs_poseidon2(c: &mut Criterion) {
+    let num_permutations = 1 << 10;
+    let mut group = c.benchmark_group("permutations");
+
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.cap_height = 0;
+    config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+    let step_rows: Vec<Row<F>> = (0..num_permutations)
+        .map(|_| Row {
+            preimage: F::rand_array(),
+        })
+        .collect();
+    let stark = GriffinStark::<F, D>::default();
+    let trace_poly_values = trace_to_poly_values(generate_griffin_trace(&step_rows));
+    let mut timing = TimingTree::default();
+    group.bench_function(BenchmarkId::new("griffin_starky", num_permutations), |b| {
+        b.iter_batched(
+            || trace_poly_values.clone(),
+            |trace_poly_values| {
+                prove::<F, C, GriffinStark<F, D>, D>(
+                    stark,
+                    &config,
+                    trace_poly_values,
+                    [],
+                    &mut timing,
+                )
+                .unwrap();
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    let step_rows: Vec<poseidon2_starky::generation::Row<F>> = (0..num_permutations)
+        .map(|_| poseidon2_starky::generation::Row {
+            preimage: F::rand_array(),
+        })
+        .collect();
+    let stark = Poseidon2Stark::<F, D>::default();
+    let trace_poly_values =
+        poseidon2_starky::stark::trace_to_poly_values(generate_poseidon2_trace(&step_rows));
+    group.bench_function(
+        BenchmarkId::new("poseidon2_starky", num_permutations),
+        |b| {
+            b.iter_batched(
+                || trace_poly_values.clone(),
+                |trace_poly_values| {
+                    prove::<F, C, Poseidon2Stark<F, D>, D>(
+                        stark.clone(),
+                        &config,
+                        trace_poly_values,
+                        [],
+                        &mut timing,
+                    )
+                    .unwrap();
+                },
+                criterion::BatchSize::SmallInput,
+            );
+        },
+    );
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().measurement_time(std::time::Duration::from_secs(20)).sample_size(50);
+    targets = bench_griffin_vs_poseidon2
+}
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+use crate::constants::{DIGEST_SIZE, NUM_ROUNDS, STATE_SIZE};
+
+/// The starting point of the column input
+pub(crate) const COL_INPUT_START: usize = 0;
+
+/// The starting point of the state after the non-linear layer of round `r`
+#[must_use]
+pub const fn col_round_state_start(r: usize) -> usize {
+    COL_INPUT_START + STATE_SIZE + STATE_SIZE * r
+}
+
+/// The starting point of the column output, the state after the linear
+/// layer of the last round
+pub(crate) const COL_OUTPUT_START: usize = col_round_state_start(NUM_ROUNDS); // 64
+
+/// The starting point of the Jive compression of the input
+pub(crate) const COL_DIGEST_START: usize = COL_OUTPUT_START + STATE_SIZE; // 72
+
+/// The total number of columns
+pub const NUM_COLS: usize = COL_DIGEST_START + DIGEST_SIZE; // 76
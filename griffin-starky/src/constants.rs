@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+/// The size of the state: a rate of 4 and a capacity of 4 elements
+pub const STATE_SIZE: usize = 8;
+
+/// The rate is in elements 0..4 and the capacity in elements 4..8
+pub const RATE_RANGE: Range<usize> = 0..4;
+
+/// The first capacity element, set to 1 when the input is not a multiple of
+/// the rate
+pub const CAPACITY_START: usize = 4;
+
+/// The number of elements of a digest; Jive compresses two digests into one
+pub const DIGEST_SIZE: usize = 4;
+
+/// The digest is the rate
+pub const DIGEST_RANGE: Range<usize> = 0..DIGEST_SIZE;
+
+/// The number of rounds
+pub const NUM_ROUNDS: usize = 7;
+
+/// The degree `d` of the power maps
+pub const DEGREE: u64 = 7;
+
+/// The inverse power map exponent, `1 / 7 mod (p - 1)`
+pub const INV_DEGREE: u64 = 10_540_996_611_094_048_183;
+
+/// The first row of the circulant MDS matrix
+pub const MDS: [u64; STATE_SIZE] = [23, 8, 13, 10, 7, 6, 21, 8];
+
+/// The round constants added after the linear layer of every round but the
+/// last one
+pub const ARK: [[u64; STATE_SIZE]; NUM_ROUNDS - 1] = [
+    [
+        9_692_712_401_870_945_221,
+        7_618_007_584_389_424_767,
+        5_248_032_629_877_155_397,
+        3_331_263_627_507_477_698,
+        860_199_187_432_911_550,
+        10_360_526_140_302_824_670,
+        5_014_858_186_237_911_359,
+        4_161_019_260_461_204_222,
+    ],
+    [
+        2_649_891_723_669_882_704,
+        15_035_697_086_627_576_083,
+        14_140_087_988_207_356_741,
+        357_780_579_603_925_138,
+        273_712_483_418_536_090,
+        348_552_596_175_072_640,
+        11_116_926_243_792_475_367,
+        2_475_357_435_469_270_767,
+    ],
+    [
+        9_513_699_262_061_178_678,
+        11_735_848_814_479_196_467,
+        12_888_397_717_055_708_631,
+        15_194_236_579_723_079_985,
+        14_734_897_209_064_082_180,
+        9_352_307_275_330_595_094,
+        2_536_293_522_055_086_772,
+        1_551_701_365_424_645_656,
+    ],
+    [
+        17_180_574_791_560_887_028,
+        10_973_179_380_721_509_279,
+        15_451_549_433_162_538_377,
+        11_230_437_049_044_589_131,
+        14_416_448_585_168_854_586,
+        13_520_950_449_774_622_599,
+        14_110_026_253_178_816_443,
+        7_562_226_163_074_683_487,
+    ],
+    [
+        15_625_584_526_294_513_461,
+        12_868_717_640_985_007_163,
+        5_045_176_603_305_276_542,
+        6_821_445_918_259_551_845,
+        15_049_718_154_108_882_541,
+        676_731_535_772_312_475,
+        14_779_363_889_066_167_393,
+        17_108_914_943_169_063_073,
+    ],
+    [
+        17_529_530_613_938_644_968,
+        13_801_329_800_663_243_071,
+        12_666_329_335_088_484_031,
+        10_289_051_774_796_875_319,
+        46_795_987_162_557_096,
+        8_590_445_841_426_612_555,
+        7_174_111_149_249_058_757,
+        5_820_086_182_616_968_416,
+    ],
+];
+
+/// The constants `alpha_i` of the Horst layer for elements 2..8
+pub const HORST_ALPHA: [u64; STATE_SIZE - 2] = [
+    6_303_398_607_380_181_568,
+    12_606_797_214_760_363_136,
+    463_451_752_725_960_383,
+    6_766_850_360_106_141_951,
+    13_070_248_967_486_323_519,
+    926_903_505_451_920_766,
+];
+
+/// The constants `beta_i` of the Horst layer for elements 2..8
+pub const HORST_BETA: [u64; STATE_SIZE - 2] = [
+    5_698_628_486_727_258_041,
+    4_347_769_877_494_447_843,
+    14_394_168_241_716_153_727,
+    17_391_079_509_977_791_372,
+    13_338_503_682_279_360_778,
+    2_236_440_758_620_861_945,
+];
@@ -0,0 +1,249 @@
+use crate::columns::{
+    col_round_state_start, COL_DIGEST_START, COL_INPUT_START, COL_OUTPUT_START, NUM_COLS,
+};
+use crate::constants::{
+    ARK, CAPACITY_START, DEGREE, DIGEST_RANGE, DIGEST_SIZE, HORST_ALPHA, HORST_BETA, INV_DEGREE,
+    MDS, NUM_ROUNDS, RATE_RANGE, STATE_SIZE,
+};
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+
+// Represent a row of the preimage
+#[derive(Debug, Clone, Default)]
+pub struct Row<Field: RichField> {
+    pub preimage: [Field; STATE_SIZE],
+}
+
+/// Pad the trace to a power of 2.
+#[must_use]
+fn pad_trace<F: RichField>(mut trace: Vec<Vec<F>>) -> Vec<Vec<F>> {
+    let ext_trace_len = trace[0].len().next_power_of_two();
+
+    // All columns have their last value duplicated.
+    for row in &mut trace {
+        row.resize(ext_trace_len, *row.last().unwrap());
+    }
+
+    trace
+}
+
+/// Multiply the state by the circulant MDS matrix
+pub(crate) fn mds<F: Field>(state: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        (0..STATE_SIZE).fold(F::ZERO, |acc, j| {
+            acc + state[j] * F::from_canonical_u64(MDS[(j + STATE_SIZE - i) % STATE_SIZE])
+        })
+    })
+}
+
+/// The non-linear layer: `x^(1/d)` on element 0, `x^d` on element 1 and the
+/// Horst layer `y_i = x_i * (L_i^2 + alpha_i * L_i + beta_i)` on the others,
+/// where `L_i = (i - 1) * y_0 + y_1 + y_(i - 1)` and `y_1` does not count
+/// for `L_2`
+fn non_linear_layer<F: Field>(state: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    let mut out = *state;
+    out[0] = state[0].exp_u64(INV_DEGREE);
+    out[1] = state[1].exp_u64(DEGREE);
+    for i in 2..STATE_SIZE {
+        let l = F::from_canonical_usize(i - 1) * out[0]
+            + out[1]
+            + if i == 2 { F::ZERO } else { out[i - 1] };
+        out[i] = state[i]
+            * (l.square()
+                + F::from_canonical_u64(HORST_ALPHA[i - 2]) * l
+                + F::from_canonical_u64(HORST_BETA[i - 2]));
+    }
+    out
+}
+
+/// The states after the non-linear layer of each round
+fn generate_round_states<F: RichField>(preimage: &[F; STATE_SIZE]) -> Vec<[F; STATE_SIZE]> {
+    let mut state = *preimage;
+    (0..NUM_ROUNDS)
+        .map(|r| {
+            let round_state = non_linear_layer(&state);
+            state = mds(&round_state);
+            // no round constants in the last round
+            if let Some(round_constants) = ARK.get(r) {
+                for (s, &c) in state.iter_mut().zip(round_constants) {
+                    *s += F::from_canonical_u64(c);
+                }
+            }
+            round_state
+        })
+        .collect()
+}
+
+/// The Griffin permutation: 7 rounds of the non-linear layer, the MDS matrix
+/// and the round constants, without constants in the last round
+#[must_use]
+pub fn permute<F: RichField>(preimage: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    mds(generate_round_states(preimage).last().unwrap())
+}
+
+/// The inputs of the permutations made by [`hash_elements`]: the first
+/// capacity element is 1 unless the number of elements is a multiple of the
+/// rate, the elements are added to the rate and the last block is padded
+/// with a 1 and zeros.
+#[must_use]
+pub fn hash_elements_rows<F: RichField>(elements: &[F]) -> Vec<Row<F>> {
+    let mut state = [F::ZERO; STATE_SIZE];
+    if elements.len() % RATE_RANGE.len() != 0 {
+        state[CAPACITY_START] = F::ONE;
+    }
+
+    let mut rows = Vec::new();
+    for block in elements.chunks(RATE_RANGE.len()) {
+        let rate = &mut state[RATE_RANGE];
+        for (s, &e) in rate.iter_mut().zip(block) {
+            *s += e;
+        }
+        if block.len() < rate.len() {
+            rate[block.len()] = F::ONE;
+            rate[block.len() + 1..].fill(F::ZERO);
+        }
+        rows.push(Row { preimage: state });
+        state = permute(&state);
+    }
+    rows
+}
+
+/// Hash field elements with the Griffin sponge, as in Winterfell's
+/// `GriffinJive64_256::hash_elements`
+#[must_use]
+pub fn hash_elements<F: RichField>(elements: &[F]) -> [F; DIGEST_SIZE] {
+    let state = hash_elements_rows(elements)
+        .last()
+        .map_or([F::ZERO; STATE_SIZE], |row| permute(&row.preimage));
+    state[DIGEST_RANGE].try_into().unwrap()
+}
+
+/// The Jive mode: the sum of the two halves of the input and of its image
+fn jive<F: Field>(input: &[F; STATE_SIZE], output: &[F; STATE_SIZE]) -> [F; DIGEST_SIZE] {
+    std::array::from_fn(|i| input[i] + input[DIGEST_SIZE + i] + output[i] + output[DIGEST_SIZE + i])
+}
+
+/// Compress two digests into one with Griffin in the Jive mode, as in
+/// Winterfell's `GriffinJive64_256::merge`
+#[must_use]
+pub fn merge<F: RichField>(left: &[F; DIGEST_SIZE], right: &[F; DIGEST_SIZE]) -> [F; DIGEST_SIZE] {
+    let preimage = std::array::from_fn(|i| {
+        if i < DIGEST_SIZE {
+            left[i]
+        } else {
+            right[i - DIGEST_SIZE]
+        }
+    });
+    jive(&preimage, &permute(&preimage))
+}
+
+/// Write the permutation columns of a single row into a column-major trace
+fn fill_permutation_row<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    preimage: &[F; STATE_SIZE],
+) {
+    for (j, &value) in preimage.iter().enumerate() {
+        trace[COL_INPUT_START + j][row] = value;
+    }
+    let round_states = generate_round_states(preimage);
+    for (r, state) in round_states.iter().enumerate() {
+        for (j, &value) in state.iter().enumerate() {
+            trace[col_round_state_start(r) + j][row] = value;
+        }
+    }
+    let output = mds(round_states.last().unwrap());
+    for (j, &value) in output.iter().enumerate() {
+        trace[COL_OUTPUT_START + j][row] = value;
+    }
+    for (j, value) in jive(preimage, &output).into_iter().enumerate() {
+        trace[COL_DIGEST_START + j][row] = value;
+    }
+}
+
+/// Function to generate the Griffin trace: one row per permutation, padded
+/// to a power of 2 by repeating the last row.
+#[must_use]
+pub fn generate_griffin_trace<F: RichField>(step_rows: &[Row<F>]) -> [Vec<F>; NUM_COLS] {
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; step_rows.len()]; NUM_COLS];
+    for (i, row) in step_rows.iter().enumerate() {
+        fill_permutation_row(&mut trace, i, &row.preimage);
+    }
+
+    pad_trace(trace).try_into().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::columns::{COL_DIGEST_START, COL_OUTPUT_START};
+    use crate::constants::{DEGREE, DIGEST_SIZE, INV_DEGREE, STATE_SIZE};
+    use crate::generation::{generate_griffin_trace, hash_elements, merge, permute, Row};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn inverse_power_map() {
+        let x = F::rand();
+        assert_eq!(x.exp_u64(DEGREE).exp_u64(INV_DEGREE), x);
+        assert_eq!(x.exp_u64(INV_DEGREE).exp_u64(DEGREE), x);
+    }
+
+    /// The permutation and sponge vectors of Winterfell's `GriffinJive64_256`,
+    /// which come from the reference Sage implementation
+    #[test]
+    fn reference_vectors() {
+        let input: [F; STATE_SIZE] = std::array::from_fn(F::from_canonical_usize);
+        let expected_permutation: [u64; STATE_SIZE] = [
+            5_100_889_723_013_202_324,
+            6_905_683_344_086_677_437,
+            8_236_358_786_066_512_460,
+            1_729_367_862_961_866_374,
+            11_501_420_603_552_582_981,
+            15_040_992_847_148_175_954,
+            10_400_407_304_634_768_298,
+            1_197_713_229_800_045_418,
+        ];
+        let expected_hash: [u64; DIGEST_SIZE] = [
+            16_887_612_651_479_285_699,
+            16_469_590_207_124_000_227,
+            11_134_472_952_466_778_260,
+            15_455_301_814_830_509_354,
+        ];
+
+        assert_eq!(
+            permute(&input),
+            expected_permutation.map(F::from_canonical_u64)
+        );
+        assert_eq!(
+            hash_elements(&input),
+            expected_hash.map(F::from_canonical_u64)
+        );
+    }
+
+    #[test]
+    fn generate_griffin_trace_outputs() {
+        let step_rows: Vec<Row<F>> = (0..5)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let trace = generate_griffin_trace(&step_rows);
+        assert_eq!(trace[0].len(), 8);
+        for (i, row) in step_rows.iter().enumerate() {
+            let expected = permute(&row.preimage);
+            for j in 0..STATE_SIZE {
+                assert_eq!(trace[COL_OUTPUT_START + j][i], expected[j]);
+            }
+            let (left, right) = row.preimage.split_at(DIGEST_SIZE);
+            let digest = merge(left.try_into().unwrap(), right.try_into().unwrap());
+            for j in 0..DIGEST_SIZE {
+                assert_eq!(trace[COL_DIGEST_START + j][i], digest[j]);
+            }
+        }
+        // the padding rows repeat the last row
+        assert_eq!(trace[COL_OUTPUT_START][7], trace[COL_OUTPUT_START][4]);
+    }
+}
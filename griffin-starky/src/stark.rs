@@ -0,0 +1,192 @@
+use crate::columns::{
+    col_round_state_start, COL_DIGEST_START, COL_INPUT_START, COL_OUTPUT_START, NUM_COLS,
+};
+use crate::constants::{
+    ARK, DEGREE, DIGEST_SIZE, HORST_ALPHA, HORST_BETA, MDS, NUM_ROUNDS, STATE_SIZE,
+};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+// linear layer (degree = 1)
+fn mds_constraints<P: PackedField>(state: &[P; STATE_SIZE]) -> [P; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        (0..STATE_SIZE).fold(P::ZEROS, |acc, j| {
+            acc + state[j] * P::Scalar::from_canonical_u64(MDS[(j + STATE_SIZE - i) % STATE_SIZE])
+        })
+    })
+}
+
+// degree: 1
+fn add_rc_constraints<P: PackedField>(
+    state: &[P; STATE_SIZE],
+    round_constants: &[u64; STATE_SIZE],
+) -> [P; STATE_SIZE] {
+    std::array::from_fn(|i| state[i] + P::Scalar::from_canonical_u64(round_constants[i]))
+}
+
+// degree: 7
+fn power_map_constraints<P: PackedField>(x: P) -> P {
+    (0..DEGREE).fold(P::ONES, |acc, _| acc * x)
+}
+
+/// Constraints of the non-linear layer from `input` to `output`. The inverse
+/// power map `x^(1/d)` is checked through its output `y` as `y^d = x`.
+fn non_linear_constraints<P: PackedField>(
+    input: &[P; STATE_SIZE],
+    output: &[P; STATE_SIZE],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    // degree: 7
+    yield_constr.constraint(power_map_constraints(output[0]) - input[0]);
+    yield_constr.constraint(output[1] - power_map_constraints(input[1]));
+
+    // Horst, degree: 3
+    for i in 2..STATE_SIZE {
+        let mut l = output[0] * P::Scalar::from_canonical_usize(i - 1) + output[1];
+        if i > 2 {
+            l += output[i - 1];
+        }
+        let horst = l.square()
+            + l * P::Scalar::from_canonical_u64(HORST_ALPHA[i - 2])
+            + P::Scalar::from_canonical_u64(HORST_BETA[i - 2]);
+        yield_constr.constraint(output[i] - input[i] * horst);
+    }
+}
+
+fn state_at<P: PackedField>(lv: &[P], start: usize) -> [P; STATE_SIZE] {
+    lv[start..start + STATE_SIZE].try_into().unwrap()
+}
+
+/// Constraints of a single Griffin permutation and its Jive compression laid
+/// out as in `columns.rs`, starting at the first column of `lv`
+pub(crate) fn eval_permutation<P: PackedField>(lv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    let input = state_at(lv, COL_INPUT_START);
+    let mut state = input;
+
+    for r in 0..NUM_ROUNDS {
+        let round_state = state_at(lv, col_round_state_start(r));
+        non_linear_constraints(&state, &round_state, yield_constr);
+        state = mds_constraints(&round_state);
+        // no round constants in the last round
+        if let Some(round_constants) = ARK.get(r) {
+            state = add_rc_constraints(&state, round_constants);
+        }
+    }
+
+    let output = state_at(lv, COL_OUTPUT_START);
+    for (&value, &expected) in output.iter().zip(&state) {
+        yield_constr.constraint(value - expected);
+    }
+
+    // Jive: the digest is the sum of the halves of the input and the output
+    for i in 0..DIGEST_SIZE {
+        yield_constr.constraint(
+            lv[COL_DIGEST_START + i]
+                - input[i]
+                - input[DIGEST_SIZE + i]
+                - output[i]
+                - output[DIGEST_SIZE + i],
+        );
+    }
+}
+
+/// The Griffin permutation table, one row per permutation.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct GriffinStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for GriffinStark<F, D> {
+    const COLUMNS: usize = NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_permutation(vars.local_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::{generate_griffin_trace, Row};
+    use crate::stark::{trace_to_poly_values, GriffinStark};
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = GriffinStark<F, D>;
+
+    #[test]
+    fn griffin_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let step_rows: Vec<Row<F>> = (0..12)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let stark = S::default();
+        let trace = generate_griffin_trace(&step_rows);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn griffin_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
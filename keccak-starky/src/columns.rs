@@ -0,0 +1,115 @@
+use crate::constants::R;
+
+/// The number of rounds of Keccak-f[1600]; each round takes one row
+pub const NUM_ROUNDS: usize = 24;
+
+/// The number of 64-bit lanes of the state, lane `(x, y)` at index `x + 5 * y`
+pub const NUM_LANES: usize = 25;
+
+/// Lanes are split into two 32-bit limbs, low limb first, so limb `i` is the
+/// half `i % 2` of lane `i / 2`
+pub const NUM_LIMBS: usize = 2 * NUM_LANES;
+
+/// The starting point of the round flags: column `reg_step(r)` is set on the
+/// row of round `r`
+const START_STEP: usize = 0;
+
+#[must_use]
+pub const fn reg_step(round: usize) -> usize {
+    START_STEP + round
+}
+
+/// The starting point of the preimage of the permutation, repeated on each of
+/// its rows
+const START_PREIMAGE: usize = START_STEP + NUM_ROUNDS;
+
+#[must_use]
+pub const fn reg_preimage_limb(i: usize) -> usize {
+    START_PREIMAGE + i
+}
+
+/// The starting point of the state at the start of the round
+const START_INPUT: usize = START_PREIMAGE + NUM_LIMBS;
+
+#[must_use]
+pub const fn reg_input_limb(i: usize) -> usize {
+    START_INPUT + i
+}
+
+/// The starting point of the bits of `C[x] = xor_y A[x, y]`
+const START_C: usize = START_INPUT + NUM_LIMBS;
+
+#[must_use]
+pub const fn reg_c(x: usize, z: usize) -> usize {
+    START_C + x * 64 + z
+}
+
+/// The starting point of the bits of
+/// `C'[x, z] = xor(C[x, z], C[x - 1, z], C[x + 1, z - 1])`
+const START_C_PRIME: usize = START_C + 5 * 64;
+
+#[must_use]
+pub const fn reg_c_prime(x: usize, z: usize) -> usize {
+    START_C_PRIME + x * 64 + z
+}
+
+/// The starting point of the bits of the state after theta
+const START_A_PRIME: usize = START_C_PRIME + 5 * 64;
+
+#[must_use]
+pub const fn reg_a_prime(x: usize, y: usize, z: usize) -> usize {
+    START_A_PRIME + (x + 5 * y) * 64 + z
+}
+
+/// The bits of the state after rho and pi, which are a permutation of the
+/// bits after theta: `B[y, 2x + 3y] = rot(A'[x, y], R[x][y])`
+#[must_use]
+pub const fn reg_b(x: usize, y: usize, z: usize) -> usize {
+    let src_x = (x + 3 * y) % 5;
+    let src_y = x;
+    let rot = R[src_x][src_y] as usize;
+    reg_a_prime(src_x, src_y, (z + 64 - rot) % 64)
+}
+
+/// The starting point of the limbs of the state after chi
+const START_A_PRIME_PRIME: usize = START_A_PRIME + NUM_LANES * 64;
+
+/// The low limb of lane `(x, y)` after chi; the high limb follows it
+#[must_use]
+pub const fn reg_a_prime_prime(x: usize, y: usize) -> usize {
+    START_A_PRIME_PRIME + 2 * (x + 5 * y)
+}
+
+/// The starting point of the bits of lane `(0, 0)` after chi, to which iota
+/// adds the round constant
+const START_A_PRIME_PRIME_0_0_BITS: usize = START_A_PRIME_PRIME + NUM_LIMBS;
+
+#[must_use]
+pub const fn reg_a_prime_prime_0_0_bit(z: usize) -> usize {
+    START_A_PRIME_PRIME_0_0_BITS + z
+}
+
+/// The starting point of the limbs of lane `(0, 0)` after iota
+const START_A_PRIME_PRIME_PRIME_0_0: usize = START_A_PRIME_PRIME_0_0_BITS + 64;
+
+/// The low limb of lane `(x, y)` at the end of the round; the high limb
+/// follows it. Only lane `(0, 0)` differs from the state after chi.
+#[must_use]
+pub const fn reg_a_prime_prime_prime(x: usize, y: usize) -> usize {
+    if x == 0 && y == 0 {
+        START_A_PRIME_PRIME_PRIME_0_0
+    } else {
+        reg_a_prime_prime(x, y)
+    }
+}
+
+/// The limb `i` of the state at the end of the round, which is the output of
+/// the permutation on the row of the last round
+#[must_use]
+pub const fn reg_output_limb(i: usize) -> usize {
+    let lane = i / 2;
+    reg_a_prime_prime_prime(lane % 5, lane / 5) + i % 2
+}
+
+/// The total number of columns
+pub const NUM_COLUMNS: usize = START_A_PRIME_PRIME_PRIME_0_0 + 2;
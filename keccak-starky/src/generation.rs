@@ -0,0 +1,173 @@
+use crate::columns::{
+    reg_a_prime, reg_a_prime_prime, reg_a_prime_prime_0_0_bit, reg_a_prime_prime_prime, reg_c,
+    reg_c_prime, reg_input_limb, reg_preimage_limb, reg_step, NUM_COLUMNS, NUM_LANES, NUM_ROUNDS,
+};
+use crate::constants::{R, RC};
+use plonky2::hash::hash_types::RichField;
+
+/// A lane as its low and high 32-bit limbs
+fn limbs(lane: u64) -> [u64; 2] {
+    [lane & 0xFFFF_FFFF, lane >> 32]
+}
+
+/// One round of Keccak-f[1600], returning the intermediate states
+/// `(C, C', A', A'', A''')`
+#[allow(clippy::type_complexity)]
+fn keccak_round(
+    round: usize,
+    state: &[u64; NUM_LANES],
+) -> (
+    [u64; 5],
+    [u64; 5],
+    [u64; NUM_LANES],
+    [u64; NUM_LANES],
+    [u64; NUM_LANES],
+) {
+    // theta
+    let c: [u64; 5] = std::array::from_fn(|x| (0..5).fold(0, |acc, y| acc ^ state[x + 5 * y]));
+    let c_prime: [u64; 5] =
+        std::array::from_fn(|x| c[x] ^ c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1));
+    let a_prime: [u64; NUM_LANES] = std::array::from_fn(|i| state[i] ^ c[i % 5] ^ c_prime[i % 5]);
+
+    // rho and pi
+    let mut b = [0; NUM_LANES];
+    for x in 0..5 {
+        for y in 0..5 {
+            b[y + 5 * ((2 * x + 3 * y) % 5)] = a_prime[x + 5 * y].rotate_left(R[x][y]);
+        }
+    }
+
+    // chi
+    let a_prime_prime: [u64; NUM_LANES] = std::array::from_fn(|i| {
+        let (x, y) = (i % 5, i / 5);
+        b[i] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y])
+    });
+
+    // iota
+    let mut a_prime_prime_prime = a_prime_prime;
+    a_prime_prime_prime[0] ^= RC[round];
+
+    (c, c_prime, a_prime, a_prime_prime, a_prime_prime_prime)
+}
+
+/// The Keccak-f[1600] permutation
+pub fn keccakf(state: &mut [u64; NUM_LANES]) {
+    for round in 0..NUM_ROUNDS {
+        *state = keccak_round(round, state).4;
+    }
+}
+
+/// Fill the row of `round` and return the state at the end of the round
+pub(crate) fn fill_round_row<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    round: usize,
+    preimage: &[u64; NUM_LANES],
+    state: &[u64; NUM_LANES],
+) -> [u64; NUM_LANES] {
+    let (c, c_prime, a_prime, a_prime_prime, a_prime_prime_prime) = keccak_round(round, state);
+    let bit = |value: u64, z: usize| F::from_bool(value >> z & 1 == 1);
+
+    trace[reg_step(round)][row] = F::ONE;
+    for lane in 0..NUM_LANES {
+        for (half, (preimage_limb, input_limb)) in limbs(preimage[lane])
+            .into_iter()
+            .zip(limbs(state[lane]))
+            .enumerate()
+        {
+            trace[reg_preimage_limb(2 * lane + half)][row] = F::from_canonical_u64(preimage_limb);
+            trace[reg_input_limb(2 * lane + half)][row] = F::from_canonical_u64(input_limb);
+        }
+    }
+    for x in 0..5 {
+        for z in 0..64 {
+            trace[reg_c(x, z)][row] = bit(c[x], z);
+            trace[reg_c_prime(x, z)][row] = bit(c_prime[x], z);
+            for y in 0..5 {
+                trace[reg_a_prime(x, y, z)][row] = bit(a_prime[x + 5 * y], z);
+            }
+        }
+        for y in 0..5 {
+            for (half, limb) in limbs(a_prime_prime[x + 5 * y]).into_iter().enumerate() {
+                trace[reg_a_prime_prime(x, y) + half][row] = F::from_canonical_u64(limb);
+            }
+        }
+    }
+    for z in 0..64 {
+        trace[reg_a_prime_prime_0_0_bit(z)][row] = bit(a_prime_prime[0], z);
+    }
+    for (half, limb) in limbs(a_prime_prime_prime[0]).into_iter().enumerate() {
+        trace[reg_a_prime_prime_prime(0, 0) + half][row] = F::from_canonical_u64(limb);
+    }
+
+    a_prime_prime_prime
+}
+
+/// Function to generate the Keccak-f[1600] trace: 24 rows per input state,
+/// padded with all-zero rows to a power of 2.
+#[must_use]
+pub fn generate_keccak_trace<F: RichField>(inputs: &[[u64; NUM_LANES]]) -> [Vec<F>; NUM_COLUMNS] {
+    let trace_len = (inputs.len() * NUM_ROUNDS).next_power_of_two();
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; NUM_COLUMNS];
+
+    for (i, preimage) in inputs.iter().enumerate() {
+        let mut state = *preimage;
+        for round in 0..NUM_ROUNDS {
+            state = fill_round_row(&mut trace, i * NUM_ROUNDS + round, round, preimage, &state);
+        }
+    }
+
+    trace.try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::{reg_output_limb, reg_step, NUM_LANES, NUM_ROUNDS};
+    use crate::generation::{generate_keccak_trace, keccakf};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, PrimeField64};
+
+    type F = GoldilocksField;
+
+    fn pseudo_random_state(seed: u64) -> [u64; NUM_LANES] {
+        std::array::from_fn(|i| {
+            (seed + i as u64)
+                .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+                .rotate_left(17)
+        })
+    }
+
+    #[test]
+    fn keccakf_matches_reference() {
+        for seed in 0..4 {
+            let mut state = pseudo_random_state(seed);
+            let mut expected = state;
+            keccakf(&mut state);
+            tiny_keccak::keccakf(&mut expected);
+            assert_eq!(state, expected);
+        }
+    }
+
+    #[test]
+    fn generate_keccak_trace_outputs() {
+        let inputs: Vec<_> = (0..3).map(pseudo_random_state).collect();
+        let trace = generate_keccak_trace::<F>(&inputs);
+        assert_eq!(trace[0].len(), 128);
+
+        for (i, input) in inputs.iter().enumerate() {
+            let row = i * NUM_ROUNDS + NUM_ROUNDS - 1;
+            assert_eq!(trace[reg_step(NUM_ROUNDS - 1)][row], F::ONE);
+
+            let mut expected = *input;
+            tiny_keccak::keccakf(&mut expected);
+            let output: Vec<u64> = (0..NUM_LANES)
+                .map(|lane| {
+                    let limb =
+                        |half| trace[reg_output_limb(2 * lane + half)][row].to_canonical_u64();
+                    limb(0) | limb(1) << 32
+                })
+                .collect();
+            assert_eq!(output, expected);
+        }
+    }
+}
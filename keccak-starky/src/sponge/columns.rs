@@ -0,0 +1,37 @@
+use crate::columns::NUM_COLUMNS;
+use crate::sponge::{DIGEST_BYTES, RATE_BYTES};
+
+/// The starting point of the permutation columns, laid out as in `crate::columns`
+pub(crate) const COL_PERMUTATION_START: usize = 0;
+
+/// 1 on the rows of the first block of a message, 0 otherwise
+pub(crate) const COL_IS_FIRST_BLOCK: usize = COL_PERMUTATION_START + NUM_COLUMNS; // 2480
+
+/// 1 on the rows of the last block of a message, 0 otherwise
+pub(crate) const COL_IS_FINAL_BLOCK: usize = COL_IS_FIRST_BLOCK + 1; // 2481
+
+/// The number of message bytes in this block and the blocks before it
+pub(crate) const COL_LENGTH: usize = COL_IS_FINAL_BLOCK + 1; // 2482
+
+/// In the first row of the final block, column `COL_IS_FINAL_LEN_START + i`
+/// is 1 if the message ends after `i` bytes of the block, where the padding
+/// starts. All are 0 in other blocks.
+pub(crate) const COL_IS_FINAL_LEN_START: usize = COL_LENGTH + 1; // 2483
+
+/// The starting point of the block bytes, in the first row of the block
+pub(crate) const COL_BYTES_START: usize = COL_IS_FINAL_LEN_START + RATE_BYTES; // 2619
+
+/// The starting point of the little-endian bit decomposition of each byte,
+/// which is also the bit decomposition of the rate lanes of the block
+pub(crate) const COL_BYTE_BITS_START: usize = COL_BYTES_START + RATE_BYTES; // 2755
+
+/// The starting point of the bits of the rate lanes before the block is
+/// absorbed, in the first row of the block
+pub(crate) const COL_ORIGINAL_RATE_BITS_START: usize = COL_BYTE_BITS_START + RATE_BYTES * 8; // 3843
+
+/// The starting point of the first bytes of the state at the end of the
+/// round. In the last row of the final block they are the digest.
+pub(crate) const COL_DIGEST_START: usize = COL_ORIGINAL_RATE_BITS_START + RATE_BYTES * 8; // 4931
+
+/// The total number of columns
+pub(crate) const SPONGE_NUM_COLS: usize = COL_DIGEST_START + DIGEST_BYTES; // 4963
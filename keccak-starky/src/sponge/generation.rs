@@ -0,0 +1,134 @@
+use crate::columns::{NUM_LANES, NUM_ROUNDS};
+use crate::generation::fill_round_row;
+use crate::sponge::columns::{
+    COL_BYTES_START, COL_BYTE_BITS_START, COL_DIGEST_START, COL_IS_FINAL_BLOCK,
+    COL_IS_FINAL_LEN_START, COL_IS_FIRST_BLOCK, COL_LENGTH, COL_ORIGINAL_RATE_BITS_START,
+    SPONGE_NUM_COLS,
+};
+use crate::sponge::{
+    absorb_block, digest, keccak256, pad_bytes, DIGEST_BYTES, RATE_BYTES, RATE_LANES,
+};
+use plonky2::hash::hash_types::RichField;
+
+/// The public inputs: the bytes of the digest, then the length of the message
+/// in bytes
+pub const SPONGE_PUBLIC_INPUTS: usize = DIGEST_BYTES + 1;
+
+/// Fill the 24 rows of one block starting at `row` and return the state
+/// after the permutation. `length` counts the message bytes up to the end of
+/// the block.
+fn fill_block_rows<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    state: &[u64; NUM_LANES],
+    block: &[u8],
+    is_first: bool,
+    final_len: Option<usize>,
+    length: usize,
+) -> [u64; NUM_LANES] {
+    let mut preimage = *state;
+    absorb_block(&mut preimage, block);
+
+    // the block, its bits and the rate before absorbing go in the first row
+    for (i, &byte) in block.iter().enumerate() {
+        trace[COL_BYTES_START + i][row] = F::from_canonical_u8(byte);
+        for j in 0..8 {
+            trace[COL_BYTE_BITS_START + 8 * i + j][row] = F::from_bool(byte >> j & 1 == 1);
+        }
+    }
+    for (lane, &value) in state[..RATE_LANES].iter().enumerate() {
+        for z in 0..64 {
+            trace[COL_ORIGINAL_RATE_BITS_START + 64 * lane + z][row] =
+                F::from_bool(value >> z & 1 == 1);
+        }
+    }
+    if let Some(len) = final_len {
+        trace[COL_IS_FINAL_LEN_START + len][row] = F::ONE;
+    }
+
+    let mut state = preimage;
+    for round in 0..NUM_ROUNDS {
+        let r = row + round;
+        state = fill_round_row(trace, r, round, &preimage, &state);
+        trace[COL_IS_FIRST_BLOCK][r] = F::from_bool(is_first);
+        trace[COL_IS_FINAL_BLOCK][r] = F::from_bool(final_len.is_some());
+        trace[COL_LENGTH][r] = F::from_canonical_usize(length);
+        for (i, byte) in digest(&state).into_iter().enumerate() {
+            trace[COL_DIGEST_START + i][r] = F::from_canonical_u8(byte);
+        }
+    }
+    state
+}
+
+/// Function to generate the trace of hashing `message` with Keccak-256, and
+/// the public inputs: 24 rows per block, padded with all-zero rows to a power
+/// of 2. As 24 does not divide a power of 2, the trace ends with at least one
+/// padding row.
+#[must_use]
+pub fn generate_keccak_sponge_trace<F: RichField>(
+    message: &[u8],
+) -> ([Vec<F>; SPONGE_NUM_COLS], [F; SPONGE_PUBLIC_INPUTS]) {
+    let padded = pad_bytes(message);
+    let num_blocks = padded.len() / RATE_BYTES;
+    let trace_len = (num_blocks * NUM_ROUNDS).next_power_of_two();
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; SPONGE_NUM_COLS];
+
+    let mut state = [0; NUM_LANES];
+    for (i, block) in padded.chunks(RATE_BYTES).enumerate() {
+        let final_len = (i == num_blocks - 1).then_some(message.len() % RATE_BYTES);
+        let length = message.len().min((i + 1) * RATE_BYTES);
+        state = fill_block_rows(
+            &mut trace,
+            i * NUM_ROUNDS,
+            &state,
+            block,
+            i == 0,
+            final_len,
+            length,
+        );
+    }
+
+    let mut public_inputs = [F::ZERO; SPONGE_PUBLIC_INPUTS];
+    for (input, byte) in public_inputs.iter_mut().zip(keccak256(message)) {
+        *input = F::from_canonical_u8(byte);
+    }
+    public_inputs[DIGEST_BYTES] = F::from_canonical_usize(message.len());
+
+    (trace.try_into().unwrap(), public_inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::NUM_ROUNDS;
+    use crate::sponge::columns::{COL_DIGEST_START, COL_IS_FINAL_BLOCK, COL_LENGTH};
+    use crate::sponge::generation::generate_keccak_sponge_trace;
+    use crate::sponge::tests::{boundary_messages, reference_keccak256};
+    use crate::sponge::RATE_BYTES;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Field;
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn generate_keccak_sponge_trace_digests() {
+        for message in boundary_messages() {
+            let (trace, public_inputs) = generate_keccak_sponge_trace::<F>(&message);
+            let digest = reference_keccak256(&message);
+            assert!(public_inputs[..32]
+                .iter()
+                .zip(digest)
+                .all(|(&input, byte)| input == F::from_canonical_u8(byte)));
+            assert_eq!(public_inputs[32], F::from_canonical_usize(message.len()));
+
+            // the digest and the length are in the last row of the final block
+            let num_blocks = message.len() / RATE_BYTES + 1;
+            let row = num_blocks * NUM_ROUNDS - 1;
+            assert_eq!(trace[COL_IS_FINAL_BLOCK][row], F::ONE);
+            for (i, &input) in public_inputs[..32].iter().enumerate() {
+                assert_eq!(trace[COL_DIGEST_START + i][row], input);
+            }
+            assert_eq!(trace[COL_LENGTH][row], public_inputs[32]);
+            assert_eq!(trace[0].len(), (row + 1).next_power_of_two());
+        }
+    }
+}
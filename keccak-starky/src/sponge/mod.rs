@@ -0,0 +1,112 @@
+//! Keccak-256 of byte strings over the Keccak-f[1600] permutation.
+//!
+//! Messages are padded with pad10*1 (a `0x01` byte, zeros and a final `0x80`
+//! bit, merged into `0x81` when only one byte is left) up to a multiple of
+//! [`RATE_BYTES`]. Each block is xored into the first [`RATE_LANES`] lanes as
+//! little-endian 64-bit words, and the digest is the first [`DIGEST_BYTES`]
+//! bytes of the final state.
+#![allow(clippy::module_name_repetitions)]
+
+pub mod columns;
+pub mod generation;
+pub mod stark;
+
+use crate::columns::NUM_LANES;
+use crate::generation::keccakf;
+
+/// The number of message bytes absorbed per permutation
+pub const RATE_BYTES: usize = 136;
+
+/// The number of lanes absorbed per permutation
+pub const RATE_LANES: usize = RATE_BYTES / 8; // 17
+
+/// The number of bytes of a digest
+pub const DIGEST_BYTES: usize = 32;
+
+/// Pad a message with pad10*1 to a multiple of [`RATE_BYTES`].
+#[must_use]
+pub fn pad_bytes(message: &[u8]) -> Vec<u8> {
+    let mut padded = message.to_vec();
+    padded.push(0x01);
+    let padded_len = (padded.len() + RATE_BYTES - 1) / RATE_BYTES * RATE_BYTES;
+    padded.resize(padded_len, 0);
+    *padded.last_mut().unwrap() |= 0x80;
+    padded
+}
+
+/// Xor a block of [`RATE_BYTES`] bytes into the rate of `state`.
+pub(crate) fn absorb_block(state: &mut [u64; NUM_LANES], block: &[u8]) {
+    for (lane, chunk) in state.iter_mut().zip(block.chunks(8)) {
+        *lane ^= u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+}
+
+/// The first [`DIGEST_BYTES`] bytes of `state`
+pub(crate) fn digest(state: &[u64; NUM_LANES]) -> [u8; DIGEST_BYTES] {
+    let mut digest = [0; DIGEST_BYTES];
+    for (chunk, lane) in digest.chunks_mut(8).zip(state) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+/// Hash a byte string natively. The result is the digest proved by
+/// [`stark::KeccakSpongeStark`] in the last row of the final block.
+#[must_use]
+pub fn keccak256(message: &[u8]) -> [u8; DIGEST_BYTES] {
+    let mut state = [0; NUM_LANES];
+    for block in pad_bytes(message).chunks(RATE_BYTES) {
+        absorb_block(&mut state, block);
+        keccakf(&mut state);
+    }
+    digest(&state)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sponge::{keccak256, pad_bytes, RATE_BYTES};
+    use proptest::prelude::*;
+    use tiny_keccak::{Hasher, Keccak};
+
+    pub(crate) fn reference_keccak256(message: &[u8]) -> [u8; 32] {
+        let mut hasher = Keccak::v256();
+        hasher.update(message);
+        let mut digest = [0; 32];
+        hasher.finalize(&mut digest);
+        digest
+    }
+
+    /// Messages of every length around the block boundaries
+    pub(crate) fn boundary_messages() -> Vec<Vec<u8>> {
+        [0usize, 1, 134, 135, 136, 137, 271, 272, 273]
+            .into_iter()
+            .map(|len| (0..len).map(|i| (i * 7 + len).to_le_bytes()[0]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn padding_lengths() {
+        assert_eq!(pad_bytes(&[]).len(), RATE_BYTES);
+        assert_eq!(pad_bytes(&[0; RATE_BYTES - 1]).len(), RATE_BYTES);
+        assert_eq!(pad_bytes(&[0; RATE_BYTES - 1])[RATE_BYTES - 1], 0x81);
+        assert_eq!(pad_bytes(&[0; RATE_BYTES]).len(), 2 * RATE_BYTES);
+        assert_eq!(pad_bytes(&[7])[..2], [7, 0x01]);
+        assert_eq!(pad_bytes(&[7])[RATE_BYTES - 1], 0x80);
+    }
+
+    #[test]
+    fn keccak256_matches_reference() {
+        for message in boundary_messages() {
+            assert_eq!(keccak256(&message), reference_keccak256(&message));
+        }
+        // keccak256("") as used by Ethereum
+        assert_eq!(keccak256(&[])[..4], [0xc5, 0xd2, 0x46, 0x01],);
+    }
+
+    proptest! {
+        #[test]
+        fn keccak256_matches_reference_on_random_messages(message in prop::collection::vec(any::<u8>(), 0..600)) {
+            prop_assert_eq!(keccak256(&message), reference_keccak256(&message));
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use crate::columns::{reg_input_limb, reg_output_limb, reg_step, NUM_LANES, NUM_ROUNDS};
+use crate::sponge::columns::{
+    COL_BYTES_START, COL_BYTE_BITS_START, COL_DIGEST_START, COL_IS_FINAL_BLOCK,
+    COL_IS_FINAL_LEN_START, COL_IS_FIRST_BLOCK, COL_LENGTH, COL_ORIGINAL_RATE_BITS_START,
+    COL_PERMUTATION_START, SPONGE_NUM_COLS,
+};
+use crate::sponge::generation::SPONGE_PUBLIC_INPUTS;
+use crate::sponge::{DIGEST_BYTES, RATE_BYTES, RATE_LANES};
+use crate::stark::{eval_keccak_permutation, limb, output_bit, xor};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+/// Constraints of the block bytes and of pad10*1 in the final block, on the
+/// first row of a block
+fn eval_block_bytes<P: PackedField>(lv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    let is_first_round = lv[reg_step(0)];
+
+    let mut len_sum = P::ZEROS;
+    for i in 0..RATE_BYTES {
+        let byte = (0..8).rev().fold(P::ZEROS, |acc, j| {
+            let bit = lv[COL_BYTE_BITS_START + 8 * i + j];
+            yield_constr.constraint(bit * (bit - P::ONES));
+            acc * P::Scalar::TWO + bit
+        });
+        yield_constr.constraint(is_first_round * (byte - lv[COL_BYTES_START + i]));
+
+        // `len_sum` is 1 after the end of the message in the final block
+        let is_final_len = lv[COL_IS_FINAL_LEN_START + i];
+        yield_constr.constraint(is_final_len * (is_final_len - P::ONES));
+        let (pad_start, pad) = if i == RATE_BYTES - 1 {
+            (0x81, 0x80)
+        } else {
+            (0x01, 0x00)
+        };
+        yield_constr.constraint(
+            is_first_round * is_final_len * (byte - P::Scalar::from_canonical_u8(pad_start)),
+        );
+        yield_constr
+            .constraint(is_first_round * len_sum * (byte - P::Scalar::from_canonical_u8(pad)));
+        len_sum += is_final_len;
+    }
+    yield_constr.constraint(is_first_round * (len_sum - lv[COL_IS_FINAL_BLOCK]));
+}
+
+/// The number of message bytes in a block, on its first row: all of them
+/// unless the block is final, where the message ends at the flagged position
+fn block_len<P: PackedField>(lv: &[P]) -> P {
+    let full = (P::ONES - lv[COL_IS_FINAL_BLOCK]) * P::Scalar::from_canonical_usize(RATE_BYTES);
+    (0..RATE_BYTES).fold(full, |acc, i| {
+        acc + lv[COL_IS_FINAL_LEN_START + i] * P::Scalar::from_canonical_usize(i)
+    })
+}
+
+/// The Keccak-256 sponge table for one message: the Keccak-f[1600] table
+/// extended with the absorbed blocks and the digest. The digest and the
+/// length of the message are public inputs.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct KeccakSpongeStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for KeccakSpongeStark<F, D> {
+    const COLUMNS: usize = SPONGE_NUM_COLS;
+    const PUBLIC_INPUTS: usize = SPONGE_PUBLIC_INPUTS;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let lv = vars.local_values;
+        let nv = vars.next_values;
+        eval_keccak_permutation(
+            &lv[COL_PERMUTATION_START..],
+            &nv[COL_PERMUTATION_START..],
+            yield_constr,
+        );
+        eval_block_bytes(lv, yield_constr);
+
+        let is_first_round = lv[reg_step(0)];
+        let is_last_round = lv[reg_step(NUM_ROUNDS - 1)];
+        let next_is_first_round = nv[reg_step(0)];
+        let not_last_round = (0..NUM_ROUNDS - 1).fold(P::ZEROS, |acc, r| acc + lv[reg_step(r)]);
+        let is_padding = P::ONES - not_last_round - is_last_round;
+        let next_is_padding = (0..NUM_ROUNDS).fold(P::ONES, |acc, r| acc - nv[reg_step(r)]);
+
+        // the block flags and the length hold on all rows of a block
+        let is_first = lv[COL_IS_FIRST_BLOCK];
+        let is_final = lv[COL_IS_FINAL_BLOCK];
+        let next_is_first = nv[COL_IS_FIRST_BLOCK];
+        yield_constr.constraint(is_first * (is_first - P::ONES));
+        yield_constr.constraint(is_final * (is_final - P::ONES));
+        for col in [COL_IS_FIRST_BLOCK, COL_IS_FINAL_BLOCK, COL_LENGTH] {
+            yield_constr.constraint_transition(not_last_round * (nv[col] - lv[col]));
+        }
+
+        // the message starts on the first row, and no other block starts one
+        yield_constr.constraint_first_row(is_first_round - P::ONES);
+        yield_constr.constraint_first_row(is_first - P::ONES);
+        yield_constr.constraint_transition(next_is_first_round * next_is_first);
+
+        // a block that continues a message follows the last round of a block
+        // of the same message that is not final
+        let next_continues = next_is_first_round * (P::ONES - next_is_first);
+        yield_constr.constraint_transition(next_continues * (P::ONES - is_last_round));
+        yield_constr.constraint_transition(next_continues * is_final);
+
+        // a block that is not final is followed by the next block, the final
+        // block by padding rows only, and the trace ends with a padding row
+        yield_constr.constraint_transition(is_last_round * (P::ONES - is_final) * next_is_padding);
+        yield_constr.constraint_transition(is_last_round * is_final * next_is_first_round);
+        yield_constr.constraint_transition(is_padding * next_is_first_round);
+        yield_constr.constraint_last_row(P::ONES - is_padding);
+
+        // the length counts the message bytes of the blocks so far
+        yield_constr.constraint_first_row(lv[COL_LENGTH] - block_len(lv));
+        yield_constr.constraint_transition(
+            next_continues * (nv[COL_LENGTH] - lv[COL_LENGTH] - block_len(nv)),
+        );
+
+        // the rate lanes before absorbing are zero in the first block and
+        // the output of the previous block otherwise
+        for lane in 0..RATE_LANES {
+            let original_bit = |z| lv[COL_ORIGINAL_RATE_BITS_START + 64 * lane + z];
+            let next_original_bit = |z| nv[COL_ORIGINAL_RATE_BITS_START + 64 * lane + z];
+            for z in 0..64 {
+                let bit = original_bit(z);
+                yield_constr.constraint(bit * (bit - P::ONES));
+                yield_constr.constraint(is_first_round * is_first * bit);
+            }
+            for half in 0..2 {
+                let output = lv[reg_output_limb(2 * lane + half)];
+                yield_constr.constraint_transition(
+                    next_continues * (limb(half, next_original_bit) - output),
+                );
+            }
+
+            // absorb: the input of the first round is the block xored into
+            // the rate
+            let get_bit = |z| xor(original_bit(z), lv[COL_BYTE_BITS_START + 64 * lane + z]);
+            for half in 0..2 {
+                let input = lv[reg_input_limb(2 * lane + half)];
+                yield_constr.constraint(is_first_round * (limb(half, get_bit) - input));
+            }
+        }
+
+        // the capacity starts at zero and is carried over between blocks
+        for i in 2 * RATE_LANES..2 * NUM_LANES {
+            let input = lv[reg_input_limb(i)];
+            yield_constr.constraint(is_first_round * is_first * input);
+            yield_constr.constraint_transition(
+                next_continues * (nv[reg_input_limb(i)] - lv[reg_output_limb(i)]),
+            );
+        }
+
+        // the first bytes of the state at the end of each round, which are
+        // the digest in the last round of the final block
+        for i in 0..DIGEST_BYTES {
+            let lane = i / 8;
+            let byte = (0..8).rev().fold(P::ZEROS, |acc, j| {
+                acc * P::Scalar::TWO + output_bit(lv, lane % 5, lane / 5, 8 * (i % 8) + j)
+            });
+            yield_constr.constraint(byte - lv[COL_DIGEST_START + i]);
+        }
+
+        // the digest and the length in the last round of the final block
+        let public_inputs = vars.public_inputs;
+        let is_digest_row = is_last_round * is_final;
+        for i in 0..DIGEST_BYTES {
+            yield_constr.constraint(is_digest_row * (lv[COL_DIGEST_START + i] - public_inputs[i]));
+        }
+        yield_constr.constraint(is_digest_row * (lv[COL_LENGTH] - public_inputs[DIGEST_BYTES]));
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sponge::generation::generate_keccak_sponge_trace;
+    use crate::sponge::stark::KeccakSpongeStark;
+    use crate::sponge::tests::boundary_messages;
+    use crate::sponge::DIGEST_BYTES;
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use plonky2::field::types::Field;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KeccakSpongeStark<F, D>;
+
+    fn config() -> StarkConfig {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config
+    }
+
+    #[test]
+    fn keccak_sponge_constraints() -> Result<()> {
+        let config = config();
+        let stark = S::default();
+
+        // single blocks around the padding boundary, then two full blocks
+        for message in &boundary_messages()[..8] {
+            let (trace, public_inputs) = generate_keccak_sponge_trace(message);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace_to_poly_values(trace),
+                public_inputs,
+                &mut TimingTree::default(),
+            )?;
+            verify_stark_proof(stark, proof, &config)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_public_inputs_fail() -> Result<()> {
+        let config = config();
+        let stark = S::default();
+
+        let message = &boundary_messages()[5];
+        let (trace, public_inputs) = generate_keccak_sponge_trace(message);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_to_poly_values(trace),
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        for i in [0, DIGEST_BYTES - 1, DIGEST_BYTES] {
+            let mut tampered = proof.clone();
+            tampered.public_inputs[i] += F::ONE;
+            assert!(verify_stark_proof(stark, tampered, &config).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn keccak_sponge_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
@@ -0,0 +1,283 @@
+use crate::columns::{
+    reg_a_prime, reg_a_prime_prime, reg_a_prime_prime_0_0_bit, reg_a_prime_prime_prime, reg_b,
+    reg_c, reg_c_prime, reg_input_limb, reg_preimage_limb, reg_step, NUM_COLUMNS, NUM_LIMBS,
+    NUM_ROUNDS,
+};
+use crate::constants::rc_bit;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+// degree: 2
+pub(crate) fn xor<P: PackedField>(a: P, b: P) -> P {
+    a + b - a * b * P::Scalar::TWO
+}
+
+// degree: 3
+fn xor3<P: PackedField>(a: P, b: P, c: P) -> P {
+    xor(a, xor(b, c))
+}
+
+// degree: 2
+fn andn<P: PackedField>(a: P, b: P) -> P {
+    (P::ONES - a) * b
+}
+
+/// Recompose the 32 bits `get_bit(32 * half)..get_bit(32 * half + 31)` into
+/// a limb
+pub(crate) fn limb<P: PackedField>(half: usize, get_bit: impl Fn(usize) -> P) -> P {
+    (0..32).rev().fold(P::ZEROS, |acc, z| {
+        acc * P::Scalar::TWO + get_bit(32 * half + z)
+    })
+}
+
+/// The bit `z` of lane `(x, y)` after chi, as an expression of degree 3 in
+/// the bits after theta
+fn chi_bit<P: PackedField>(lv: &[P], x: usize, y: usize, z: usize) -> P {
+    xor(
+        lv[reg_b(x, y, z)],
+        andn(lv[reg_b((x + 1) % 5, y, z)], lv[reg_b((x + 2) % 5, y, z)]),
+    )
+}
+
+/// The bit `z` of lane `(x, y)` at the end of the round, of degree 3
+pub(crate) fn output_bit<P: PackedField>(lv: &[P], x: usize, y: usize, z: usize) -> P {
+    if x == 0 && y == 0 {
+        let rc = (0..NUM_ROUNDS)
+            .filter(|&round| rc_bit(round, z))
+            .fold(P::ZEROS, |acc, round| acc + lv[reg_step(round)]);
+        xor(lv[reg_a_prime_prime_0_0_bit(z)], rc)
+    } else {
+        chi_bit(lv, x, y, z)
+    }
+}
+
+/// Constraints of the round flags and of the state passed between rounds
+fn eval_round_transitions<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    // the round flags are one-hot or all zero on padding rows, start with
+    // round 0 and step through the rounds in order
+    let mut flag_sum = P::ZEROS;
+    for round in 0..NUM_ROUNDS {
+        let flag = lv[reg_step(round)];
+        yield_constr.constraint(flag * (flag - P::ONES));
+        flag_sum += flag;
+        if round > 0 {
+            yield_constr.constraint_first_row(flag);
+        }
+        if round < NUM_ROUNDS - 1 {
+            yield_constr.constraint_transition(nv[reg_step(round + 1)] - flag);
+        }
+    }
+    yield_constr.constraint(flag_sum * (flag_sum - P::ONES));
+
+    // the first round starts from the preimage, which is carried along
+    // with the state to the last round
+    let not_last_round = flag_sum - lv[reg_step(NUM_ROUNDS - 1)];
+    for i in 0..NUM_LIMBS {
+        yield_constr
+            .constraint(lv[reg_step(0)] * (lv[reg_input_limb(i)] - lv[reg_preimage_limb(i)]));
+        yield_constr.constraint_transition(
+            not_last_round * (nv[reg_preimage_limb(i)] - lv[reg_preimage_limb(i)]),
+        );
+    }
+    for x in 0..5 {
+        for y in 0..5 {
+            for half in 0..2 {
+                let output = lv[reg_a_prime_prime_prime(x, y) + half];
+                let next_input = nv[reg_input_limb(2 * (x + 5 * y) + half)];
+                yield_constr.constraint_transition(not_last_round * (next_input - output));
+            }
+        }
+    }
+}
+
+/// Constraints of a single round laid out as in `columns.rs`
+fn eval_round<P: PackedField>(lv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    // theta: C'[x, z] = xor(C[x, z], C[x - 1, z], C[x + 1, z - 1])
+    for x in 0..5 {
+        for z in 0..64 {
+            let c = lv[reg_c(x, z)];
+            let c_prime = lv[reg_c_prime(x, z)];
+            yield_constr.constraint(c * (c - P::ONES));
+            let expected = xor3(
+                c,
+                lv[reg_c((x + 4) % 5, z)],
+                lv[reg_c((x + 1) % 5, (z + 63) % 64)],
+            );
+            yield_constr.constraint(c_prime - expected);
+        }
+    }
+
+    // A[x, y, z] = xor(A'[x, y, z], D[x, z]) = xor(A'[x, y, z], C[x, z], C'[x, z]),
+    // using the identity checked above
+    for x in 0..5 {
+        for y in 0..5 {
+            let get_bit = |z| {
+                xor3(
+                    lv[reg_a_prime(x, y, z)],
+                    lv[reg_c(x, z)],
+                    lv[reg_c_prime(x, z)],
+                )
+            };
+            for half in 0..2 {
+                let a = lv[reg_input_limb(2 * (x + 5 * y) + half)];
+                yield_constr.constraint(limb(half, get_bit) - a);
+            }
+        }
+    }
+
+    // xor_y A'[x, y, z] = C'[x, z], so the difference of the sum and
+    // C'[x, z] is one of 0, 2 and 4
+    for x in 0..5 {
+        for z in 0..64 {
+            let mut sum = P::ZEROS;
+            for y in 0..5 {
+                let a_prime = lv[reg_a_prime(x, y, z)];
+                yield_constr.constraint(a_prime * (a_prime - P::ONES));
+                sum += a_prime;
+            }
+            let diff = sum - lv[reg_c_prime(x, z)];
+            yield_constr.constraint(
+                diff * (diff - P::Scalar::TWO) * (diff - P::Scalar::from_canonical_u8(4)),
+            );
+        }
+    }
+
+    // rho, pi and chi: A''[x, y] = xor(B[x, y], andn(B[x + 1, y], B[x + 2, y]))
+    for x in 0..5 {
+        for y in 0..5 {
+            let get_bit = |z| chi_bit(lv, x, y, z);
+            for half in 0..2 {
+                let a_prime_prime = lv[reg_a_prime_prime(x, y) + half];
+                yield_constr.constraint(limb(half, get_bit) - a_prime_prime);
+            }
+        }
+    }
+
+    // iota: A'''[0, 0] = xor(A''[0, 0], RC), with the round constant
+    // selected by the round flags
+    let a_prime_prime_0_0_bit = |z| lv[reg_a_prime_prime_0_0_bit(z)];
+    for z in 0..64 {
+        let bit = a_prime_prime_0_0_bit(z);
+        yield_constr.constraint(bit * (bit - P::ONES));
+    }
+    let get_bit = |z| output_bit(lv, 0, 0, z);
+    for half in 0..2 {
+        yield_constr
+            .constraint(limb(half, a_prime_prime_0_0_bit) - lv[reg_a_prime_prime(0, 0) + half]);
+        yield_constr.constraint(limb(half, get_bit) - lv[reg_a_prime_prime_prime(0, 0) + half]);
+    }
+}
+
+/// Constraints of the Keccak-f[1600] table laid out as in `columns.rs`,
+/// starting at the first column of `lv` and `nv`
+pub(crate) fn eval_keccak_permutation<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    eval_round_transitions(lv, nv, yield_constr);
+    eval_round(lv, yield_constr);
+}
+
+/// The Keccak-f[1600] permutation table, one row per round.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct KeccakStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for KeccakStark<F, D> {
+    const COLUMNS: usize = NUM_COLUMNS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_keccak_permutation(vars.local_values, vars.next_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::NUM_LANES;
+    use crate::generation::generate_keccak_trace;
+    use crate::stark::{trace_to_poly_values, KeccakStark};
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KeccakStark<F, D>;
+
+    #[test]
+    fn keccak_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+
+        let inputs: Vec<[u64; NUM_LANES]> = (0..2)
+            .map(|_| F::rand_array::<NUM_LANES>().map(|f| f.0.rotate_left(13) ^ f.0))
+            .collect();
+
+        let stark = S::default();
+        let trace = generate_keccak_trace(&inputs);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn keccak_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
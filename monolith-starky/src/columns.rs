@@ -0,0 +1,72 @@
+use crate::constants::{NUM_BARS, NUM_BYTES, NUM_ROUNDS, STATE_SIZE};
+
+/// The starting point of the one-hot round flags. Padding rows have no flag
+/// set.
+pub(crate) const COL_STEP_START: usize = 0;
+
+/// The starting point of the column input, read on the row of the first
+/// round only
+pub(crate) const COL_INPUT_START: usize = COL_STEP_START + NUM_ROUNDS; // 6
+
+/// The starting point of the state at the start of the round
+pub(crate) const COL_STATE_START: usize = COL_INPUT_START + STATE_SIZE; // 18
+
+/// The bytes of Bars element `i`, little-endian
+#[must_use]
+pub const fn col_input_bytes(i: usize) -> usize {
+    COL_STATE_START + STATE_SIZE + NUM_BYTES * i
+}
+
+/// The looked-up bytes of Bars element `i`, little-endian
+#[must_use]
+pub const fn col_output_bytes(i: usize) -> usize {
+    col_input_bytes(NUM_BARS) + NUM_BYTES * i
+}
+
+/// The inverse of `hi - (2^32 - 1)` for the high half of the bytes of Bars
+/// element `i`, or zero if they are equal. It proves that the bytes are the
+/// canonical decomposition.
+#[must_use]
+pub const fn col_canonical_inv(i: usize) -> usize {
+    col_output_bytes(NUM_BARS) + i
+}
+
+/// The starting point of the state at the end of the round. On the row of
+/// the last round it is the output of the permutation.
+pub const COL_OUTPUT_START: usize = col_canonical_inv(NUM_BARS); // 98
+
+/// The lookup table: every byte and its image, with the last entry repeated
+/// on the rows past 256
+pub(crate) const COL_TABLE_INPUT: usize = COL_OUTPUT_START + STATE_SIZE; // 110
+pub(crate) const COL_TABLE_OUTPUT: usize = COL_TABLE_INPUT + 1; // 111
+
+/// The bits of the table input, little-endian
+pub(crate) const COL_TABLE_INPUT_BITS_START: usize = COL_TABLE_OUTPUT + 1; // 112
+
+/// The bits of `!x.rotate_left(1) & x.rotate_left(2) & x.rotate_left(3)` for
+/// the table input `x`, little-endian
+pub(crate) const COL_TABLE_AND_BITS_START: usize = COL_TABLE_INPUT_BITS_START + NUM_BYTE_BITS; // 120
+
+/// The number of bits of a byte
+pub(crate) const NUM_BYTE_BITS: usize = 8;
+
+/// The number of lookups per row, one per byte of a Bars element
+pub(crate) const NUM_LOOKUPS: usize = NUM_BARS * NUM_BYTES;
+
+/// The permuted input byte, permuted output byte, permuted table input and
+/// permuted table output of lookup `j`
+#[must_use]
+pub const fn col_permuted(j: usize) -> [usize; 4] {
+    let start = COL_TABLE_AND_BITS_START + NUM_BYTE_BITS + 4 * j;
+    [start, start + 1, start + 2, start + 3]
+}
+
+/// The total number of columns
+pub const NUM_COLS: usize = col_permuted(NUM_LOOKUPS)[0]; // 256
+
+/// The input byte and output byte of lookup `j`
+#[must_use]
+pub const fn col_lookup(j: usize) -> [usize; 2] {
+    let (i, k) = (j / NUM_BYTES, j % NUM_BYTES);
+    [col_input_bytes(i) + k, col_output_bytes(i) + k]
+}
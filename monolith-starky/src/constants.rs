@@ -0,0 +1,111 @@
+/// The size of the state: a rate of 8 and a capacity of 4 elements
+pub const STATE_SIZE: usize = 12;
+
+/// The number of rounds, each made of the Bars, Bricks and Concrete layers.
+/// The permutation starts with an extra Concrete layer.
+pub const NUM_ROUNDS: usize = 6;
+
+/// The number of state elements that go through the Bars layer
+pub const NUM_BARS: usize = 4;
+
+/// The number of bytes of a Bars element; the Bar S-box works on bytes
+pub const NUM_BYTES: usize = 8;
+
+/// The Bar S-box of a byte: `(x ^ (!x <<< 1) & (x <<< 2) & (x <<< 3)) <<< 1`
+const fn bar(x: u8) -> u8 {
+    (x ^ (!x).rotate_left(1) & x.rotate_left(2) & x.rotate_left(3)).rotate_left(1)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn lookup_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = bar(i as u8);
+        i += 1;
+    }
+    table
+}
+
+/// The byte lookup table of the Bars layer
+pub const LOOKUP_TABLE: [u8; 256] = lookup_table();
+
+/// The first row of the circulant MDS matrix of the Concrete layer
+pub const MDS: [u64; STATE_SIZE] = [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8];
+
+/// The round constants of the Concrete layers, the first one for the initial
+/// layer. The first and the last ones are zero.
+pub const ROUND_CONSTANTS: [[u64; STATE_SIZE]; NUM_ROUNDS + 1] = [
+    [0; STATE_SIZE],
+    [
+        13_596_126_580_325_903_823,
+        5_676_126_986_831_820_406,
+        11_349_149_288_412_960_427,
+        3_368_797_843_020_733_411,
+        16_240_671_731_749_717_664,
+        9_273_190_757_374_900_239,
+        14_446_552_112_110_239_438,
+        4_033_077_683_985_131_644,
+        4_291_229_347_329_361_293,
+        13_231_607_645_683_636_062,
+        1_383_651_072_186_713_277,
+        8_898_815_177_417_587_567,
+    ],
+    [
+        2_383_619_671_172_821_638,
+        6_065_528_368_924_797_662,
+        16_737_578_966_352_303_081,
+        2_661_700_069_680_749_654,
+        7_414_030_722_730_336_790,
+        18_124_970_299_993_404_776,
+        9_169_923_000_283_400_738,
+        15_832_813_151_034_110_977,
+        16_245_117_847_613_094_506,
+        11_056_181_639_108_379_773,
+        10_546_400_734_398_052_938,
+        8_443_860_941_261_719_174,
+    ],
+    [
+        15_799_082_741_422_909_885,
+        13_421_235_861_052_008_152,
+        15_448_208_253_823_605_561,
+        2_540_286_744_040_770_964,
+        2_895_626_806_801_935_918,
+        8_644_593_510_196_221_619,
+        17_722_491_003_064_835_823,
+        5_166_255_496_419_771_636,
+        1_015_740_739_405_252_346,
+        4_400_043_467_547_597_488,
+        5_176_473_243_271_652_644,
+        4_517_904_634_837_939_508,
+    ],
+    [
+        18_341_030_605_319_882_173,
+        13_366_339_881_666_916_534,
+        6_291_492_342_503_367_536,
+        10_004_214_885_638_819_819,
+        4_748_655_089_269_860_551,
+        1_520_762_444_865_670_308,
+        8_393_589_389_936_386_108,
+        11_025_183_333_304_586_284,
+        5_993_305_003_203_422_738,
+        458_912_836_931_247_573,
+        5_947_003_897_778_655_410,
+        17_184_667_486_285_295_106,
+    ],
+    [
+        15_710_528_677_110_011_358,
+        8_929_476_121_507_374_707,
+        2_351_989_866_172_789_037,
+        11_264_145_846_854_799_752,
+        14_924_075_362_538_455_764,
+        10_107_004_551_857_451_916,
+        18_325_221_206_052_792_232,
+        16_751_515_052_585_522_105,
+        15_305_034_267_720_085_905,
+        15_639_149_412_312_342_017,
+        14_624_541_102_106_656_564,
+        3_542_311_898_554_959_098,
+    ],
+    [0; STATE_SIZE],
+];
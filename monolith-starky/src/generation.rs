@@ -0,0 +1,226 @@
+use crate::columns::{
+    col_canonical_inv, col_input_bytes, col_lookup, col_output_bytes, col_permuted,
+    COL_INPUT_START, COL_OUTPUT_START, COL_STATE_START, COL_STEP_START, COL_TABLE_AND_BITS_START,
+    COL_TABLE_INPUT, COL_TABLE_INPUT_BITS_START, COL_TABLE_OUTPUT, NUM_BYTE_BITS, NUM_COLS,
+    NUM_LOOKUPS,
+};
+use crate::constants::{LOOKUP_TABLE, MDS, NUM_BARS, NUM_ROUNDS, ROUND_CONSTANTS, STATE_SIZE};
+use permutation_air::lookup::permuted_cols;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+
+// Represent a row of the preimage
+#[derive(Debug, Clone, Default)]
+pub struct Row<Field: RichField> {
+    pub preimage: [Field; STATE_SIZE],
+}
+
+/// The Bars layer: every byte of the canonical form of the first elements
+/// goes through the Bar S-box `table`
+fn bars<F: RichField>(state: &[F; STATE_SIZE], table: &[u8; 256]) -> [F; STATE_SIZE] {
+    let mut state = *state;
+    for s in &mut state[..NUM_BARS] {
+        let bytes = s.to_canonical_u64().to_le_bytes();
+        *s = F::from_noncanonical_u64(u64::from_le_bytes(bytes.map(|byte| table[byte as usize])));
+    }
+    state
+}
+
+/// The Bricks layer: a type-3 Feistel network with squares
+fn bricks<F: Field>(state: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        if i == 0 {
+            state[0]
+        } else {
+            state[i] + state[i - 1].square()
+        }
+    })
+}
+
+/// The Concrete layer: the circulant MDS matrix and the round constants
+pub(crate) fn concrete<F: Field>(
+    state: &[F; STATE_SIZE],
+    round_constants: &[u64; STATE_SIZE],
+) -> [F; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        (0..STATE_SIZE).fold(F::from_canonical_u64(round_constants[i]), |acc, j| {
+            acc + state[j] * F::from_canonical_u64(MDS[(j + STATE_SIZE - i) % STATE_SIZE])
+        })
+    })
+}
+
+/// Round `r`, after the initial Concrete layer
+fn round<F: RichField>(state: &[F; STATE_SIZE], r: usize, table: &[u8; 256]) -> [F; STATE_SIZE] {
+    concrete(&bricks(&bars(state, table)), &ROUND_CONSTANTS[r + 1])
+}
+
+/// The Monolith-64 permutation
+#[must_use]
+pub fn permute<F: RichField>(preimage: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    let state = concrete(preimage, &ROUND_CONSTANTS[0]);
+    (0..NUM_ROUNDS).fold(state, |state, r| round(&state, r, &LOOKUP_TABLE))
+}
+
+/// Fill the rows of the rounds of one permutation starting at `row`
+fn fill_permutation_rows<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    preimage: &[F; STATE_SIZE],
+    table: &[u8; 256],
+) {
+    for (j, &value) in preimage.iter().enumerate() {
+        trace[COL_INPUT_START + j][row] = value;
+    }
+
+    let mut state = concrete(preimage, &ROUND_CONSTANTS[0]);
+    for r in 0..NUM_ROUNDS {
+        let row = row + r;
+        trace[COL_STEP_START + r][row] = F::ONE;
+        for (j, &value) in state.iter().enumerate() {
+            trace[COL_STATE_START + j][row] = value;
+        }
+        for i in 0..NUM_BARS {
+            let value = state[i].to_canonical_u64();
+            for (k, byte) in value.to_le_bytes().into_iter().enumerate() {
+                trace[col_input_bytes(i) + k][row] = F::from_canonical_u8(byte);
+                trace[col_output_bytes(i) + k][row] = F::from_canonical_u8(table[byte as usize]);
+            }
+            trace[col_canonical_inv(i)][row] = (F::from_canonical_u64(value >> 32)
+                - F::from_canonical_u32(u32::MAX))
+            .try_inverse()
+            .unwrap_or(F::ZERO);
+        }
+        state = round(&state, r, table);
+        for (j, &value) in state.iter().enumerate() {
+            trace[COL_OUTPUT_START + j][row] = value;
+        }
+    }
+}
+
+/// Fill the lookup table `table` and the permuted columns of every lookup
+fn fill_lookups<F: RichField>(trace: &mut [Vec<F>], table: &[u8; 256]) {
+    let trace_len = trace[0].len();
+    for row in 0..trace_len {
+        let x = row.min(table.len() - 1);
+        trace[COL_TABLE_INPUT][row] = F::from_canonical_usize(x);
+        trace[COL_TABLE_OUTPUT][row] = F::from_canonical_u8(table[x]);
+        #[allow(clippy::cast_possible_truncation)]
+        let byte = x as u8;
+        let and = !byte.rotate_left(1) & byte.rotate_left(2) & byte.rotate_left(3);
+        for k in 0..NUM_BYTE_BITS {
+            trace[COL_TABLE_INPUT_BITS_START + k][row] = F::from_canonical_u8(byte >> k & 1);
+            trace[COL_TABLE_AND_BITS_START + k][row] = F::from_canonical_u8(and >> k & 1);
+        }
+    }
+    let table: Vec<(F, F)> = (0..trace_len)
+        .map(|row| (trace[COL_TABLE_INPUT][row], trace[COL_TABLE_OUTPUT][row]))
+        .collect();
+
+    for j in 0..NUM_LOOKUPS {
+        let [input, output] = col_lookup(j);
+        let inputs: Vec<(F, F)> = (0..trace_len)
+            .map(|row| (trace[input][row], trace[output][row]))
+            .collect();
+        let (permuted_inputs, permuted_table) = permuted_cols(&inputs, &table);
+
+        let [permuted_input, permuted_output, permuted_table_input, permuted_table_output] =
+            col_permuted(j);
+        for row in 0..trace_len {
+            trace[permuted_input][row] = permuted_inputs[row].0;
+            trace[permuted_output][row] = permuted_inputs[row].1;
+            trace[permuted_table_input][row] = permuted_table[row].0;
+            trace[permuted_table_output][row] = permuted_table[row].1;
+        }
+    }
+}
+
+/// Function to generate the Monolith trace: 6 rows per permutation, padded
+/// with all-zero rows to a power of 2 of at least 256 rows, the size of the
+/// lookup table.
+#[must_use]
+pub fn generate_monolith_trace<F: RichField>(step_rows: &[Row<F>]) -> [Vec<F>; NUM_COLS] {
+    generate_monolith_trace_with_table(step_rows, &LOOKUP_TABLE)
+}
+
+/// Generate the trace of a Monolith variant with the Bar S-box table `table`
+pub(crate) fn generate_monolith_trace_with_table<F: RichField>(
+    step_rows: &[Row<F>],
+    table: &[u8; 256],
+) -> [Vec<F>; NUM_COLS] {
+    let trace_len = (step_rows.len() * NUM_ROUNDS)
+        .next_power_of_two()
+        .max(LOOKUP_TABLE.len());
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; NUM_COLS];
+
+    for (i, row) in step_rows.iter().enumerate() {
+        fill_permutation_rows(&mut trace, i * NUM_ROUNDS, &row.preimage, table);
+    }
+    fill_lookups(&mut trace, table);
+
+    trace.try_into().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::columns::COL_OUTPUT_START;
+    use crate::constants::{LOOKUP_TABLE, NUM_ROUNDS, STATE_SIZE};
+    use crate::generation::{generate_monolith_trace, permute, Row};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, PrimeField64, Sample};
+
+    type F = GoldilocksField;
+
+    /// The Monolith-64 test vector of the reference implementation, with
+    /// 8-bit lookups
+    #[test]
+    fn reference_vector() {
+        let input = std::array::from_fn(F::from_canonical_usize);
+        let expected: [u64; STATE_SIZE] = [
+            5_867_581_605_548_782_913,
+            588_867_029_099_903_233,
+            6_043_817_495_575_026_667,
+            805_786_589_926_590_032,
+            9_919_982_299_747_097_782,
+            6_718_641_691_835_914_685,
+            7_951_881_005_429_661_950,
+            15_453_177_927_755_089_358,
+            974_633_365_445_157_727,
+            9_654_662_171_963_364_206,
+            6_281_307_445_101_925_412,
+            13_745_376_999_934_453_119,
+        ];
+        assert_eq!(permute(&input).map(|x| x.to_canonical_u64()), expected);
+    }
+
+    #[test]
+    fn lookup_table() {
+        // the Bar S-box is a permutation of the bytes that fixes 0 and 255,
+        // so it maps canonical elements to canonical elements
+        let mut sorted = LOOKUP_TABLE;
+        sorted.sort_unstable();
+        assert!(sorted.iter().enumerate().all(|(i, &x)| usize::from(x) == i));
+        assert_eq!(LOOKUP_TABLE[0], 0);
+        assert_eq!(LOOKUP_TABLE[255], 255);
+    }
+
+    #[test]
+    fn generate_monolith_trace_outputs() {
+        let step_rows: Vec<Row<F>> = (0..3)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let trace = generate_monolith_trace(&step_rows);
+        assert_eq!(trace[0].len(), 256);
+        for (i, row) in step_rows.iter().enumerate() {
+            let expected = permute(&row.preimage);
+            for j in 0..STATE_SIZE {
+                assert_eq!(
+                    trace[COL_OUTPUT_START + j][i * NUM_ROUNDS + NUM_ROUNDS - 1],
+                    expected[j]
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::columns::{
+    col_canonical_inv, col_input_bytes, col_lookup, col_output_bytes, col_permuted,
+    COL_INPUT_START, COL_OUTPUT_START, COL_STATE_START, COL_STEP_START, COL_TABLE_AND_BITS_START,
+    COL_TABLE_INPUT, COL_TABLE_INPUT_BITS_START, COL_TABLE_OUTPUT, NUM_BYTE_BITS, NUM_COLS,
+    NUM_LOOKUPS,
+};
+use crate::constants::{MDS, NUM_BARS, NUM_BYTES, NUM_ROUNDS, ROUND_CONSTANTS, STATE_SIZE};
+use permutation_air::lookup::{eval_lookups, eval_table_input, lookup_permutation_pairs};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::permutation::PermutationPair;
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+// linear layer (degree = 1)
+fn mds_constraints<P: PackedField>(state: &[P; STATE_SIZE]) -> [P; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        (0..STATE_SIZE).fold(P::ZEROS, |acc, j| {
+            acc + state[j] * P::Scalar::from_canonical_u64(MDS[(j + STATE_SIZE - i) % STATE_SIZE])
+        })
+    })
+}
+
+// degree: 2
+fn bricks_constraints<P: PackedField>(state: &[P; STATE_SIZE]) -> [P; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        if i == 0 {
+            state[0]
+        } else {
+            state[i] + state[i - 1].square()
+        }
+    })
+}
+
+/// Recompose the little-endian bytes starting at column `col`
+fn bytes_at<P: PackedField>(lv: &[P], col: usize, num_bytes: usize) -> P {
+    (0..num_bytes).rev().fold(P::ZEROS, |acc, k| {
+        acc * P::Scalar::from_canonical_u16(256) + lv[col + k]
+    })
+}
+
+/// Constraints of the round flags, returning 1 on the rows of every round
+/// but the last one
+fn eval_rounds<P: PackedField>(lv: &[P], nv: &[P], yield_constr: &mut ConstraintConsumer<P>) -> P {
+    // the round flags are one-hot or all zero on padding rows, start with
+    // round 0 and step through the rounds in order
+    let mut flag_sum = P::ZEROS;
+    for round in 0..NUM_ROUNDS {
+        let flag = lv[COL_STEP_START + round];
+        yield_constr.constraint(flag * (flag - P::ONES));
+        flag_sum += flag;
+        if round > 0 {
+            yield_constr.constraint_first_row(flag);
+        }
+        if round < NUM_ROUNDS - 1 {
+            yield_constr.constraint_transition(nv[COL_STEP_START + round + 1] - flag);
+        }
+    }
+    yield_constr.constraint(flag_sum * (flag_sum - P::ONES));
+    flag_sum - lv[COL_STEP_START + NUM_ROUNDS - 1]
+}
+
+/// Constraints of the Bar S-box of Bars element `i`, returning its output.
+/// The looked-up bytes are checked by the lookup argument.
+fn eval_bar<P: PackedField>(lv: &[P], i: usize, yield_constr: &mut ConstraintConsumer<P>) -> P {
+    // the input bytes are the element
+    let input = bytes_at(lv, col_input_bytes(i), NUM_BYTES);
+    yield_constr.constraint(input - lv[COL_STATE_START + i]);
+
+    // and below p: if the high half is 2^32 - 1, the low half is zero
+    let lo = bytes_at(lv, col_input_bytes(i), NUM_BYTES / 2);
+    let hi = bytes_at(lv, col_input_bytes(i) + NUM_BYTES / 2, NUM_BYTES / 2);
+    let hi_diff = hi - P::Scalar::from_canonical_u32(u32::MAX);
+    yield_constr.constraint(lo * (P::ONES - hi_diff * lv[col_canonical_inv(i)]));
+
+    bytes_at(lv, col_output_bytes(i), NUM_BYTES)
+}
+
+/// Constraints of the lookup table: it holds every byte `x` and its image
+/// `bar(x)`, computed on the bits of `x`
+fn eval_table<P: PackedField>(lv: &[P], nv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    eval_table_input(lv, nv, COL_TABLE_INPUT, 255, yield_constr);
+
+    // the input is the sum of its bits
+    let bit = |k: usize| lv[COL_TABLE_INPUT_BITS_START + k % NUM_BYTE_BITS];
+    let mut input = P::ZEROS;
+    for k in (0..NUM_BYTE_BITS).rev() {
+        yield_constr.constraint(bit(k) * (bit(k) - P::ONES));
+        input = input * P::Scalar::TWO + bit(k);
+    }
+    yield_constr.constraint(input - lv[COL_TABLE_INPUT]);
+
+    // bit k of `x.rotate_left(r)` is bit k - r of x, and the image is
+    // `(x ^ !x.rotate_left(1) & x.rotate_left(2) & x.rotate_left(3))`
+    // rotated left by 1
+    let mut output = P::ZEROS;
+    for k in 0..NUM_BYTE_BITS {
+        let and = lv[COL_TABLE_AND_BITS_START + k];
+        let (x1, x2, x3) = (bit(k + 7), bit(k + 6), bit(k + 5));
+        yield_constr.constraint(and - (P::ONES - x1) * x2 * x3);
+        let xor = bit(k) + and - bit(k) * and * P::Scalar::TWO;
+        // bit k of the image is bit k - 1 of the xor
+        output += xor * P::Scalar::from_canonical_u8(1 << ((k + 1) % NUM_BYTE_BITS));
+    }
+    yield_constr.constraint(output - lv[COL_TABLE_OUTPUT]);
+}
+
+/// Constraints of the Monolith table laid out as in `columns.rs`
+fn eval_monolith<P: PackedField>(lv: &[P], nv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    let not_last_round = eval_rounds(lv, nv, yield_constr);
+
+    // the first round starts from the initial Concrete layer, whose round
+    // constants are zero
+    let step0 = lv[COL_STEP_START];
+    let input: [P; STATE_SIZE] = lv[COL_INPUT_START..COL_INPUT_START + STATE_SIZE]
+        .try_into()
+        .unwrap();
+    for (i, value) in mds_constraints(&input).into_iter().enumerate() {
+        yield_constr.constraint(step0 * (lv[COL_STATE_START + i] - value));
+    }
+
+    // Bars, Bricks and Concrete with the constants of the round
+    let bars_state: [P; STATE_SIZE] = std::array::from_fn(|i| {
+        if i < NUM_BARS {
+            eval_bar(lv, i, yield_constr)
+        } else {
+            lv[COL_STATE_START + i]
+        }
+    });
+    let state = mds_constraints(&bricks_constraints(&bars_state));
+    for (i, &value) in state.iter().enumerate() {
+        let round_constant = (0..NUM_ROUNDS).fold(P::ZEROS, |acc, r| {
+            acc + lv[COL_STEP_START + r] * P::Scalar::from_canonical_u64(ROUND_CONSTANTS[r + 1][i])
+        });
+        yield_constr.constraint(lv[COL_OUTPUT_START + i] - value - round_constant);
+    }
+
+    // the next round starts from the output of this round
+    for i in 0..STATE_SIZE {
+        yield_constr.constraint_transition(
+            not_last_round * (nv[COL_STATE_START + i] - lv[COL_OUTPUT_START + i]),
+        );
+    }
+
+    eval_table(lv, nv, yield_constr);
+    for j in 0..NUM_LOOKUPS {
+        eval_lookups(lv, nv, col_permuted(j), yield_constr);
+    }
+}
+
+/// The Monolith-64 permutation table, one row per round and 6 rows per
+/// permutation.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct MonolithStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for MonolithStark<F, D> {
+    const COLUMNS: usize = NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_monolith(vars.local_values, vars.next_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+
+    fn permutation_pairs(&self) -> Vec<PermutationPair> {
+        (0..NUM_LOOKUPS)
+            .flat_map(|j| {
+                lookup_permutation_pairs(
+                    col_lookup(j),
+                    [COL_TABLE_INPUT, COL_TABLE_OUTPUT],
+                    col_permuted(j),
+                )
+            })
+            .collect()
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::LOOKUP_TABLE;
+    use crate::generation::{generate_monolith_trace, generate_monolith_trace_with_table, Row};
+    use crate::stark::{trace_to_poly_values, MonolithStark};
+    use anyhow::Result;
+    use permutation_air::conformance::check_constraints;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = MonolithStark<F, D>;
+
+    #[test]
+    fn monolith_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+
+        let step_rows: Vec<Row<F>> = (0..12)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let stark = S::default();
+        let trace = generate_monolith_trace(&step_rows);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn forged_table_fails() {
+        // a table swapping two images gives a trace of another permutation
+        // whose lookups and rounds are consistent, so only the table
+        // constraints reject it
+        let mut table = LOOKUP_TABLE;
+        table.swap(1, 2);
+        let step_rows: Vec<Row<F>> = (0..12)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let stark = S::default();
+        let honest = generate_monolith_trace(&step_rows);
+        assert!(check_constraints(&stark, &honest, &[]).is_ok());
+        let forged = generate_monolith_trace_with_table(&step_rows, &table);
+        assert!(check_constraints(&stark, &forged, &[]).is_err());
+    }
+
+    #[test]
+    fn monolith_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
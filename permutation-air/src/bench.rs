@@ -0,0 +1,305 @@
+//! A benchmark harness for [`PermutationAir`] tables. For every trace height
+//! and `rate_bits` it measures native permutation throughput, trace
+//! generation, proving and verification times, the proof size and the peak
+//! heap usage, and writes the results as CSV.
+
+use crate::conformance::random_rows;
+use crate::generation::generate_trace;
+use crate::prover::{config_for_degree, proof_size_bytes};
+use crate::PermutationAir;
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::{GenericConfig, Hasher};
+use plonky2::util::timing::TimingTree;
+use starky::prover::prove;
+use starky::stark::Stark;
+use starky::verifier::verify_stark_proof;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting the peak number of allocated bytes. A
+/// bench binary installs it with `#[global_allocator]`; without it the peak
+/// memory of every record is 0.
+pub struct PeakAllocator;
+
+impl PeakAllocator {
+    /// Restart the peak from the current heap usage and return that usage
+    pub fn reset_peak() -> usize {
+        let allocated = ALLOCATED.load(Ordering::SeqCst);
+        PEAK.store(allocated, Ordering::SeqCst);
+        allocated
+    }
+
+    /// The peak heap usage since the last [`PeakAllocator::reset_peak`]
+    pub fn peak() -> usize {
+        PEAK.load(Ordering::SeqCst)
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+/// The sweep of a benchmark run
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    /// The base-2 logarithms of the trace heights
+    pub log_rows: RangeInclusive<usize>,
+    /// The FRI blowups to try, by default the minimum for the constraint
+    /// degree and the next ones. Values below the minimum are skipped, see
+    /// [`Sweep::skipped_rate_bits`].
+    pub rate_bits: Option<Vec<usize>>,
+    /// The number of permutations timed for the native throughput
+    pub native_permutations: usize,
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Self {
+            log_rows: 8..=20,
+            rate_bits: None,
+            native_permutations: 1 << 14,
+        }
+    }
+}
+
+impl Sweep {
+    /// The default sweep, overridden by the environment variables
+    /// `HASH_BENCH_LOG_ROWS` (`min..=max` or a single value),
+    /// `HASH_BENCH_RATE_BITS` (a comma-separated list) and
+    /// `HASH_BENCH_NATIVE_PERMUTATIONS`
+    #[must_use]
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(log_rows) = std::env::var("HASH_BENCH_LOG_ROWS") {
+            let (min, max) = log_rows.split_once("..=").unwrap_or((&log_rows, &log_rows));
+            config.log_rows = min.trim().parse().unwrap()..=max.trim().parse().unwrap();
+        }
+        if let Ok(rate_bits) = std::env::var("HASH_BENCH_RATE_BITS") {
+            config.rate_bits = Some(
+                rate_bits
+                    .split(',')
+                    .map(|bits| bits.trim().parse().unwrap())
+                    .collect(),
+            );
+        }
+        if let Ok(count) = std::env::var("HASH_BENCH_NATIVE_PERMUTATIONS") {
+            config.native_permutations = count.trim().parse().unwrap();
+        }
+        config
+    }
+
+    /// The blowups swept for a table of constraint degree `degree`
+    #[must_use]
+    pub fn rate_bits_for(&self, degree: usize) -> Vec<usize> {
+        let min_rate_bits = min_rate_bits(degree);
+        match &self.rate_bits {
+            Some(rate_bits) => rate_bits
+                .iter()
+                .copied()
+                .filter(|&bits| bits >= min_rate_bits)
+                .collect(),
+            None => (min_rate_bits..min_rate_bits + DEFAULT_NUM_RATE_BITS).collect(),
+        }
+    }
+
+    /// The requested blowups too small for a table of constraint degree
+    /// `degree`
+    #[must_use]
+    pub fn skipped_rate_bits(&self, degree: usize) -> Vec<usize> {
+        let min_rate_bits = min_rate_bits(degree);
+        self.rate_bits
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|&bits| bits < min_rate_bits)
+            .collect()
+    }
+}
+
+/// The number of blowups of the default sweep
+const DEFAULT_NUM_RATE_BITS: usize = 3;
+
+/// The smallest blowup for the constraint degree `degree`
+fn min_rate_bits(degree: usize) -> usize {
+    config_for_degree(degree).fri_config.rate_bits
+}
+
+/// The measurements of one table at one trace height and blowup
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub name: String,
+    pub log_rows: usize,
+    pub rate_bits: usize,
+    pub native_permutations_per_sec: f64,
+    pub trace_generation: Duration,
+    pub prove: Duration,
+    pub verify: Duration,
+    pub proof_size_bytes: usize,
+    /// The peak heap usage above the usage before trace generation
+    pub peak_memory_bytes: usize,
+}
+
+/// The CSV header matching [`Record::to_csv`]
+pub const CSV_HEADER: &str = "name,log_rows,rate_bits,native_permutations_per_sec,\
+trace_generation_ms,prove_ms,verify_ms,proof_size_bytes,peak_memory_bytes";
+
+impl Record {
+    /// The record as a CSV line, with times in milliseconds
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{:.0},{:.3},{:.3},{:.3},{},{}",
+            self.name,
+            self.log_rows,
+            self.rate_bits,
+            self.native_permutations_per_sec,
+            self.trace_generation.as_secs_f64() * 1e3,
+            self.prove.as_secs_f64() * 1e3,
+            self.verify.as_secs_f64() * 1e3,
+            self.proof_size_bytes,
+            self.peak_memory_bytes,
+        )
+    }
+}
+
+/// Write `records` as CSV with a header
+///
+/// # Errors
+/// Returns an error if writing fails.
+pub fn write_csv<W: Write>(records: &[Record], mut writer: W) -> std::io::Result<()> {
+    writeln!(writer, "{CSV_HEADER}")?;
+    for record in records {
+        writeln!(writer, "{}", record.to_csv())?;
+    }
+    Ok(())
+}
+
+/// Native permutations per second of `air`
+fn native_throughput<F: RichField, A: PermutationAir<F, WIDTH>, const WIDTH: usize>(
+    air: &A,
+    num_permutations: usize,
+) -> f64 {
+    let mut state = [F::ZERO; WIDTH];
+    let timer = Instant::now();
+    for _ in 0..num_permutations {
+        state = air.permute(&state);
+    }
+    std::hint::black_box(state);
+    #[allow(clippy::cast_precision_loss)]
+    let throughput = num_permutations as f64 / timer.elapsed().as_secs_f64();
+    throughput
+}
+
+/// Benchmark `stark` over the sweep of `config`, writing each record as a
+/// CSV line to `out` as soon as it is measured
+///
+/// # Errors
+/// Returns an error if proving fails, a proof does not verify or writing to
+/// `out` fails.
+pub fn run<F, C, S, const D: usize, const WIDTH: usize>(
+    name: &str,
+    stark: &S,
+    config: &Sweep,
+    out: &mut impl Write,
+) -> Result<Vec<Record>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + PermutationAir<F, WIDTH> + Clone,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    let native_permutations_per_sec = native_throughput(stark, config.native_permutations);
+    let rate_bits_sweep = config.rate_bits_for(stark.constraint_degree());
+
+    let mut records = Vec::new();
+    for log_rows in config.log_rows.clone() {
+        for &rate_bits in &rate_bits_sweep {
+            let mut stark_config = config_for_degree(stark.constraint_degree());
+            stark_config.fri_config.rate_bits = rate_bits;
+            let step_rows = random_rows(1 << log_rows);
+
+            let baseline = PeakAllocator::reset_peak();
+            let timer = Instant::now();
+            let trace: Vec<PolynomialValues<F>> = generate_trace(stark, &step_rows)
+                .into_iter()
+                .map(PolynomialValues::new)
+                .collect();
+            let trace_generation = timer.elapsed();
+
+            let timer = Instant::now();
+            let proof = prove::<F, C, S, D>(
+                stark.clone(),
+                &stark_config,
+                trace,
+                [F::ZERO; S::PUBLIC_INPUTS],
+                &mut TimingTree::default(),
+            )?;
+            let prove = timer.elapsed();
+            let proof_size_bytes = proof_size_bytes(&proof);
+
+            let timer = Instant::now();
+            verify_stark_proof(stark.clone(), proof, &stark_config)?;
+            let verify = timer.elapsed();
+
+            let record = Record {
+                name: name.to_string(),
+                log_rows,
+                rate_bits,
+                native_permutations_per_sec,
+                trace_generation,
+                prove,
+                verify,
+                proof_size_bytes,
+                peak_memory_bytes: PeakAllocator::peak().saturating_sub(baseline),
+            };
+            writeln!(out, "{}", record.to_csv())?;
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::Sweep;
+
+    #[test]
+    fn rate_bits_follow_the_constraint_degree() {
+        // degree 7 needs rate_bits 3 and degree 3 only 1
+        let sweep = Sweep::default();
+        assert_eq!(sweep.rate_bits_for(7), [3, 4, 5]);
+        assert_eq!(sweep.rate_bits_for(3), [1, 2, 3]);
+        assert!(sweep.skipped_rate_bits(7).is_empty());
+
+        let sweep = Sweep {
+            rate_bits: Some(vec![1, 2, 3, 4]),
+            ..Sweep::default()
+        };
+        assert_eq!(sweep.rate_bits_for(7), [3, 4]);
+        assert_eq!(sweep.skipped_rate_bits(7), [1, 2]);
+        assert!(sweep.skipped_rate_bits(3).is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+//! A test suite every [`PermutationAir`] table should pass: the trace holds
+//! the native permutation, the constraints have the declared degree and
+//! random traces prove and verify.
+
+use crate::generation::{generate_trace, Row};
+use crate::prover::{config_for_degree, prove_and_verify};
+use crate::PermutationAir;
+use anyhow::{ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::{GenericConfig, Hasher};
+use plonky2::util::log2_strict;
+use starky::constraint_consumer::ConstraintConsumer;
+use starky::stark::Stark;
+use starky::stark_testing::test_stark_low_degree;
+use starky::vars::StarkEvaluationVars;
+
+/// `num_rows` random permutation inputs
+#[must_use]
+pub fn random_rows<F: RichField, const WIDTH: usize>(num_rows: usize) -> Vec<Row<F, WIDTH>> {
+    (0..num_rows)
+        .map(|_| Row {
+            preimage: F::rand_array(),
+        })
+        .collect()
+}
+
+/// The trace has one row per permutation, padded to a power of 2 by
+/// repeating the last row, and each row holds its input and the native
+/// permutation of it
+///
+/// # Errors
+/// Returns an error naming the first mismatching row and position.
+pub fn check_trace<F: RichField, A: PermutationAir<F, WIDTH>, const WIDTH: usize>(
+    air: &A,
+    num_rows: usize,
+) -> Result<()> {
+    let step_rows = random_rows(num_rows);
+    let trace = generate_trace(air, &step_rows);
+    ensure!(
+        trace.len() == A::NUM_COLS,
+        "expected {} columns",
+        A::NUM_COLS
+    );
+
+    let trace_len = num_rows.next_power_of_two();
+    for (i, row) in step_rows
+        .iter()
+        .chain(std::iter::repeat(step_rows.last().unwrap()))
+        .take(trace_len)
+        .enumerate()
+    {
+        let output = air.permute(&row.preimage);
+        for j in 0..WIDTH {
+            ensure!(
+                trace[A::INPUT_START + j][i] == row.preimage[j],
+                "input mismatch at row {i}, position {j}"
+            );
+            ensure!(
+                trace[A::OUTPUT_START + j][i] == output[j],
+                "output mismatch at row {i}, position {j}"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Evaluate the constraints of `stark` on every row of `trace`, without its
+/// permutation arguments. The prover cannot prove a trace that fails this
+/// check, so tests of forged traces use it instead of a proof.
+///
+/// # Errors
+/// Returns an error naming the first row where a constraint does not vanish.
+pub fn check_constraints<F, S, const D: usize>(
+    stark: &S,
+    trace: &[Vec<F>],
+    public_inputs: &[F; S::PUBLIC_INPUTS],
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+{
+    ensure!(trace.len() == S::COLUMNS, "expected {} columns", S::COLUMNS);
+    let trace_len = trace[0].len();
+    ensure!(
+        trace_len.is_power_of_two(),
+        "trace length {trace_len} is not a power of two"
+    );
+
+    // constraints are combined with a random challenge, as in the prover
+    let alpha = F::rand();
+    let generator = F::primitive_root_of_unity(log2_strict(trace_len));
+    let last = generator.inverse();
+    let row = |i: usize| -> [F; S::COLUMNS] { std::array::from_fn(|col| trace[col][i]) };
+    for i in 0..trace_len {
+        let local_values = row(i);
+        let next_values = row((i + 1) % trace_len);
+        let vars = StarkEvaluationVars {
+            local_values: &local_values,
+            next_values: &next_values,
+            public_inputs,
+        };
+        let mut consumer = ConstraintConsumer::new(
+            vec![alpha],
+            generator.exp_u64(i as u64) - last,
+            F::from_bool(i == 0),
+            F::from_bool(i + 1 == trace_len),
+        );
+        stark.eval_packed_base(vars, &mut consumer);
+        ensure!(
+            consumer.accumulators()[0] == F::ZERO,
+            "constraints do not hold on row {i}"
+        );
+    }
+    Ok(())
+}
+
+/// Run the whole suite on `stark`: the trace check, the degree check, the
+/// constraint check and a proof of `num_rows` random permutations with a
+/// blowup large enough for the constraint degree
+///
+/// # Errors
+/// Returns an error if a check fails or the proof does not verify.
+pub fn test_permutation_air<F, C, S, const D: usize, const WIDTH: usize>(
+    stark: &S,
+    num_rows: usize,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + PermutationAir<F, WIDTH> + Clone,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    check_trace(stark, num_rows)?;
+    test_stark_low_degree(stark.clone())?;
+
+    let config = config_for_degree(stark.constraint_degree());
+    let trace = generate_trace(stark, &random_rows(num_rows));
+    check_constraints(stark, &trace, &[F::ZERO; S::PUBLIC_INPUTS])?;
+    prove_and_verify::<F, C, S, D>(stark.clone(), &config, trace)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::conformance::test_permutation_air;
+    use crate::PermutationAir;
+    use anyhow::Result;
+    use plonky2::field::extension::{Extendable, FieldExtension};
+    use plonky2::field::packed::PackedField;
+    use plonky2::hash::hash_types::RichField;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+    use starky::stark::Stark;
+    use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+    use std::marker::PhantomData;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// The permutation `(a, b) -> ((a + b)^7, a)` with the input in columns
+    /// 0..2 and the output in columns 2..4
+    #[derive(Copy, Clone, Default)]
+    struct ToyStark<F, const D: usize> {
+        _f: PhantomData<F>,
+    }
+
+    impl<F: RichField, const D: usize> PermutationAir<F, 2> for ToyStark<F, D> {
+        const NUM_COLS: usize = 4;
+        const INPUT_START: usize = 0;
+        const OUTPUT_START: usize = 2;
+
+        fn permute(&self, input: &[F; 2]) -> [F; 2] {
+            [(input[0] + input[1]).exp_u64(7), input[0]]
+        }
+
+        fn generate_row_witness(&self, input: &[F; 2]) -> Vec<F> {
+            let output = self.permute(input);
+            vec![input[0], input[1], output[0], output[1]]
+        }
+
+        fn eval<FE, P, const D2: usize>(&self, lv: &[P], yield_constr: &mut ConstraintConsumer<P>)
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let sum = lv[0] + lv[1];
+            let power = (0..7).fold(P::ONES, |acc, _| acc * sum);
+            yield_constr.constraint(lv[2] - power);
+            yield_constr.constraint(lv[3] - lv[0]);
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for ToyStark<F, D> {
+        const COLUMNS: usize = 4;
+        const PUBLIC_INPUTS: usize = 0;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            self.eval(vars.local_values, yield_constr);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            7
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            _builder: &mut CircuitBuilder<F, D>,
+            _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+            _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn toy_conformance() -> Result<()> {
+        test_permutation_air::<F, C, _, D, 2>(&ToyStark::<F, D>::default(), 12)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::PermutationAir;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+
+// Represent a row of the preimage
+#[derive(Debug, Clone)]
+pub struct Row<F: RichField, const WIDTH: usize> {
+    pub preimage: [F; WIDTH],
+}
+
+impl<F: RichField, const WIDTH: usize> Default for Row<F, WIDTH> {
+    fn default() -> Self {
+        Self {
+            preimage: [F::ZERO; WIDTH],
+        }
+    }
+}
+
+/// Pad the trace to a power of 2.
+#[must_use]
+pub fn pad_trace<F: RichField>(mut trace: Vec<Vec<F>>) -> Vec<Vec<F>> {
+    let ext_trace_len = trace[0].len().next_power_of_two();
+
+    // All columns have their last value duplicated.
+    for row in &mut trace {
+        row.resize(ext_trace_len, *row.last().unwrap());
+    }
+
+    trace
+}
+
+/// Write the row witness of the permutation of `preimage` into a
+/// column-major trace
+pub fn fill_permutation_row<F: RichField, A: PermutationAir<F, WIDTH>, const WIDTH: usize>(
+    air: &A,
+    trace: &mut [Vec<F>],
+    row: usize,
+    preimage: &[F; WIDTH],
+) {
+    for (column, value) in trace.iter_mut().zip(air.generate_row_witness(preimage)) {
+        column[row] = value;
+    }
+}
+
+/// Generate the column-major trace of `air`: one row per permutation, padded
+/// to a power of 2 by repeating the last row.
+#[must_use]
+pub fn generate_trace<F: RichField, A: PermutationAir<F, WIDTH>, const WIDTH: usize>(
+    air: &A,
+    step_rows: &[Row<F, WIDTH>],
+) -> Vec<Vec<F>> {
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; step_rows.len()]; A::NUM_COLS];
+    for (i, row) in step_rows.iter().enumerate() {
+        fill_permutation_row(air, &mut trace, i, &row.preimage);
+    }
+
+    pad_trace(trace)
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
@@ -0,0 +1,52 @@
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+#![feature(stmt_expr_attributes)]
+#![feature(no_coverage)]
+#![feature(register_tool)]
+#![register_tool(tarpaulin)]
+#![deny(clippy::pedantic)]
+#![deny(clippy::cargo)]
+#![allow(clippy::missing_panics_doc)]
+
+pub mod bench;
+pub mod conformance;
+pub mod generation;
+pub mod lookup;
+pub mod prover;
+
+use plonky2::field::extension::FieldExtension;
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use starky::constraint_consumer::ConstraintConsumer;
+
+/// A permutation of `WIDTH` field elements arithmetized in a single trace row.
+///
+/// The row holds the input at `INPUT_START`, the output at `OUTPUT_START`
+/// and whatever intermediate state the constraints need, and the constraints
+/// only read the local row. Tables embedding the permutation at an offset,
+/// such as a sponge, call [`PermutationAir::eval`] on a slice of their row.
+#[allow(clippy::module_name_repetitions)]
+pub trait PermutationAir<F: RichField, const WIDTH: usize> {
+    /// The number of columns of a row
+    const NUM_COLS: usize;
+
+    /// The starting point of the input columns
+    const INPUT_START: usize;
+
+    /// The starting point of the output columns
+    const OUTPUT_START: usize;
+
+    /// The native permutation
+    fn permute(&self, input: &[F; WIDTH]) -> [F; WIDTH];
+
+    /// The row of `NUM_COLS` values proving the permutation of `input`
+    fn generate_row_witness(&self, input: &[F; WIDTH]) -> Vec<F>;
+
+    /// Constraints of a single permutation laid out as in
+    /// [`PermutationAir::generate_row_witness`], starting at the first column
+    /// of `lv`
+    fn eval<FE, P, const D2: usize>(&self, lv: &[P], yield_constr: &mut ConstraintConsumer<P>)
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+}
@@ -0,0 +1,162 @@
+//! A lookup argument for pairs of columns, in the style of the Halo2 lookup
+//! argument: the looked-up pairs are sorted into permuted columns, the table
+//! is permuted so that every first occurrence of a pair sits next to its
+//! table entry, and permutation arguments tie both to the original columns.
+//!
+//! The table is a function of its first column, which lets the constraints
+//! compare the first columns only to decide whether a row repeats the row
+//! above or starts a new table entry.
+
+use plonky2::field::packed::PackedField;
+use plonky2::field::types::{Field, PrimeField64};
+use starky::constraint_consumer::ConstraintConsumer;
+use starky::permutation::PermutationPair;
+use std::cmp::Ordering;
+
+/// The permutation pairs of a lookup of `lookup` in `table`, with the permuted
+/// columns `permuted` laid out as permuted input, permuted output, permuted
+/// table input and permuted table output
+#[must_use]
+#[allow(clippy::module_name_repetitions)]
+pub fn lookup_permutation_pairs(
+    lookup: [usize; 2],
+    table: [usize; 2],
+    permuted: [usize; 4],
+) -> [PermutationPair; 2] {
+    [
+        PermutationPair {
+            column_pairs: vec![(lookup[0], permuted[0]), (lookup[1], permuted[1])],
+        },
+        PermutationPair {
+            column_pairs: vec![(table[0], permuted[2]), (table[1], permuted[3])],
+        },
+    ]
+}
+
+/// Constraints of a lookup on the permuted columns `permuted`. Every permuted
+/// pair either repeats the pair above it or equals the permuted table entry
+/// next to it, and the first pair equals its table entry.
+pub fn eval_lookups<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    permuted: [usize; 4],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let [input, output, table_input, table_output] = permuted;
+
+    yield_constr.constraint_first_row(lv[input] - lv[table_input]);
+    yield_constr.constraint_first_row(lv[output] - lv[table_output]);
+
+    // a new input equals its table entry, output included
+    let new_input = nv[input] - lv[input];
+    let input_diff = nv[input] - nv[table_input];
+    let output_diff = nv[output] - nv[table_output];
+    yield_constr.constraint_transition(input_diff * new_input);
+    yield_constr.constraint_transition(output_diff * new_input);
+
+    // a repeated input repeats its output, or equals its table entry
+    let repeated_output = nv[output] - lv[output];
+    yield_constr.constraint_transition(repeated_output * input_diff);
+    yield_constr.constraint_transition(repeated_output * output_diff);
+}
+
+/// Constraints of the first column `col` of a table: it starts at 0 and
+/// steps by 0 or 1 up to `last`, so it holds every value in `0..=last`
+pub fn eval_table_input<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    col: usize,
+    last: u64,
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let step = nv[col] - lv[col];
+    yield_constr.constraint_first_row(lv[col]);
+    yield_constr.constraint_transition(step * (step - P::ONES));
+    yield_constr.constraint_last_row(lv[col] - P::Scalar::from_canonical_u64(last));
+}
+
+/// A looked-up pair or a table entry: an input and its output
+pub type Pair<F> = (F, F);
+
+/// Sort the looked-up pairs `inputs` and permute the pairs of `table`
+/// accordingly. Every input must be in the table, and both must have the same
+/// length.
+#[must_use]
+pub fn permuted_cols<F: PrimeField64>(
+    inputs: &[Pair<F>],
+    table: &[Pair<F>],
+) -> (Vec<Pair<F>>, Vec<Pair<F>>) {
+    let n = inputs.len();
+    assert_eq!(table.len(), n);
+
+    let key = |pair: &Pair<F>| pair.0.to_canonical_u64();
+    let mut sorted_inputs = inputs.to_vec();
+    sorted_inputs.sort_unstable_by_key(key);
+    let mut sorted_table = table.to_vec();
+    sorted_table.sort_unstable_by_key(key);
+
+    let mut permuted_table = vec![(F::ZERO, F::ZERO); n];
+    let mut unused_table_indices = Vec::new();
+    let mut unused_table_values = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < n {
+        match key(&sorted_inputs[i]).cmp(&key(&sorted_table[j])) {
+            Ordering::Greater => {
+                unused_table_values.push(sorted_table[j]);
+                j += 1;
+            }
+            Ordering::Less => {
+                unused_table_indices.push(i);
+                i += 1;
+            }
+            Ordering::Equal => {
+                permuted_table[i] = sorted_table[j];
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    unused_table_indices.extend(i..n);
+    unused_table_values.extend_from_slice(&sorted_table[j..]);
+    for (index, value) in unused_table_indices.into_iter().zip(unused_table_values) {
+        permuted_table[index] = value;
+    }
+
+    (sorted_inputs, permuted_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lookup::permuted_cols;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, PrimeField64};
+
+    type F = GoldilocksField;
+
+    fn pairs(values: &[(u64, u64)]) -> Vec<(F, F)> {
+        values
+            .iter()
+            .map(|&(a, b)| (F::from_canonical_u64(a), F::from_canonical_u64(b)))
+            .collect()
+    }
+
+    #[test]
+    fn permuted_columns() {
+        let table = pairs(&[(0, 5), (1, 7), (2, 9), (3, 4), (3, 4)]);
+        let inputs = pairs(&[(2, 9), (0, 5), (2, 9), (2, 9), (3, 4)]);
+        let (permuted_inputs, permuted_table) = permuted_cols(&inputs, &table);
+
+        assert_eq!(
+            permuted_inputs,
+            pairs(&[(0, 5), (2, 9), (2, 9), (2, 9), (3, 4)])
+        );
+        // first occurrences sit next to their table entry, and the table is
+        // a permutation of the original one
+        assert_eq!(permuted_table[0], permuted_inputs[0]);
+        assert_eq!(permuted_table[1], permuted_inputs[1]);
+        assert_eq!(permuted_table[4], permuted_inputs[4]);
+        let mut sorted_table = permuted_table;
+        sorted_table.sort_unstable_by_key(|pair| pair.0.to_canonical_u64());
+        assert_eq!(sorted_table, table);
+    }
+}
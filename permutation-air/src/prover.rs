@@ -0,0 +1,113 @@
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::{GenericConfig, Hasher};
+use plonky2::util::serialization::Write;
+use plonky2::util::timing::TimingTree;
+use starky::config::StarkConfig;
+use starky::proof::StarkProofWithPublicInputs;
+use starky::prover::prove;
+use starky::stark::Stark;
+use starky::verifier::verify_stark_proof;
+
+/// The fast config of starky with a blowup large enough for
+/// `constraint_degree`
+#[must_use]
+pub fn config_for_degree(constraint_degree: usize) -> StarkConfig {
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.cap_height = 0;
+    // the quotient has degree `constraint_degree - 1` times the trace length
+    let quotient_factor = constraint_degree.max(2) - 1;
+    config.fri_config.rate_bits = config
+        .fri_config
+        .rate_bits
+        .max(quotient_factor.next_power_of_two().trailing_zeros() as usize);
+    config
+}
+
+/// Prove a trace without public inputs
+///
+/// # Errors
+/// Returns an error if proving fails.
+pub fn prove_trace<F, C, S, const D: usize>(
+    stark: S,
+    config: &StarkConfig,
+    trace: Vec<Vec<F>>,
+) -> Result<StarkProofWithPublicInputs<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    let public_inputs = [F::ZERO; S::PUBLIC_INPUTS];
+    prove::<F, C, S, D>(
+        stark,
+        config,
+        trace.into_iter().map(PolynomialValues::new).collect(),
+        public_inputs,
+        &mut TimingTree::default(),
+    )
+}
+
+/// Prove a trace without public inputs and verify the proof
+///
+/// # Errors
+/// Returns an error if proving fails or the proof does not verify.
+pub fn prove_and_verify<F, C, S, const D: usize>(
+    stark: S,
+    config: &StarkConfig,
+    trace: Vec<Vec<F>>,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Clone,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    let proof = prove_trace::<F, C, S, D>(stark.clone(), config, trace)?;
+    verify_stark_proof(stark, proof, config)
+}
+
+/// The size of `proof` in the compact serialization of plonky2: caps,
+/// openings, the FRI proof and the public inputs, without lengths
+#[must_use]
+pub fn proof_size_bytes<F, C, const D: usize>(proof: &StarkProofWithPublicInputs<F, C, D>) -> usize
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let StarkProofWithPublicInputs {
+        proof,
+        public_inputs,
+    } = proof;
+    let openings = &proof.openings;
+    let mut buffer = Vec::new();
+    buffer.write_merkle_cap(&proof.trace_cap).unwrap();
+    if let Some(cap) = &proof.permutation_zs_cap {
+        buffer.write_merkle_cap(cap).unwrap();
+    }
+    buffer.write_merkle_cap(&proof.quotient_polys_cap).unwrap();
+    for values in [
+        Some(&openings.local_values),
+        Some(&openings.next_values),
+        openings.permutation_zs.as_ref(),
+        openings.permutation_zs_next.as_ref(),
+        Some(&openings.quotient_polys),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        buffer.write_field_ext_vec::<F, D>(values).unwrap();
+    }
+    buffer
+        .write_fri_proof::<F, C, D>(&proof.opening_proof)
+        .unwrap();
+    buffer.write_field_vec(public_inputs).unwrap();
+    buffer.len()
+}
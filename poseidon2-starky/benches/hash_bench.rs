@@ -0,0 +1,53 @@
+use permutation_air::bench::{run, write_csv, PeakAllocator, Sweep, CSV_HEADER};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use poseidon2_starky::columns::STATE_SIZE;
+use poseidon2_starky::params::{Poseidon2Params, ROUNDS_F_REDUCED, ROUNDS_P_REDUCED};
+use poseidon2_starky::stark::Poseidon2Stark;
+use starky::stark::Stark;
+use std::fs::File;
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// Sweep the trace heights and blowups of `Sweep::from_env` for both
+/// Poseidon2 presets. The records go to stdout as they are measured and to
+/// the CSV file `HASH_BENCH_OUTPUT`, `target/hash_bench.csv` by default.
+/// Requested blowups below the minimum of a table are reported on stderr.
+fn main() -> anyhow::Result<()> {
+    let sweep = Sweep::from_env();
+    let mut stdout = std::io::stdout();
+    println!("{CSV_HEADER}");
+
+    let poseidon2_128 = Poseidon2Stark::<F, D>::default();
+    let poseidon2_reduced =
+        Poseidon2Stark::<F, D, ROUNDS_F_REDUCED, ROUNDS_P_REDUCED>::new(Poseidon2Params::reduced());
+    for (name, degree) in [
+        ("poseidon2_128", poseidon2_128.constraint_degree()),
+        ("poseidon2_reduced", poseidon2_reduced.constraint_degree()),
+    ] {
+        let skipped = sweep.skipped_rate_bits(degree);
+        if !skipped.is_empty() {
+            eprintln!(
+                "{name}: skipping rate_bits {skipped:?}, below the minimum for degree {degree}"
+            );
+        }
+    }
+
+    let mut records =
+        run::<F, C, _, D, STATE_SIZE>("poseidon2_128", &poseidon2_128, &sweep, &mut stdout)?;
+    records.extend(run::<F, C, _, D, STATE_SIZE>(
+        "poseidon2_reduced",
+        &poseidon2_reduced,
+        &sweep,
+        &mut stdout,
+    )?);
+
+    let output =
+        std::env::var("HASH_BENCH_OUTPUT").unwrap_or_else(|_| "target/hash_bench.csv".to_string());
+    write_csv(&records, File::create(output)?)?;
+    Ok(())
+}
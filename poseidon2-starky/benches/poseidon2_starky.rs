@@ -36,7 +36,8 @@ fn bench_poseidon2_starky(c: &mut Criterion) {
         b.iter_batched(
             || trace_poly_values.clone(),
             |trace_poly_values| {
-                prove::<F, C, S, D>(stark, &config, trace_poly_values, [], &mut timing).unwrap();
+                prove::<F, C, S, D>(stark.clone(), &config, trace_poly_values, [], &mut timing)
+                    .unwrap();
             },
             criterion::BatchSize::SmallInput,
         );
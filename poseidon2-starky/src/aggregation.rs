@@ -0,0 +1,500 @@
+//! Aggregation of many starky proofs, such as one [`Poseidon2Stark`] proof
+//! per block, into a single plonky2 proof.
+//!
+//! The leaves of the aggregation tree recursively verify
+//! [`AggregationConfig::leaf_batch`] starky proofs each, and every inner node
+//! verifies [`AggregationConfig::arity`] proofs of the level below, until a
+//! single proof remains. Each starky proof contributes its *statement*, its
+//! public inputs followed by its trace cap, and each level exposes the
+//! statements of its children either concatenated or hashed, see
+//! [`PublicInputsMode`]. A level whose size is not a multiple of the batch
+//! size repeats its last proof, so the public inputs of the final proof are
+//! always [`expected_public_inputs`] of the statements.
+//!
+//! The circuits of the tree only depend on the stark, the trace length, the
+//! number of proofs and the [`AggregationConfig`], so a verifier derives the
+//! data to check the final proof with [`root_verifier_data`] rather
+//! than trusting data shipped along with the proof.
+//!
+//! [`Poseidon2Stark`]: crate::stark::Poseidon2Stark
+
+use anyhow::{ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData, VerifierCircuitData};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, GenericHashOut, Hasher};
+use plonky2::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use starky::config::StarkConfig;
+use starky::proof::{
+    StarkOpeningSetTarget, StarkProofTarget, StarkProofWithPublicInputs,
+    StarkProofWithPublicInputsTarget,
+};
+use starky::recursive_verifier::{
+    add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target, verify_stark_proof_circuit,
+};
+use starky::stark::Stark;
+use starky::verifier::verify_stark_proof;
+
+/// How a level of the aggregation tree exposes the public inputs of the
+/// proofs it verifies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicInputsMode {
+    /// The public inputs of the children, concatenated
+    Concatenate,
+    /// The hash of the concatenated public inputs of the children, with the
+    /// hasher of the plonky2 config
+    Hash,
+}
+
+/// The shape of an aggregation tree
+#[derive(Debug, Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct AggregationConfig {
+    /// The config of every plonky2 circuit of the tree
+    pub circuit_config: CircuitConfig,
+    /// The number of starky proofs verified by a leaf
+    pub leaf_batch: usize,
+    /// The number of proofs verified by an inner node
+    pub arity: usize,
+    pub public_inputs: PublicInputsMode,
+}
+
+impl Default for AggregationConfig {
+    fn default() -> Self {
+        Self {
+            circuit_config: CircuitConfig::standard_recursion_config(),
+            leaf_batch: 4,
+            arity: 2,
+            public_inputs: PublicInputsMode::Hash,
+        }
+    }
+}
+
+/// The root of an aggregation tree: a single plonky2 proof
+#[allow(clippy::module_name_repetitions)]
+pub struct AggregatedProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    pub proof: ProofWithPublicInputs<F, C, D>,
+    /// The number of aggregated starky proofs
+    pub num_proofs: usize,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    AggregatedProof<F, C, D>
+{
+    /// Verify the proof against `verifier_data`, as returned by
+    /// [`root_verifier_data`], and check that its public inputs are
+    /// [`expected_public_inputs`] of `statements`
+    ///
+    /// # Errors
+    /// Returns an error if the proof does not verify or does not aggregate
+    /// `statements`.
+    pub fn verify(
+        &self,
+        statements: &[Vec<F>],
+        config: &AggregationConfig,
+        verifier_data: &VerifierCircuitData<F, C, D>,
+    ) -> Result<()>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        ensure!(
+            statements.len() == self.num_proofs,
+            "expected {} statements, got {}",
+            self.num_proofs,
+            statements.len()
+        );
+        ensure!(
+            self.proof.public_inputs == expected_public_inputs::<F, C, D>(statements, config),
+            "public inputs do not match the statements"
+        );
+        verifier_data.verify(self.proof.clone())
+    }
+}
+
+/// The statement of a starky proof: its public inputs followed by its trace
+/// cap
+#[must_use]
+pub fn statement<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    proof: &StarkProofWithPublicInputs<F, C, D>,
+) -> Vec<F> {
+    let trace_cap = proof
+        .proof
+        .trace_cap
+        .0
+        .iter()
+        .flat_map(GenericHashOut::to_vec);
+    proof
+        .public_inputs
+        .iter()
+        .copied()
+        .chain(trace_cap)
+        .collect()
+}
+
+/// Split `items` into groups of `size`, repeating the last item to fill the
+/// last group
+fn padded_chunks<T: Clone>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    items
+        .chunks(size)
+        .map(|chunk| {
+            let mut chunk = chunk.to_vec();
+            chunk.resize(size, chunk[chunk.len() - 1].clone());
+            chunk
+        })
+        .collect()
+}
+
+/// The public inputs of a node whose children expose `children`
+fn combine<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    children: &[Vec<F>],
+    mode: PublicInputsMode,
+) -> Vec<F>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let inputs = children.concat();
+    match mode {
+        PublicInputsMode::Concatenate => inputs,
+        PublicInputsMode::Hash => C::Hasher::hash_no_pad(&inputs).elements.to_vec(),
+    }
+}
+
+/// In-circuit version of [`combine`], registering the result as the public
+/// inputs of the circuit
+fn register_combined<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    children: &[Vec<Target>],
+    mode: PublicInputsMode,
+) where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let inputs = children.concat();
+    match mode {
+        PublicInputsMode::Concatenate => builder.register_public_inputs(&inputs),
+        PublicInputsMode::Hash => {
+            let hash = builder.hash_n_to_hash_no_pad::<C::Hasher>(inputs);
+            builder.register_public_inputs(&hash.elements);
+        }
+    }
+}
+
+/// The public inputs of the root of the aggregation tree of proofs with
+/// `statements`
+#[must_use]
+pub fn expected_public_inputs<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    statements: &[Vec<F>],
+    config: &AggregationConfig,
+) -> Vec<F>
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let mut level: Vec<Vec<F>> = padded_chunks(statements, config.leaf_batch)
+        .iter()
+        .map(|batch| combine::<F, C, D>(batch, config.public_inputs))
+        .collect();
+    while level.len() > 1 {
+        level = padded_chunks(&level, config.arity)
+            .iter()
+            .map(|children| combine::<F, C, D>(children, config.public_inputs))
+            .collect();
+    }
+    level.remove(0)
+}
+
+/// `StarkProofWithPublicInputsTarget` is not `Clone`, but the recursive
+/// verifier consumes it while the witness still needs it
+fn clone_stark_proof_target<const D: usize>(
+    target: &StarkProofWithPublicInputsTarget<D>,
+) -> StarkProofWithPublicInputsTarget<D> {
+    let proof = &target.proof;
+    let openings = &proof.openings;
+    StarkProofWithPublicInputsTarget {
+        proof: StarkProofTarget {
+            trace_cap: proof.trace_cap.clone(),
+            permutation_zs_cap: proof.permutation_zs_cap.clone(),
+            quotient_polys_cap: proof.quotient_polys_cap.clone(),
+            openings: StarkOpeningSetTarget {
+                local_values: openings.local_values.clone(),
+                next_values: openings.next_values.clone(),
+                permutation_zs: openings.permutation_zs.clone(),
+                permutation_zs_next: openings.permutation_zs_next.clone(),
+                quotient_polys: openings.quotient_polys.clone(),
+            },
+            opening_proof: proof.opening_proof.clone(),
+        },
+        public_inputs: target.public_inputs.clone(),
+    }
+}
+
+/// The leaf circuit, verifying `config.leaf_batch` starky proofs of
+/// `degree_bits`
+pub(crate) fn leaf_circuit<F, C, S, const D: usize>(
+    stark: &S,
+    stark_config: &StarkConfig,
+    degree_bits: usize,
+    config: &AggregationConfig,
+) -> (
+    CircuitData<F, C, D>,
+    Vec<StarkProofWithPublicInputsTarget<D>>,
+)
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Clone,
+    C::Hasher: AlgebraicHasher<F>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+{
+    let mut builder = CircuitBuilder::<F, D>::new(config.circuit_config.clone());
+    let targets: Vec<_> = (0..config.leaf_batch)
+        .map(|_| {
+            let target = add_virtual_stark_proof_with_pis(
+                &mut builder,
+                stark.clone(),
+                stark_config,
+                degree_bits,
+            );
+            verify_stark_proof_circuit::<F, C, S, D>(
+                &mut builder,
+                stark.clone(),
+                clone_stark_proof_target(&target),
+                stark_config,
+            );
+            target
+        })
+        .collect();
+    let statements: Vec<Vec<Target>> = targets
+        .iter()
+        .map(|target| {
+            let trace_cap = target
+                .proof
+                .trace_cap
+                .0
+                .iter()
+                .flat_map(|hash| hash.elements);
+            target
+                .public_inputs
+                .iter()
+                .copied()
+                .chain(trace_cap)
+                .collect()
+        })
+        .collect();
+    register_combined::<F, C, D>(&mut builder, &statements, config.public_inputs);
+    (builder.build::<C>(), targets)
+}
+
+/// An inner node circuit, verifying `config.arity` proofs of `child`
+pub(crate) fn node_circuit<F, C, const D: usize>(
+    child: &VerifierCircuitData<F, C, D>,
+    config: &AggregationConfig,
+) -> (CircuitData<F, C, D>, Vec<ProofWithPublicInputsTarget<D>>)
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    let mut builder = CircuitBuilder::<F, D>::new(config.circuit_config.clone());
+    let verifier_data = builder.constant_verifier_data(&child.verifier_only);
+    let targets: Vec<_> = (0..config.arity)
+        .map(|_| {
+            let target = builder.add_virtual_proof_with_pis(&child.common);
+            builder.verify_proof::<C>(&target, &verifier_data, &child.common);
+            target
+        })
+        .collect();
+    let children: Vec<Vec<Target>> = targets
+        .iter()
+        .map(|target| target.public_inputs.clone())
+        .collect();
+    register_combined::<F, C, D>(&mut builder, &children, config.public_inputs);
+    (builder.build::<C>(), targets)
+}
+
+/// The number of leaves of the aggregation tree of `num_proofs` proofs
+fn num_leaves(num_proofs: usize, config: &AggregationConfig) -> Result<usize> {
+    ensure!(num_proofs > 0, "no proofs to aggregate");
+    ensure!(
+        config.leaf_batch > 0 && config.arity > 1,
+        "the leaf batch must be positive and the arity at least 2"
+    );
+    Ok((num_proofs + config.leaf_batch - 1) / config.leaf_batch)
+}
+
+/// The data to verify the root of the aggregation tree of `num_proofs` proofs
+/// of `stark` with `degree_bits`, derived without the proofs
+///
+/// # Errors
+/// Returns an error if there are no proofs or the batch sizes are zero.
+pub fn root_verifier_data<F, C, S, const D: usize>(
+    stark: &S,
+    stark_config: &StarkConfig,
+    degree_bits: usize,
+    num_proofs: usize,
+    config: &AggregationConfig,
+) -> Result<VerifierCircuitData<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Clone,
+    C::Hasher: AlgebraicHasher<F>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+{
+    let mut level = num_leaves(num_proofs, config)?;
+    let (leaf, _) = leaf_circuit::<F, C, S, D>(stark, stark_config, degree_bits, config);
+    let mut verifier_data = leaf.verifier_data();
+    while level > 1 {
+        verifier_data = node_circuit(&verifier_data, config).0.verifier_data();
+        level = (level + config.arity - 1) / config.arity;
+    }
+    Ok(verifier_data)
+}
+
+/// Aggregate `proofs` of `stark`, all with the same trace length, into a
+/// single plonky2 proof
+///
+/// # Errors
+/// Returns an error if there are no proofs, the batch sizes are zero, the
+/// trace lengths differ, a starky proof does not verify or proving fails.
+pub fn aggregate<F, C, S, const D: usize>(
+    stark: &S,
+    stark_config: &StarkConfig,
+    proofs: &[StarkProofWithPublicInputs<F, C, D>],
+    config: &AggregationConfig,
+) -> Result<AggregatedProof<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Clone,
+    C::Hasher: AlgebraicHasher<F>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    num_leaves(proofs.len(), config)?;
+    let degree_bits = proofs[0].proof.recover_degree_bits(stark_config);
+    for proof in proofs {
+        ensure!(
+            proof.proof.recover_degree_bits(stark_config) == degree_bits,
+            "all proofs must have the same trace length"
+        );
+        // a bad proof would only surface as an unsatisfiable witness
+        verify_stark_proof(stark.clone(), proof.clone(), stark_config)?;
+    }
+
+    let (mut data, targets) = leaf_circuit::<F, C, S, D>(stark, stark_config, degree_bits, config);
+    let mut level = padded_chunks(proofs, config.leaf_batch)
+        .iter()
+        .map(|batch| {
+            let mut witness = PartialWitness::new();
+            for (target, proof) in targets.iter().zip(batch) {
+                set_stark_proof_with_pis_target(&mut witness, target, proof);
+            }
+            data.prove(witness)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    while level.len() > 1 {
+        let (node, targets) = node_circuit(&data.verifier_data(), config);
+        level = padded_chunks(&level, config.arity)
+            .iter()
+            .map(|children| {
+                let mut witness = PartialWitness::new();
+                for (target, proof) in targets.iter().zip(children) {
+                    witness.set_proof_with_pis_target(target, proof);
+                }
+                node.prove(witness)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        data = node;
+    }
+
+    Ok(AggregatedProof {
+        proof: level.remove(0),
+        num_proofs: proofs.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aggregation::{
+        aggregate, root_verifier_data, statement, AggregationConfig, PublicInputsMode,
+    };
+    use crate::columns::STATE_SIZE;
+    use crate::stark::Poseidon2Stark;
+    use anyhow::Result;
+    use permutation_air::conformance::random_rows;
+    use permutation_air::generation::generate_trace;
+    use permutation_air::prover::{config_for_degree, prove_trace};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use starky::proof::StarkProofWithPublicInputs;
+    use starky::stark::Stark;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2Stark<F, D>;
+
+    /// Aggregate `num_blocks` proofs and verify the result against the data
+    /// derived for `num_blocks` and for `other_num_proofs` proofs
+    fn aggregate_blocks(
+        num_blocks: usize,
+        other_num_proofs: usize,
+        config: &AggregationConfig,
+    ) -> Result<bool> {
+        let stark = S::default();
+        let mut stark_config = config_for_degree(stark.constraint_degree());
+        // fewer queries keep the recursive verifier small in debug builds
+        stark_config.fri_config.num_query_rounds = 10;
+        let proofs = (0..num_blocks)
+            .map(|_| {
+                let trace = generate_trace(&stark, &random_rows::<F, STATE_SIZE>(8));
+                prove_trace::<F, C, S, D>(stark.clone(), &stark_config, trace)
+            })
+            .collect::<Result<Vec<StarkProofWithPublicInputs<F, C, D>>>>()?;
+        let statements: Vec<_> = proofs.iter().map(statement).collect();
+        let degree_bits = proofs[0].proof.recover_degree_bits(&stark_config);
+
+        let aggregated = aggregate(&stark, &stark_config, &proofs, config)?;
+        let verifier_data =
+            root_verifier_data(&stark, &stark_config, degree_bits, num_blocks, config)?;
+        aggregated.verify(&statements, config, &verifier_data)?;
+
+        let other_data =
+            root_verifier_data(&stark, &stark_config, degree_bits, other_num_proofs, config)?;
+        Ok(aggregated.verify(&statements, config, &other_data).is_ok())
+    }
+
+    #[test]
+    fn aggregate_tree_hashed() -> Result<()> {
+        // two leaves, the second one padded, and a root node
+        let config = AggregationConfig {
+            leaf_batch: 2,
+            ..AggregationConfig::default()
+        };
+        // the data of a single leaf, with the same public inputs, rejects
+        // the root
+        assert!(!aggregate_blocks(3, 2, &config)?);
+        Ok(())
+    }
+
+    #[test]
+    fn aggregate_single_leaf_concatenated() -> Result<()> {
+        let config = AggregationConfig {
+            public_inputs: PublicInputsMode::Concatenate,
+            ..AggregationConfig::default()
+        };
+        // any number of proofs up to the leaf batch shares the same circuit
+        assert!(aggregate_blocks(2, 4, &config)?);
+        Ok(())
+    }
+}
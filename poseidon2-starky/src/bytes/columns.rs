@@ -0,0 +1,27 @@
+use crate::bytes::RATE_BYTES;
+use crate::columns::NUM_COLS;
+
+/// The starting point of the permutation columns, laid out as in `crate::columns`
+pub(crate) const COL_PERMUTATION_START: usize = 0;
+
+/// 1 if the row absorbs the last block of the message, 0 otherwise
+pub(crate) const COL_IS_LAST_BLOCK: usize = COL_PERMUTATION_START + NUM_COLS; // 101
+
+/// 1 if the row comes after the message, 0 otherwise
+pub(crate) const COL_IS_PADDING: usize = COL_IS_LAST_BLOCK + 1; // 102
+
+/// The number of message bytes absorbed up to and including this row
+pub(crate) const COL_LENGTH: usize = COL_IS_PADDING + 1; // 103
+
+/// The starting point of the message bytes absorbed in this row
+pub(crate) const COL_BYTES_START: usize = COL_LENGTH + 1; // 104
+
+/// The starting point of the flags marking the bytes of the block that belong
+/// to the message rather than to its padding
+pub(crate) const COL_IS_MESSAGE_START: usize = COL_BYTES_START + RATE_BYTES; // 132
+
+/// The starting point of the little-endian bit decomposition of each byte
+pub(crate) const COL_BYTE_BITS_START: usize = COL_IS_MESSAGE_START + RATE_BYTES; // 160
+
+/// The total number of columns
+pub(crate) const BYTES_NUM_COLS: usize = COL_BYTE_BITS_START + RATE_BYTES * 8; // 384
@@ -0,0 +1,128 @@
+use crate::bytes::columns::{
+    BYTES_NUM_COLS, COL_BYTES_START, COL_BYTE_BITS_START, COL_IS_LAST_BLOCK, COL_IS_MESSAGE_START,
+    COL_IS_PADDING, COL_LENGTH, COL_PERMUTATION_START,
+};
+use crate::bytes::{pack_bytes, pad_bytes, DIGEST_SIZE, RATE, RATE_BYTES};
+use crate::columns::{COL_OUTPUT_START, STATE_SIZE};
+use crate::generation::poseidon2_air;
+use permutation_air::generation::fill_permutation_row;
+use plonky2::hash::hash_types::RichField;
+
+/// The number of public inputs: the digest and the message length in bytes
+pub const BYTES_PUBLIC_INPUTS: usize = DIGEST_SIZE + 1;
+
+/// Append the rows absorbing a single message to a column-major trace, and
+/// return the digest.
+fn fill_message_rows<F: RichField>(
+    trace: &mut [Vec<F>],
+    message: &[u8],
+    is_padding: bool,
+) -> [F; DIGEST_SIZE] {
+    let padded = pad_bytes(message);
+    let num_blocks = padded.len() / RATE_BYTES;
+    let mut state = [F::ZERO; STATE_SIZE];
+
+    let air = poseidon2_air();
+    for (block_index, block) in padded.chunks(RATE_BYTES).enumerate() {
+        let row = trace[0].len();
+        for col in trace.iter_mut() {
+            col.push(F::ZERO);
+        }
+
+        let limbs: Vec<F> = pack_bytes(block);
+        for j in 0..RATE {
+            state[j] += limbs[j];
+        }
+        fill_permutation_row(&air, &mut trace[COL_PERMUTATION_START..], row, &state);
+        for j in 0..STATE_SIZE {
+            state[j] = trace[COL_PERMUTATION_START + COL_OUTPUT_START + j][row];
+        }
+
+        let length = message.len().min((block_index + 1) * RATE_BYTES);
+        trace[COL_IS_LAST_BLOCK][row] = F::from_bool(!is_padding && block_index + 1 == num_blocks);
+        trace[COL_IS_PADDING][row] = F::from_bool(is_padding);
+        trace[COL_LENGTH][row] = F::from_canonical_usize(length);
+        for (j, &byte) in block.iter().enumerate() {
+            trace[COL_BYTES_START + j][row] = F::from_canonical_u8(byte);
+            trace[COL_IS_MESSAGE_START + j][row] =
+                F::from_bool(block_index * RATE_BYTES + j < message.len());
+            for k in 0..8 {
+                trace[COL_BYTE_BITS_START + 8 * j + k][row] = F::from_bool(byte >> k & 1 == 1);
+            }
+        }
+    }
+
+    state[..DIGEST_SIZE].try_into().unwrap()
+}
+
+/// Function to generate the trace hashing `message` with
+/// [`hash_bytes`](crate::bytes::hash_bytes), and its public inputs.
+///
+/// The trace is padded to a power of two with rows hashing the empty message,
+/// which are flagged as padding.
+#[must_use]
+pub fn generate_poseidon2_bytes_trace<F: RichField>(
+    message: &[u8],
+) -> ([Vec<F>; BYTES_NUM_COLS], [F; BYTES_PUBLIC_INPUTS]) {
+    let mut trace: Vec<Vec<F>> = vec![Vec::new(); BYTES_NUM_COLS];
+
+    let digest = fill_message_rows(&mut trace, message, false);
+    let ext_trace_len = trace[0].len().next_power_of_two();
+    while trace[0].len() < ext_trace_len {
+        fill_message_rows(&mut trace, &[], true);
+    }
+
+    let mut public_inputs = [F::ZERO; BYTES_PUBLIC_INPUTS];
+    public_inputs[..DIGEST_SIZE].copy_from_slice(&digest);
+    public_inputs[DIGEST_SIZE] = F::from_canonical_usize(message.len());
+
+    let trace = trace.try_into().unwrap_or_else(|v: Vec<Vec<F>>| {
+        panic!(
+            "Expected a Vec of length {} but it was {}",
+            BYTES_NUM_COLS,
+            v.len()
+        )
+    });
+    (trace, public_inputs)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bytes::columns::{COL_IS_LAST_BLOCK, COL_PERMUTATION_START};
+    use crate::bytes::generation::generate_poseidon2_bytes_trace;
+    use crate::bytes::{hash_bytes, DIGEST_SIZE, RATE_BYTES};
+    use crate::columns::COL_OUTPUT_START;
+    use plonky2::field::types::Field;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn digests_match_native_hash() {
+        for len in [0, 1, RATE_BYTES - 1, RATE_BYTES, 3 * RATE_BYTES + 5] {
+            let message: Vec<u8> = (0..len)
+                .map(|i| u8::try_from(i * 37 % 256).unwrap())
+                .collect();
+
+            let (trace, public_inputs) = generate_poseidon2_bytes_trace::<F>(&message);
+            assert!(trace[0].len().is_power_of_two());
+            assert_eq!(public_inputs[..DIGEST_SIZE], hash_bytes::<F>(&message));
+            assert_eq!(public_inputs[DIGEST_SIZE], F::from_canonical_usize(len));
+
+            // the digest sits in the row of the only last block
+            let last_rows: Vec<usize> = (0..trace[0].len())
+                .filter(|&row| trace[COL_IS_LAST_BLOCK][row] == F::ONE)
+                .collect();
+            assert_eq!(last_rows.len(), 1);
+            for j in 0..DIGEST_SIZE {
+                assert_eq!(
+                    trace[COL_PERMUTATION_START + COL_OUTPUT_START + j][last_rows[0]],
+                    public_inputs[j],
+                    "Mismatch at position {j}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Hashing of byte strings with the Poseidon2 permutation.
+//!
+//! Messages are padded with a single `0x01` byte followed by zeros up to a
+//! multiple of [`RATE_BYTES`], split into blocks of [`RATE`] limbs of
+//! [`BYTES_PER_LIMB`] little-endian bytes each, and absorbed into a sponge
+//! whose capacity starts at zero. Seven bytes always fit below the Goldilocks
+//! modulus, so packing is injective.
+#![allow(clippy::module_name_repetitions)]
+
+pub mod columns;
+pub mod generation;
+pub mod stark;
+
+use crate::columns::STATE_SIZE;
+use crate::generation::generate_outputs;
+use crate::params::POSEIDON2_PARAMS_128;
+use plonky2::hash::hash_types::RichField;
+
+/// The number of state elements absorbed per permutation
+pub const RATE: usize = 4;
+
+/// The number of bytes packed into one field element
+pub const BYTES_PER_LIMB: usize = 7;
+
+/// The number of message bytes absorbed per permutation
+pub const RATE_BYTES: usize = RATE * BYTES_PER_LIMB; // 28
+
+/// The number of field elements in a digest
+pub const DIGEST_SIZE: usize = 4;
+
+/// Pad a message with `0x01` and then zeros to a multiple of [`RATE_BYTES`].
+#[must_use]
+pub fn pad_bytes(message: &[u8]) -> Vec<u8> {
+    let mut padded = message.to_vec();
+    padded.push(1);
+    let padded_len = (padded.len() + RATE_BYTES - 1) / RATE_BYTES * RATE_BYTES;
+    padded.resize(padded_len, 0);
+    padded
+}
+
+/// Pack little-endian groups of [`BYTES_PER_LIMB`] bytes into field elements.
+#[must_use]
+pub fn pack_bytes<F: RichField>(bytes: &[u8]) -> Vec<F> {
+    bytes
+        .chunks(BYTES_PER_LIMB)
+        .map(|chunk| {
+            let limb = chunk
+                .iter()
+                .rev()
+                .fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte));
+            F::from_canonical_u64(limb)
+        })
+        .collect()
+}
+
+/// Hash a byte string natively. The result is the digest proved by
+/// [`stark::Poseidon2BytesStark`] in the row of the last message block.
+#[must_use]
+pub fn hash_bytes<F: RichField>(message: &[u8]) -> [F; DIGEST_SIZE] {
+    let limbs: Vec<F> = pack_bytes(&pad_bytes(message));
+    let mut state = [F::ZERO; STATE_SIZE];
+    for block in limbs.chunks(RATE) {
+        for (s, limb) in state.iter_mut().zip(block) {
+            *s += *limb;
+        }
+        state = generate_outputs(&POSEIDON2_PARAMS_128, &state);
+    }
+    state[..DIGEST_SIZE].try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::{hash_bytes, pack_bytes, pad_bytes, RATE_BYTES};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, PrimeField64};
+    use proptest::prelude::*;
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn padding_lengths() {
+        assert_eq!(pad_bytes(&[]).len(), RATE_BYTES);
+        assert_eq!(pad_bytes(&[0; RATE_BYTES - 1]).len(), RATE_BYTES);
+        assert_eq!(pad_bytes(&[0; RATE_BYTES]).len(), 2 * RATE_BYTES);
+        assert_eq!(pad_bytes(&[7, 8]), {
+            let mut expected = vec![7, 8, 1];
+            expected.resize(RATE_BYTES, 0);
+            expected
+        });
+    }
+
+    #[test]
+    fn packing_is_little_endian() {
+        let limbs: Vec<F> = pack_bytes(&[1, 2, 3, 4, 5, 6, 7, 0xff]);
+        assert_eq!(limbs[0], F::from_canonical_u64(0x0007_0605_0403_0201));
+        assert_eq!(limbs[1], F::from_canonical_u64(0xff));
+    }
+
+    #[test]
+    fn trailing_zeros_change_the_digest() {
+        assert_ne!(hash_bytes::<F>(&[]), hash_bytes::<F>(&[0]));
+        assert_ne!(hash_bytes::<F>(&[1]), hash_bytes::<F>(&[1, 0]));
+    }
+
+    proptest! {
+        #[test]
+        fn packed_limbs_fit_in_seven_bytes(bytes in prop::collection::vec(any::<u8>(), 0..100)) {
+            for limb in pack_bytes::<F>(&bytes) {
+                prop_assert!(limb.to_canonical_u64() < 1 << 56);
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::bytes::columns::{
+    BYTES_NUM_COLS, COL_BYTES_START, COL_BYTE_BITS_START, COL_IS_LAST_BLOCK, COL_IS_MESSAGE_START,
+    COL_IS_PADDING, COL_LENGTH, COL_PERMUTATION_START,
+};
+use crate::bytes::generation::BYTES_PUBLIC_INPUTS;
+use crate::bytes::{BYTES_PER_LIMB, DIGEST_SIZE, RATE, RATE_BYTES};
+use crate::columns::{COL_INPUT_START, COL_OUTPUT_START, STATE_SIZE};
+use crate::params::POSEIDON2_PARAMS_128;
+use crate::stark::eval_permutation;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+// degree: 1
+fn limb_constraints<F: RichField + Extendable<D>, const D: usize, FE, P, const D2: usize>(
+    row: &[P],
+) -> [P; RATE]
+where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let mut limbs = [P::ZEROS; RATE];
+
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        for k in 0..BYTES_PER_LIMB {
+            *limb += row[COL_BYTES_START + i * BYTES_PER_LIMB + k]
+                * FE::from_canonical_u64(1 << (8 * k));
+        }
+    }
+
+    limbs
+}
+
+/// A sponge over [`Poseidon2Stark`](crate::stark::Poseidon2Stark) rows that
+/// absorbs a range-checked and padded message, exposing its digest and its
+/// length in bytes as public inputs.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Poseidon2BytesStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Poseidon2BytesStark<F, D> {
+    const COLUMNS: usize = BYTES_NUM_COLS;
+    const PUBLIC_INPUTS: usize = BYTES_PUBLIC_INPUTS;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let lv = vars.local_values;
+        let nv = vars.next_values;
+        eval_permutation(
+            &POSEIDON2_PARAMS_128,
+            &lv[COL_PERMUTATION_START..],
+            yield_constr,
+        );
+
+        // the flags are boolean, the message starts the trace and ends in
+        // exactly one last block, after which every row is padding
+        let is_last = lv[COL_IS_LAST_BLOCK];
+        let is_padding = lv[COL_IS_PADDING];
+        let next_is_padding = nv[COL_IS_PADDING];
+        let is_message_block = P::ONES - is_last - is_padding;
+        yield_constr.constraint(is_last * (is_last - P::ONES));
+        yield_constr.constraint(is_padding * (is_padding - P::ONES));
+        yield_constr.constraint(is_last * is_padding);
+        yield_constr.constraint_first_row(is_padding);
+        yield_constr.constraint_transition(next_is_padding - is_last - is_padding);
+        yield_constr.constraint_last_row(is_message_block);
+
+        // every byte is the sum of its bits
+        for i in 0..RATE_BYTES {
+            let mut byte = P::ZEROS;
+            for k in 0..8 {
+                let bit = lv[COL_BYTE_BITS_START + 8 * i + k];
+                yield_constr.constraint(bit * (bit - P::ONES));
+                byte += bit * FE::from_canonical_u64(1 << k);
+            }
+            yield_constr.constraint(byte - lv[COL_BYTES_START + i]);
+        }
+
+        // the message flags are a boolean prefix, full in every block but the
+        // last, which ends with the padding: 0x01 after the last message byte,
+        // then zeros
+        let is_message = &lv[COL_IS_MESSAGE_START..COL_IS_MESSAGE_START + RATE_BYTES];
+        let mut num_message_bytes = P::ZEROS;
+        for i in 0..RATE_BYTES {
+            let previous = if i == 0 { P::ONES } else { is_message[i - 1] };
+            let padding_byte = previous - is_message[i];
+            yield_constr.constraint(is_message[i] * (is_message[i] - P::ONES));
+            yield_constr.constraint(is_message[i] * (P::ONES - previous));
+            yield_constr.constraint(is_message_block * (is_message[i] - P::ONES));
+            yield_constr
+                .constraint((P::ONES - is_message[i]) * (lv[COL_BYTES_START + i] - padding_byte));
+            num_message_bytes += is_message[i];
+        }
+        yield_constr.constraint(is_last * is_message[RATE_BYTES - 1]);
+
+        // the length counts the message bytes absorbed so far
+        let length = lv[COL_LENGTH];
+        let mut next_num_message_bytes = P::ZEROS;
+        for i in 0..RATE_BYTES {
+            next_num_message_bytes += nv[COL_IS_MESSAGE_START + i];
+        }
+        yield_constr.constraint_first_row(length - num_message_bytes);
+        yield_constr.constraint_transition(
+            (P::ONES - next_is_padding) * (nv[COL_LENGTH] - length - next_num_message_bytes),
+        );
+
+        // the first block is absorbed into a zero state
+        let limbs = limb_constraints(lv);
+        let input = &lv[COL_PERMUTATION_START + COL_INPUT_START..];
+        for i in 0..STATE_SIZE {
+            let absorbed = if i < RATE { limbs[i] } else { P::ZEROS };
+            yield_constr.constraint_first_row(input[i] - absorbed);
+        }
+
+        // later blocks of the message are added to the previous output
+        let next_limbs = limb_constraints(nv);
+        let next_input = &nv[COL_PERMUTATION_START + COL_INPUT_START..];
+        let output = &lv[COL_PERMUTATION_START + COL_OUTPUT_START..];
+        for i in 0..STATE_SIZE {
+            let absorbed = if i < RATE { next_limbs[i] } else { P::ZEROS };
+            yield_constr.constraint_transition(
+                (P::ONES - next_is_padding) * (next_input[i] - output[i] - absorbed),
+            );
+        }
+
+        // the last block outputs the digest and the length
+        let public_inputs = vars.public_inputs;
+        for i in 0..DIGEST_SIZE {
+            yield_constr.constraint(is_last * (output[i] - public_inputs[i]));
+        }
+        yield_constr.constraint(is_last * (length - public_inputs[DIGEST_SIZE]));
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::generation::generate_poseidon2_bytes_trace;
+    use crate::bytes::stark::Poseidon2BytesStark;
+    use crate::bytes::{hash_bytes, DIGEST_SIZE};
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use plonky2::field::types::{Field, PrimeField64, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2BytesStark<F, D>;
+
+    #[test]
+    fn poseidon2_bytes_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        for len in [0, 27, 28, 100] {
+            let message: Vec<u8> = (0..len)
+                .map(|_| F::rand().to_canonical_u64().to_le_bytes()[0])
+                .collect();
+
+            let stark = S::default();
+            let (trace, public_inputs) = generate_poseidon2_bytes_trace(&message);
+            assert_eq!(public_inputs[..DIGEST_SIZE], hash_bytes::<F>(&message));
+            assert_eq!(public_inputs[DIGEST_SIZE], F::from_canonical_usize(len));
+            let trace_poly_values = trace_to_poly_values(trace);
+
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace_poly_values,
+                public_inputs,
+                &mut TimingTree::default(),
+            )?;
+
+            // the proof does not verify against another digest or length
+            for i in 0..=DIGEST_SIZE {
+                let mut tampered = proof.clone();
+                tampered.public_inputs[i] += F::ONE;
+                assert!(verify_stark_proof(stark, tampered, &config).is_err());
+            }
+            verify_stark_proof(stark, proof, &config)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn poseidon2_bytes_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
@@ -0,0 +1,278 @@
+//! A Fiat-Shamir challenger duplexing over the Poseidon2 permutation.
+//!
+//! [`Poseidon2Challenger`] follows plonky2's `Challenger`: observed elements
+//! are buffered and overwrite the [`RATE`] elements of the state once the
+//! buffer is full or a challenge is requested, and challenges are popped from
+//! the rate of the last permutation output. With recording enabled, every
+//! permutation is kept as a [`DuplexRow`], with the observed elements
+//! overwriting the rate and the rate elements popped as challenges. The
+//! transcript can then be proved by [`Poseidon2DuplexStark`] from the trace of
+//! [`generate_duplex_trace`] with a zero initial capacity, which chains the
+//! permutations from the zero state and reads every challenge from the rate.
+//! The proof exposes the [`transcript_commitment`] of the rows and the
+//! challenges, in the order of the rate, against which a verifier checks the
+//! observed elements and the challenges.
+//!
+//! [`Poseidon2DuplexStark`]: crate::duplex::stark::Poseidon2DuplexStark
+//! [`generate_duplex_trace`]: crate::duplex::generation::generate_duplex_trace
+//! [`transcript_commitment`]: crate::duplex::transcript_commitment
+
+use crate::bytes::RATE;
+use crate::columns::STATE_SIZE;
+use crate::duplex::DuplexRow;
+use crate::generation::generate_outputs;
+use crate::params::POSEIDON2_PARAMS_128;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::plonk::config::{GenericHashOut, Hasher};
+
+/// Observes prover messages and generates challenges from the transcript
+#[derive(Debug, Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Poseidon2Challenger<F: RichField> {
+    state: [F; STATE_SIZE],
+    input_buffer: Vec<F>,
+    output_buffer: Vec<F>,
+    rows: Option<Vec<DuplexRow<F>>>,
+}
+
+impl<F: RichField> Default for Poseidon2Challenger<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: RichField> Poseidon2Challenger<F> {
+    /// A challenger starting from the zero state
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            state: [F::ZERO; STATE_SIZE],
+            input_buffer: Vec::with_capacity(RATE),
+            output_buffer: Vec::with_capacity(RATE),
+            rows: None,
+        }
+    }
+
+    /// A challenger recording the [`DuplexRow`] of every permutation
+    #[must_use]
+    pub fn new_recording() -> Self {
+        Self {
+            rows: Some(Vec::new()),
+            ..Self::new()
+        }
+    }
+
+    pub fn observe_element(&mut self, element: F) {
+        // buffered outputs would not reflect this input
+        self.output_buffer.clear();
+
+        self.input_buffer.push(element);
+
+        if self.input_buffer.len() == RATE {
+            self.duplexing();
+        }
+    }
+
+    pub fn observe_elements(&mut self, elements: &[F]) {
+        for &element in elements {
+            self.observe_element(element);
+        }
+    }
+
+    pub fn observe_extension_element<const D: usize>(&mut self, element: &F::Extension)
+    where
+        F: Extendable<D>,
+    {
+        self.observe_elements(&element.to_basefield_array());
+    }
+
+    pub fn observe_extension_elements<const D: usize>(&mut self, elements: &[F::Extension])
+    where
+        F: Extendable<D>,
+    {
+        for element in elements {
+            self.observe_extension_element(element);
+        }
+    }
+
+    pub fn observe_hash<OH: Hasher<F>>(&mut self, hash: OH::Hash) {
+        self.observe_elements(&hash.to_vec());
+    }
+
+    pub fn observe_cap<OH: Hasher<F>>(&mut self, cap: &MerkleCap<F, OH>) {
+        for &hash in &cap.0 {
+            self.observe_hash::<OH>(hash);
+        }
+    }
+
+    pub fn get_challenge(&mut self) -> F {
+        // buffered inputs must be absorbed before the challenge, and an empty
+        // output buffer needs a new permutation
+        if !self.input_buffer.is_empty() || self.output_buffer.is_empty() {
+            self.duplexing();
+        }
+
+        let challenge = self
+            .output_buffer
+            .pop()
+            .expect("Output buffer should be non-empty");
+        if let Some(rows) = &mut self.rows {
+            // the output buffer is the rate of the last permutation
+            rows.last_mut().unwrap().squeezed[self.output_buffer.len()] = true;
+        }
+        challenge
+    }
+
+    pub fn get_n_challenges(&mut self, n: usize) -> Vec<F> {
+        (0..n).map(|_| self.get_challenge()).collect()
+    }
+
+    pub fn get_extension_challenge<const D: usize>(&mut self) -> F::Extension
+    where
+        F: Extendable<D>,
+    {
+        let mut arr = [F::ZERO; D];
+        arr.copy_from_slice(&self.get_n_challenges(D));
+        F::Extension::from_basefield_array(arr)
+    }
+
+    pub fn get_n_extension_challenges<const D: usize>(&mut self, n: usize) -> Vec<F::Extension>
+    where
+        F: Extendable<D>,
+    {
+        (0..n)
+            .map(|_| self.get_extension_challenge::<D>())
+            .collect()
+    }
+
+    /// The rows of the permutations so far, if recording
+    #[must_use]
+    pub fn rows(&self) -> Option<&[DuplexRow<F>]> {
+        self.rows.as_deref()
+    }
+
+    /// The rows of all permutations, after absorbing buffered inputs, if
+    /// recording
+    #[must_use]
+    pub fn into_rows(mut self) -> Option<Vec<DuplexRow<F>>> {
+        if !self.input_buffer.is_empty() {
+            self.duplexing();
+        }
+        self.rows
+    }
+
+    /// Overwrite the rate with the buffered inputs, permute and refill the
+    /// output buffer
+    fn duplexing(&mut self) {
+        assert!(self.input_buffer.len() <= RATE);
+
+        let mut row = DuplexRow {
+            overwritten: self.input_buffer.len(),
+            ..DuplexRow::default()
+        };
+        for ((s, absorbed), input) in self
+            .state
+            .iter_mut()
+            .zip(&mut row.absorbed)
+            .zip(self.input_buffer.drain(..))
+        {
+            *s = input;
+            *absorbed = input;
+        }
+
+        if let Some(rows) = &mut self.rows {
+            rows.push(row);
+        }
+        self.state = generate_outputs(&POSEIDON2_PARAMS_128, &self.state);
+
+        self.output_buffer.clear();
+        self.output_buffer.extend_from_slice(&self.state[..RATE]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::challenger::Poseidon2Challenger;
+    use crate::duplex::generation::generate_duplex_trace;
+    use crate::duplex::stark::Poseidon2DuplexStark;
+    use crate::duplex::{transcript_commitment, CAPACITY};
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use permutation_air::prover::config_for_degree;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::prover::prove;
+    use starky::stark::Stark;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2DuplexStark<F, D>;
+
+    #[test]
+    fn proved_transcript_yields_the_challenges() -> Result<()> {
+        let messages = F::rand_vec(9);
+        let transcript = |challenger: &mut Poseidon2Challenger<F>| {
+            challenger.observe_elements(&messages[..3]);
+            let mut challenges = challenger.get_n_challenges(2);
+            challenger.observe_elements(&messages[3..]);
+            challenges.extend(challenger.get_n_challenges(5));
+            challenges
+        };
+
+        let mut native = Poseidon2Challenger::new();
+        let challenges = transcript(&mut native);
+        assert!(native.rows().is_none());
+
+        let mut recording = Poseidon2Challenger::new_recording();
+        assert_eq!(transcript(&mut recording), challenges);
+        let rows = recording.into_rows().unwrap();
+        assert_eq!(rows.len(), 4);
+
+        let (trace, public_inputs) = generate_duplex_trace(&[F::ZERO; CAPACITY], &rows);
+        let stark = S::default();
+        let config = config_for_degree(stark.constraint_degree());
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_to_poly_values(trace),
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        // challenges are popped from the end of the rate of each output: two
+        // from the first permutation, none from the second, which absorbs a
+        // full buffer, four from the third and one from the fourth
+        let commitment = |challenges: &[F]| {
+            let mut squeezed = challenges.to_vec();
+            for range in [0..2, 2..6, 6..7] {
+                squeezed[range].reverse();
+            }
+            transcript_commitment(&rows, &squeezed)
+        };
+        assert_eq!(proof.public_inputs[..CAPACITY], [F::ZERO; CAPACITY]);
+        assert_eq!(proof.public_inputs[CAPACITY..], commitment(&challenges));
+
+        // the transcript starts from the zero state
+        let mut forged = proof.clone();
+        forged.public_inputs[0] = F::ONE;
+        assert!(verify_stark_proof(stark, forged, &config).is_err());
+
+        // and does not yield another challenge
+        let mut forged_challenges = challenges;
+        forged_challenges[3] += F::ONE;
+        assert_ne!(
+            proof.public_inputs[CAPACITY..],
+            commitment(&forged_challenges)
+        );
+        let mut forged = proof;
+        forged.public_inputs[CAPACITY..].copy_from_slice(&commitment(&forged_challenges));
+        assert!(verify_stark_proof(stark, forged, &config).is_err());
+        Ok(())
+    }
+}
@@ -1,8 +1,7 @@
 /// The size of the state
 pub const STATE_SIZE: usize = 8;
-pub(crate) const SBOX_DEGREE: usize = 7;
 
-/// Poseidon constants
+/// Rounds of the 128-bit preset: full rounds in each half and partial rounds
 pub(crate) const ROUNDS_F: usize = 4;
 pub(crate) const ROUNDS_P: usize = 22;
 
@@ -13,21 +12,36 @@ pub(crate) const COL_INPUT_START: usize = 0;
 pub(crate) const COL_1ST_FULLROUND_STATE_START: usize = COL_INPUT_START + STATE_SIZE; // 8
 
 /// The value of state[0] after each partial round
-pub(crate) const COL_PARTIAL_ROUND_STATE_START: usize =
-    COL_1ST_FULLROUND_STATE_START + STATE_SIZE * ROUNDS_F; // 40
+#[must_use]
+pub const fn col_partial_round_state_start(rounds_f: usize) -> usize {
+    COL_1ST_FULLROUND_STATE_START + STATE_SIZE * rounds_f
+}
 
 /// The starting point of the state after the partial round
-pub(crate) const COL_PARTIAL_ROUND_END_STATE_START: usize =
-    COL_PARTIAL_ROUND_STATE_START + ROUNDS_P - 1; // 61
+#[must_use]
+pub const fn col_partial_round_end_state_start(rounds_f: usize, rounds_p: usize) -> usize {
+    col_partial_round_state_start(rounds_f) + rounds_p - 1
+}
 
 /// The starting point of the state after each 2nd full round
-pub(crate) const COL_2ND_FULLROUND_STATE_START: usize =
-    COL_PARTIAL_ROUND_END_STATE_START + STATE_SIZE; // 69
+#[must_use]
+pub const fn col_2nd_fullround_state_start(rounds_f: usize, rounds_p: usize) -> usize {
+    col_partial_round_end_state_start(rounds_f, rounds_p) + STATE_SIZE
+}
 
 /// The starting point of the column output
 /// This is the same as the last state after the 2nd full round
-pub(crate) const COL_OUTPUT_START: usize =
-    COL_2ND_FULLROUND_STATE_START + STATE_SIZE * (ROUNDS_F - 1);
+#[must_use]
+pub const fn col_output_start(rounds_f: usize, rounds_p: usize) -> usize {
+    col_2nd_fullround_state_start(rounds_f, rounds_p) + STATE_SIZE * (rounds_f - 1)
+}
 
 /// The total number of columns
-pub(crate) const NUM_COLS: usize = COL_2ND_FULLROUND_STATE_START + STATE_SIZE * ROUNDS_F; // 101
+#[must_use]
+pub const fn num_cols(rounds_f: usize, rounds_p: usize) -> usize {
+    col_2nd_fullround_state_start(rounds_f, rounds_p) + STATE_SIZE * rounds_f
+}
+
+/// Column positions of the 128-bit preset
+pub(crate) const COL_OUTPUT_START: usize = col_output_start(ROUNDS_F, ROUNDS_P);
+pub(crate) const NUM_COLS: usize = num_cols(ROUNDS_F, ROUNDS_P); // 101
@@ -0,0 +1,33 @@
+use crate::bytes::RATE;
+use crate::columns::NUM_COLS;
+
+/// The starting point of the permutation columns, laid out as in `crate::columns`
+pub(crate) const COL_PERMUTATION_START: usize = 0;
+
+/// 1 if the row absorbs the first block of a hash, 0 otherwise
+pub(crate) const COL_IS_FIRST_BLOCK: usize = COL_PERMUTATION_START + NUM_COLS; // 101
+
+/// 1 if the row absorbs the last block of a hash, 0 otherwise
+pub(crate) const COL_IS_LAST_BLOCK: usize = COL_IS_FIRST_BLOCK + 1; // 102
+
+/// The domain tag of the hash
+pub(crate) const COL_TAG: usize = COL_IS_LAST_BLOCK + 1; // 103
+
+/// The inverse of the domain tag, witnessing that it is nonzero
+pub(crate) const COL_TAG_INV: usize = COL_TAG + 1; // 104
+
+/// The number of elements hashed
+pub(crate) const COL_LENGTH: usize = COL_TAG_INV + 1; // 105
+
+/// The number of elements left to absorb, including this block
+pub(crate) const COL_REMAINING: usize = COL_LENGTH + 1; // 106
+
+/// The starting point of the elements absorbed in this row
+pub(crate) const COL_ABSORBED_START: usize = COL_REMAINING + 1; // 107
+
+/// The starting point of the flags marking absorbed elements that are input
+/// rather than padding
+pub(crate) const COL_PRESENT_START: usize = COL_ABSORBED_START + RATE; // 111
+
+/// The total number of columns
+pub(crate) const DOMAIN_NUM_COLS: usize = COL_PRESENT_START + RATE; // 115
@@ -0,0 +1,116 @@
+use crate::bytes::RATE;
+use crate::columns::COL_OUTPUT_START;
+use crate::domain::columns::{
+    COL_ABSORBED_START, COL_IS_FIRST_BLOCK, COL_IS_LAST_BLOCK, COL_LENGTH, COL_PERMUTATION_START,
+    COL_PRESENT_START, COL_REMAINING, COL_TAG, COL_TAG_INV, DOMAIN_NUM_COLS,
+};
+use crate::domain::{blocks, initial_state, DomainTag};
+use crate::generation::poseidon2_air;
+use permutation_air::generation::fill_permutation_row;
+use plonky2::hash::hash_types::RichField;
+
+/// Append the rows of a single domain-separated hash to a column-major trace.
+fn fill_hash_rows<F: RichField>(trace: &mut [Vec<F>], domain: DomainTag, inputs: &[F]) {
+    let blocks = blocks(inputs);
+    let mut state = initial_state(domain, inputs.len());
+    let tag = F::from_canonical_u32(domain.get());
+
+    let air = poseidon2_air();
+    for (block_index, block) in blocks.iter().enumerate() {
+        let row = trace[0].len();
+        for col in trace.iter_mut() {
+            col.push(F::ZERO);
+        }
+
+        for (s, &x) in state.iter_mut().zip(block) {
+            *s += x;
+        }
+        fill_permutation_row(&air, &mut trace[COL_PERMUTATION_START..], row, &state);
+        for (j, s) in state.iter_mut().enumerate() {
+            *s = trace[COL_PERMUTATION_START + COL_OUTPUT_START + j][row];
+        }
+
+        let remaining = inputs.len() - block_index * RATE;
+        trace[COL_IS_FIRST_BLOCK][row] = F::from_bool(block_index == 0);
+        trace[COL_IS_LAST_BLOCK][row] = F::from_bool(block_index + 1 == blocks.len());
+        trace[COL_TAG][row] = tag;
+        trace[COL_TAG_INV][row] = tag.inverse();
+        trace[COL_LENGTH][row] = F::from_canonical_usize(inputs.len());
+        trace[COL_REMAINING][row] = F::from_canonical_usize(remaining);
+        for (j, &x) in block.iter().enumerate() {
+            trace[COL_ABSORBED_START + j][row] = x;
+            trace[COL_PRESENT_START + j][row] = F::from_bool(j < remaining);
+        }
+    }
+}
+
+/// Function to generate the trace of the domain-separated hash of each
+/// `(domain, inputs)` pair with [`hash_with_domain`](crate::domain::hash_with_domain).
+///
+/// The trace is padded to a power of two with rows hashing the empty input
+/// in the commitment domain.
+#[must_use]
+pub fn generate_domain_trace<F: RichField>(
+    hashes: &[(DomainTag, Vec<F>)],
+) -> [Vec<F>; DOMAIN_NUM_COLS] {
+    let mut trace: Vec<Vec<F>> = vec![Vec::new(); DOMAIN_NUM_COLS];
+
+    for (domain, inputs) in hashes {
+        fill_hash_rows(&mut trace, *domain, inputs);
+    }
+    let ext_trace_len = trace[0].len().max(1).next_power_of_two();
+    while trace[0].len() < ext_trace_len {
+        fill_hash_rows(&mut trace, DomainTag::COMMITMENT, &[]);
+    }
+
+    trace.try_into().unwrap_or_else(|v: Vec<Vec<F>>| {
+        panic!(
+            "Expected a Vec of length {} but it was {}",
+            DOMAIN_NUM_COLS,
+            v.len()
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bytes::{DIGEST_SIZE, RATE};
+    use crate::columns::COL_OUTPUT_START;
+    use crate::domain::columns::{COL_IS_LAST_BLOCK, COL_PERMUTATION_START};
+    use crate::domain::generation::generate_domain_trace;
+    use crate::domain::{hash_with_domain, DomainTag};
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn digests_match_native_hash() {
+        let hashes: Vec<(DomainTag, Vec<F>)> = [0, 1, RATE, RATE + 1, 3 * RATE + 2]
+            .iter()
+            .zip([DomainTag::COMMITMENT, DomainTag::NULLIFIER].iter().cycle())
+            .map(|(&len, &domain)| (domain, F::rand_vec(len)))
+            .collect();
+
+        let trace = generate_domain_trace::<F>(&hashes);
+        assert!(trace[0].len().is_power_of_two());
+
+        // The digest of a hash sits in the row of its last block.
+        let last_rows: Vec<usize> = (0..trace[0].len())
+            .filter(|&row| trace[COL_IS_LAST_BLOCK][row] == F::ONE)
+            .collect();
+        for (&row, (domain, inputs)) in last_rows.iter().zip(&hashes) {
+            let expected = hash_with_domain(*domain, inputs);
+            for j in 0..DIGEST_SIZE {
+                assert_eq!(
+                    trace[COL_PERMUTATION_START + COL_OUTPUT_START + j][row],
+                    expected[j],
+                    "Mismatch at row {row}, position {j}"
+                );
+            }
+        }
+        assert!(last_rows.len() >= hashes.len());
+    }
+}
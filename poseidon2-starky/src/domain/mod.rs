@@ -0,0 +1,166 @@
+//! Domain-separated hashing with the Poseidon2 permutation, in the style of
+//! the SAFE sponge API.
+//!
+//! A hash of `n` field elements starts from a state whose capacity holds the
+//! domain tag and `n`, `[tag, n, 0, 0]`, absorbs the inputs by adding them to
+//! the [`RATE`] elements in blocks, the last one padded with zeros, and
+//! squeezes [`DIGEST_SIZE`] elements. At least one block is absorbed. Tag 0
+//! is reserved for the untagged sponge of [`crate::bytes`], so hashes of
+//! different domains, or of inputs of different lengths, start from
+//! different states.
+#![allow(clippy::module_name_repetitions)]
+
+pub mod columns;
+pub mod generation;
+pub mod stark;
+
+use crate::bytes::{DIGEST_SIZE, RATE};
+use crate::columns::STATE_SIZE;
+use crate::generation::generate_outputs;
+use crate::params::POSEIDON2_PARAMS_128;
+use crate::smt::Digest;
+use plonky2::hash::hash_types::RichField;
+use std::num::NonZeroU32;
+
+/// The capacity positions of the tag and the input length
+pub(crate) const TAG_POSITION: usize = RATE;
+pub(crate) const LENGTH_POSITION: usize = RATE + 1;
+
+/// A nonzero domain tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DomainTag(NonZeroU32);
+
+impl DomainTag {
+    /// The domain of [`commitment`]
+    pub const COMMITMENT: Self = Self::new_unchecked(1);
+    /// The domain of [`nullifier`]
+    pub const NULLIFIER: Self = Self::new_unchecked(2);
+
+    /// The tag `tag`, or `None` for the reserved tag 0
+    #[must_use]
+    pub const fn new(tag: u32) -> Option<Self> {
+        match NonZeroU32::new(tag) {
+            Some(tag) => Some(Self(tag)),
+            None => None,
+        }
+    }
+
+    const fn new_unchecked(tag: u32) -> Self {
+        match Self::new(tag) {
+            Some(tag) => tag,
+            None => panic!("tag 0 is reserved"),
+        }
+    }
+
+    #[must_use]
+    pub const fn get(self) -> u32 {
+        self.0.get()
+    }
+}
+
+/// The state before absorbing `length` elements in `domain`
+pub(crate) fn initial_state<F: RichField>(domain: DomainTag, length: usize) -> [F; STATE_SIZE] {
+    let mut state = [F::ZERO; STATE_SIZE];
+    state[TAG_POSITION] = F::from_canonical_u32(domain.get());
+    state[LENGTH_POSITION] = F::from_canonical_usize(length);
+    state
+}
+
+/// The blocks absorbing `inputs`, the last one padded with zeros, and at
+/// least one block
+pub(crate) fn blocks<F: RichField>(inputs: &[F]) -> Vec<[F; RATE]> {
+    let num_blocks = ((inputs.len() + RATE - 1) / RATE).max(1);
+    (0..num_blocks)
+        .map(|b| {
+            let mut block = [F::ZERO; RATE];
+            for (x, &input) in block.iter_mut().zip(inputs.iter().skip(b * RATE)) {
+                *x = input;
+            }
+            block
+        })
+        .collect()
+}
+
+/// Hash `inputs` in `domain`
+#[must_use]
+pub fn hash_with_domain<F: RichField>(domain: DomainTag, inputs: &[F]) -> Digest<F> {
+    let mut state = initial_state(domain, inputs.len());
+    for block in blocks(inputs) {
+        for (s, x) in state.iter_mut().zip(block) {
+            *s += x;
+        }
+        state = generate_outputs(&POSEIDON2_PARAMS_128, &state);
+    }
+    state[..DIGEST_SIZE].try_into().unwrap()
+}
+
+/// The commitment to a note `value` with `blinding`
+#[must_use]
+pub fn commitment<F: RichField>(value: &[F], blinding: &Digest<F>) -> Digest<F> {
+    let inputs: Vec<F> = value.iter().chain(blinding).copied().collect();
+    hash_with_domain(DomainTag::COMMITMENT, &inputs)
+}
+
+/// The nullifier of the note with `commitment`, derived with the secret
+/// `nullifier_key`
+#[must_use]
+pub fn nullifier<F: RichField>(nullifier_key: &Digest<F>, commitment: &Digest<F>) -> Digest<F> {
+    let inputs: Vec<F> = nullifier_key.iter().chain(commitment).copied().collect();
+    hash_with_domain(DomainTag::NULLIFIER, &inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::hash_bytes;
+    use crate::domain::{commitment, hash_with_domain, nullifier, DomainTag};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn domains_and_lengths_separate() {
+        let inputs = F::rand_array::<8>();
+        let custom = DomainTag::new(7).unwrap();
+        assert_ne!(
+            hash_with_domain(DomainTag::COMMITMENT, &inputs),
+            hash_with_domain(DomainTag::NULLIFIER, &inputs)
+        );
+        assert_ne!(
+            hash_with_domain(custom, &inputs),
+            hash_with_domain(DomainTag::COMMITMENT, &inputs)
+        );
+        // trailing zeros are not padding
+        assert_ne!(
+            hash_with_domain(custom, &inputs[..5]),
+            hash_with_domain(custom, &[&inputs[..5], &[F::ZERO; 3]].concat())
+        );
+        assert_ne!(
+            hash_with_domain::<F>(custom, &[]),
+            hash_with_domain(custom, &[F::ZERO])
+        );
+        assert_eq!(DomainTag::new(0), None);
+    }
+
+    #[test]
+    fn note_helpers() {
+        let value = F::rand_array::<3>();
+        let blinding = F::rand_array();
+        let key = F::rand_array();
+        let note = commitment(&value, &blinding);
+        assert_eq!(
+            note,
+            hash_with_domain(DomainTag::COMMITMENT, &[&value[..], &blinding].concat())
+        );
+        assert_ne!(note, commitment(&value, &F::rand_array()));
+        assert_ne!(
+            nullifier(&key, &note),
+            hash_with_domain(DomainTag::COMMITMENT, &[&key[..], &note].concat())
+        );
+        // the untagged sponge starts from a zero capacity
+        assert_ne!(
+            hash_with_domain::<F>(DomainTag::COMMITMENT, &[]),
+            hash_bytes::<F>(&[])
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::bytes::RATE;
+use crate::columns::{COL_INPUT_START, COL_OUTPUT_START, STATE_SIZE};
+use crate::domain::columns::{
+    COL_ABSORBED_START, COL_IS_FIRST_BLOCK, COL_IS_LAST_BLOCK, COL_LENGTH, COL_PERMUTATION_START,
+    COL_PRESENT_START, COL_REMAINING, COL_TAG, COL_TAG_INV, DOMAIN_NUM_COLS,
+};
+use crate::domain::{LENGTH_POSITION, TAG_POSITION};
+use crate::params::POSEIDON2_PARAMS_128;
+use crate::stark::eval_permutation;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+/// A sponge over [`Poseidon2Stark`](crate::stark::Poseidon2Stark) rows whose
+/// initial capacity holds a nonzero domain tag and the input length.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Poseidon2DomainStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Poseidon2DomainStark<F, D> {
+    const COLUMNS: usize = DOMAIN_NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let lv = vars.local_values;
+        let nv = vars.next_values;
+        eval_permutation(
+            &POSEIDON2_PARAMS_128,
+            &lv[COL_PERMUTATION_START..],
+            yield_constr,
+        );
+
+        // the block flags are boolean, the trace starts with a new hash and
+        // ends with a finished one, and a hash ends right before the next
+        // one starts
+        let is_first = lv[COL_IS_FIRST_BLOCK];
+        let is_last = lv[COL_IS_LAST_BLOCK];
+        yield_constr.constraint(is_first * (is_first - P::ONES));
+        yield_constr.constraint(is_last * (is_last - P::ONES));
+        yield_constr.constraint_first_row(is_first - P::ONES);
+        yield_constr.constraint_last_row(is_last - P::ONES);
+        yield_constr.constraint_transition(nv[COL_IS_FIRST_BLOCK] - is_last);
+
+        // the tag is nonzero, so no hash shares its initial state with the
+        // untagged sponge
+        let tag = lv[COL_TAG];
+        let length = lv[COL_LENGTH];
+        let remaining = lv[COL_REMAINING];
+        yield_constr.constraint(tag * lv[COL_TAG_INV] - P::ONES);
+
+        // the first block starts from the capacity [tag, length, 0, 0] and
+        // absorbs all of the length
+        let input = &lv[COL_PERMUTATION_START + COL_INPUT_START..];
+        let absorbed = &lv[COL_ABSORBED_START..COL_ABSORBED_START + RATE];
+        yield_constr.constraint(is_first * (remaining - length));
+        for i in 0..STATE_SIZE {
+            let initial = match i {
+                _ if i < RATE => absorbed[i],
+                TAG_POSITION => tag,
+                LENGTH_POSITION => length,
+                _ => P::ZEROS,
+            };
+            yield_constr.constraint(is_first * (input[i] - initial));
+        }
+
+        // later blocks keep the tag and length, absorb the next elements and
+        // are added to the previous output
+        let not_last = P::ONES - is_last;
+        yield_constr.constraint_transition(not_last * (nv[COL_TAG] - tag));
+        yield_constr.constraint_transition(not_last * (nv[COL_LENGTH] - length));
+        yield_constr.constraint_transition(
+            not_last * (nv[COL_REMAINING] - remaining + FE::from_canonical_usize(RATE)),
+        );
+        let next_input = &nv[COL_PERMUTATION_START + COL_INPUT_START..];
+        let output = &lv[COL_PERMUTATION_START + COL_OUTPUT_START..];
+        for i in 0..STATE_SIZE {
+            let absorbed = if i < RATE {
+                nv[COL_ABSORBED_START + i]
+            } else {
+                P::ZEROS
+            };
+            yield_constr.constraint_transition(not_last * (next_input[i] - output[i] - absorbed));
+        }
+
+        // the present flags are a boolean prefix counting the elements left
+        // in the last block, the rest of which is zero padding; every block
+        // but the last is full and a last block after the first is not empty
+        let present = &lv[COL_PRESENT_START..COL_PRESENT_START + RATE];
+        let mut num_present = P::ZEROS;
+        for i in 0..RATE {
+            yield_constr.constraint(present[i] * (present[i] - P::ONES));
+            yield_constr.constraint(not_last * (present[i] - P::ONES));
+            yield_constr.constraint((P::ONES - present[i]) * absorbed[i]);
+            if i > 0 {
+                yield_constr.constraint(present[i] * (P::ONES - present[i - 1]));
+            }
+            num_present += present[i];
+        }
+        yield_constr.constraint(is_last * (num_present - remaining));
+        yield_constr.constraint((P::ONES - is_first) * (present[0] - P::ONES));
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::generation::generate_domain_trace;
+    use crate::domain::stark::Poseidon2DomainStark;
+    use crate::domain::DomainTag;
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2DomainStark<F, D>;
+
+    #[test]
+    fn poseidon2_domain_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let custom = DomainTag::new(42).unwrap();
+        let hashes: Vec<(DomainTag, Vec<F>)> = [
+            (DomainTag::COMMITMENT, 7),
+            (DomainTag::NULLIFIER, 8),
+            (custom, 0),
+            (custom, 13),
+        ]
+        .into_iter()
+        .map(|(domain, len)| (domain, F::rand_vec(len)))
+        .collect();
+
+        let stark = S::default();
+        let trace = generate_domain_trace(&hashes);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn poseidon2_domain_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
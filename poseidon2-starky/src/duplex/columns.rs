@@ -0,0 +1,32 @@
+use crate::bytes::RATE;
+use crate::columns::NUM_COLS;
+
+/// The starting point of the permutation columns, laid out as in `crate::columns`
+pub(crate) const COL_PERMUTATION_START: usize = 0;
+
+/// The starting point of the elements written into the rate before the
+/// permutation
+pub(crate) const COL_ABSORBED_START: usize = COL_PERMUTATION_START + NUM_COLS; // 101
+
+/// The starting point of the flags marking rate elements overwritten rather
+/// than added to, a prefix of the rate
+pub(crate) const COL_OVERWRITE_START: usize = COL_ABSORBED_START + RATE; // 105
+
+/// The starting point of the flags marking squeezed rate elements of the
+/// output
+pub(crate) const COL_IS_SQUEEZED_START: usize = COL_OVERWRITE_START + RATE; // 109
+
+/// The starting point of the squeezed elements, zero where not squeezed
+pub(crate) const COL_SQUEEZED_START: usize = COL_IS_SQUEEZED_START + RATE; // 113
+
+/// The permutation compressing the absorbed elements of this row into the
+/// commitment to the rows above, with the shape of the row added, laid out as
+/// in `crate::columns`
+pub(crate) const COL_ABSORBED_PERMUTATION_START: usize = COL_SQUEEZED_START + RATE; // 117
+
+/// The permutation compressing the squeezed elements of this row into the
+/// output of the previous one, laid out as in `crate::columns`
+pub(crate) const COL_SQUEEZED_PERMUTATION_START: usize = COL_ABSORBED_PERMUTATION_START + NUM_COLS; // 218
+
+/// The total number of columns
+pub(crate) const DUPLEX_NUM_COLS: usize = COL_SQUEEZED_PERMUTATION_START + NUM_COLS; // 319
@@ -0,0 +1,193 @@
+use crate::bytes::{DIGEST_SIZE, RATE};
+use crate::columns::{COL_OUTPUT_START, STATE_SIZE};
+use crate::duplex::columns::{
+    COL_ABSORBED_PERMUTATION_START, COL_ABSORBED_START, COL_IS_SQUEEZED_START, COL_OVERWRITE_START,
+    COL_PERMUTATION_START, COL_SQUEEZED_PERMUTATION_START, COL_SQUEEZED_START, DUPLEX_NUM_COLS,
+};
+use crate::duplex::{DuplexRow, CAPACITY};
+use crate::generation::poseidon2_air;
+use crate::stark::Poseidon2Stark;
+use permutation_air::generation::fill_permutation_row;
+use plonky2::hash::hash_types::RichField;
+
+/// The number of public inputs: the initial capacity, then the
+/// [`transcript_commitment`](crate::duplex::transcript_commitment)
+pub const DUPLEX_PUBLIC_INPUTS: usize = CAPACITY + DIGEST_SIZE;
+
+/// Fill the permutation of `input` starting at column `start` of `row`, and
+/// return its output
+fn fill_permutation<F: RichField>(
+    air: &Poseidon2Stark<F, 1>,
+    trace: &mut [Vec<F>],
+    start: usize,
+    row: usize,
+    input: &[F; STATE_SIZE],
+) -> [F; STATE_SIZE] {
+    fill_permutation_row(air, &mut trace[start..], row, input);
+    std::array::from_fn(|j| trace[start + COL_OUTPUT_START + j][row])
+}
+
+/// Function to generate the trace of the duplex run of `rows` from
+/// `initial_capacity`, one row per permutation, and the public inputs.
+///
+/// The trace is padded to a power of two with permutations that absorb and
+/// squeeze nothing, which continue the run without changing what it squeezed.
+/// Two more permutations per row compress the shape, the absorbed elements
+/// and the squeezed elements of the row into the commitment.
+#[must_use]
+pub fn generate_duplex_trace<F: RichField>(
+    initial_capacity: &[F; CAPACITY],
+    rows: &[DuplexRow<F>],
+) -> ([Vec<F>; DUPLEX_NUM_COLS], [F; DUPLEX_PUBLIC_INPUTS]) {
+    let trace_len = rows.len().max(1).next_power_of_two();
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; DUPLEX_NUM_COLS];
+
+    let mut state = [F::ZERO; STATE_SIZE];
+    state[RATE..].copy_from_slice(initial_capacity);
+    let mut commitment = [F::ZERO; DIGEST_SIZE];
+    let air = poseidon2_air();
+    for row in 0..trace_len {
+        let duplex = rows.get(row).cloned().unwrap_or_default();
+        for (i, &x) in duplex.absorbed.iter().enumerate() {
+            if i < duplex.overwritten {
+                state[i] = x;
+            } else {
+                state[i] += x;
+            }
+        }
+        state = fill_permutation(&air, &mut trace, COL_PERMUTATION_START, row, &state);
+
+        let mut squeezed = [F::ZERO; RATE];
+        for i in 0..RATE {
+            trace[COL_ABSORBED_START + i][row] = duplex.absorbed[i];
+            trace[COL_OVERWRITE_START + i][row] = F::from_bool(i < duplex.overwritten);
+            trace[COL_IS_SQUEEZED_START + i][row] = F::from_bool(duplex.squeezed[i]);
+            if duplex.squeezed[i] {
+                squeezed[i] = state[i];
+                trace[COL_SQUEEZED_START + i][row] = state[i];
+            }
+        }
+
+        let mut input = [F::ZERO; STATE_SIZE];
+        input[..DIGEST_SIZE].copy_from_slice(&commitment);
+        input[0] += duplex.shape();
+        input[DIGEST_SIZE..].copy_from_slice(&duplex.absorbed);
+        let output = fill_permutation(
+            &air,
+            &mut trace,
+            COL_ABSORBED_PERMUTATION_START,
+            row,
+            &input,
+        );
+        input[..DIGEST_SIZE].copy_from_slice(&output[..DIGEST_SIZE]);
+        input[DIGEST_SIZE..].copy_from_slice(&squeezed);
+        let output = fill_permutation(
+            &air,
+            &mut trace,
+            COL_SQUEEZED_PERMUTATION_START,
+            row,
+            &input,
+        );
+        commitment.copy_from_slice(&output[..DIGEST_SIZE]);
+    }
+
+    let trace = trace.try_into().unwrap_or_else(|v: Vec<Vec<F>>| {
+        panic!(
+            "Expected a Vec of length {} but it was {}",
+            DUPLEX_NUM_COLS,
+            v.len()
+        )
+    });
+    let mut public_inputs = [F::ZERO; DUPLEX_PUBLIC_INPUTS];
+    public_inputs[..CAPACITY].copy_from_slice(initial_capacity);
+    public_inputs[CAPACITY..].copy_from_slice(&commitment);
+    (trace, public_inputs)
+}
+
+/// The squeezed elements of a duplex trace, in the order of the rows and of
+/// the rate
+#[must_use]
+pub fn squeezed_elements<F: RichField>(trace: &[Vec<F>; DUPLEX_NUM_COLS]) -> Vec<F> {
+    (0..trace[0].len())
+        .flat_map(|row| {
+            (0..RATE)
+                .filter(move |&i| trace[COL_IS_SQUEEZED_START + i][row] == F::ONE)
+                .map(move |i| trace[COL_SQUEEZED_START + i][row])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bytes::RATE;
+    use crate::columns::{COL_INPUT_START, COL_OUTPUT_START, STATE_SIZE};
+    use crate::duplex::columns::COL_PERMUTATION_START;
+    use crate::duplex::generation::{generate_duplex_trace, squeezed_elements};
+    use crate::duplex::{transcript_commitment, DuplexRow, CAPACITY};
+    use crate::generation::generate_outputs;
+    use crate::params::POSEIDON2_PARAMS_128;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn rows_chain_the_permutations() {
+        let capacity: [F; CAPACITY] = F::rand_array();
+        let absorbed: [[F; RATE]; 3] = std::array::from_fn(|_| F::rand_array());
+        let rows = [
+            DuplexRow {
+                absorbed: absorbed[0],
+                overwritten: 0,
+                squeezed: [false; RATE],
+            },
+            DuplexRow {
+                absorbed: absorbed[1],
+                overwritten: 2,
+                squeezed: [true, false, false, true],
+            },
+            DuplexRow {
+                absorbed: absorbed[2],
+                overwritten: 0,
+                squeezed: [false, true, false, false],
+            },
+        ];
+        let (trace, public_inputs) = generate_duplex_trace(&capacity, &rows);
+        assert_eq!(trace[0].len(), 4);
+        assert_eq!(public_inputs[..CAPACITY], capacity);
+
+        let mut state = [F::ZERO; STATE_SIZE];
+        state[RATE..].copy_from_slice(&capacity);
+        let mut squeezed = Vec::new();
+        for (row, duplex) in rows.iter().enumerate() {
+            for (i, &x) in duplex.absorbed.iter().enumerate() {
+                if i < duplex.overwritten {
+                    state[i] = x;
+                } else {
+                    state[i] += x;
+                }
+            }
+            for j in 0..STATE_SIZE {
+                assert_eq!(
+                    trace[COL_PERMUTATION_START + COL_INPUT_START + j][row],
+                    state[j]
+                );
+            }
+            state = generate_outputs(&POSEIDON2_PARAMS_128, &state);
+            for j in 0..STATE_SIZE {
+                assert_eq!(
+                    trace[COL_PERMUTATION_START + COL_OUTPUT_START + j][row],
+                    state[j]
+                );
+            }
+            squeezed.extend((0..RATE).filter(|&i| duplex.squeezed[i]).map(|i| state[i]));
+        }
+        assert_eq!(squeezed_elements(&trace), squeezed);
+        assert_eq!(
+            public_inputs[CAPACITY..],
+            transcript_commitment(&rows, &squeezed)
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! A duplex sponge over the Poseidon2 permutation, with a STARK proving a
+//! whole run.
+//!
+//! A run starts from a state whose rate is zero and whose [`CAPACITY`]
+//! elements are given, such as the tag of a [`crate::sponge::Sponge`]. Before
+//! each permutation, elements are added to the rate, or overwrite a prefix
+//! of it as in [`crate::challenger::Poseidon2Challenger`]. The capacity
+//! carries from each permutation output into the next input, and squeezed
+//! elements are read from the rate of an output. A [`DuplexRow`] records what
+//! happens around one permutation, and
+//! [`generate_duplex_trace`](generation::generate_duplex_trace) replays the
+//! rows of a run into a trace for [`Poseidon2DuplexStark`](stark::Poseidon2DuplexStark).
+//! The proof exposes the initial capacity and [`transcript_commitment`], which
+//! binds what the run absorbed and squeezed.
+#![allow(clippy::module_name_repetitions)]
+
+pub mod columns;
+pub mod generation;
+pub mod stark;
+
+use crate::bytes::{DIGEST_SIZE, RATE};
+use crate::columns::STATE_SIZE;
+use crate::smt::{compress, Digest};
+use plonky2::hash::hash_types::RichField;
+
+/// The number of capacity elements
+pub const CAPACITY: usize = STATE_SIZE - RATE;
+
+/// One permutation of a duplex run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DuplexRow<F> {
+    /// The elements written into the rate before the permutation
+    pub absorbed: [F; RATE],
+    /// The number of leading rate elements overwritten by `absorbed` rather
+    /// than added to
+    pub overwritten: usize,
+    /// The rate elements of the output that are squeezed
+    pub squeezed: [bool; RATE],
+}
+
+impl<F: RichField> DuplexRow<F> {
+    /// The number of overwritten elements plus 8 times the bits of the
+    /// squeezed positions, which the commitment adds to its accumulator
+    pub(crate) fn shape(&self) -> F {
+        let squeezed = (0..RATE)
+            .filter(|&i| self.squeezed[i])
+            .fold(0, |acc, i| acc | 1 << i);
+        F::from_canonical_usize(self.overwritten + (squeezed << 3))
+    }
+}
+
+/// The commitment to a duplex run exposed by
+/// [`Poseidon2DuplexStark`](stark::Poseidon2DuplexStark), from its rows and
+/// the elements it squeezed in the order of the rows and of the rate.
+///
+/// The rows are padded to a power of two as in the trace. For every row, the
+/// shape of the row is added to an accumulator starting at zero, then the
+/// absorbed elements and the squeezed elements, zero where not squeezed, are
+/// compressed into it.
+///
+/// # Panics
+/// Panics if the rows do not squeeze exactly `squeezed.len()` elements.
+#[must_use]
+pub fn transcript_commitment<F: RichField>(rows: &[DuplexRow<F>], squeezed: &[F]) -> Digest<F> {
+    let num_rows = rows.len().max(1).next_power_of_two();
+    let mut squeezed = squeezed.iter();
+    let commitment = (0..num_rows).fold([F::ZERO; DIGEST_SIZE], |mut acc, row| {
+        let duplex = rows.get(row).cloned().unwrap_or_default();
+        let mut outputs = [F::ZERO; RATE];
+        for (i, output) in outputs.iter_mut().enumerate() {
+            if duplex.squeezed[i] {
+                *output = *squeezed.next().expect("Too few squeezed elements");
+            }
+        }
+        acc[0] += duplex.shape();
+        compress(&compress(&acc, &duplex.absorbed), &outputs)
+    });
+    assert!(squeezed.next().is_none(), "Too many squeezed elements");
+    commitment
+}
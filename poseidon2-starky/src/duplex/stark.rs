@@ -0,0 +1,219 @@
+use crate::bytes::{DIGEST_SIZE, RATE};
+use crate::columns::{COL_INPUT_START, COL_OUTPUT_START, STATE_SIZE};
+use crate::duplex::columns::{
+    COL_ABSORBED_PERMUTATION_START, COL_ABSORBED_START, COL_IS_SQUEEZED_START, COL_OVERWRITE_START,
+    COL_PERMUTATION_START, COL_SQUEEZED_PERMUTATION_START, COL_SQUEEZED_START, DUPLEX_NUM_COLS,
+};
+use crate::duplex::generation::DUPLEX_PUBLIC_INPUTS;
+use crate::duplex::CAPACITY;
+use crate::params::POSEIDON2_PARAMS_128;
+use crate::stark::eval_permutation;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+/// A duplex run over [`Poseidon2Stark`](crate::stark::Poseidon2Stark) rows:
+/// the first row starts from a zero rate and the capacity in the public
+/// inputs, every row carries the capacity of its output into the next input
+/// and adds its absorbed elements to, or writes them over, the rate of the
+/// previous output. Squeezed elements are rate elements of the output.
+///
+/// Two more permutations per row compress the shape, the absorbed elements
+/// and the squeezed elements of every row into the
+/// [`transcript_commitment`](crate::duplex::transcript_commitment), which
+/// follows the initial capacity in the public inputs.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Poseidon2DuplexStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Poseidon2DuplexStark<F, D> {
+    const COLUMNS: usize = DUPLEX_NUM_COLS;
+    const PUBLIC_INPUTS: usize = DUPLEX_PUBLIC_INPUTS;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let lv = vars.local_values;
+        let nv = vars.next_values;
+        eval_permutation(
+            &POSEIDON2_PARAMS_128,
+            &lv[COL_PERMUTATION_START..],
+            yield_constr,
+        );
+        let input = &lv[COL_PERMUTATION_START + COL_INPUT_START..];
+        let output = &lv[COL_PERMUTATION_START + COL_OUTPUT_START..];
+        let next_input = &nv[COL_PERMUTATION_START + COL_INPUT_START..];
+
+        // the run starts from a zero rate and the public capacity
+        for i in 0..RATE {
+            yield_constr.constraint_first_row(input[i] - lv[COL_ABSORBED_START + i]);
+        }
+        for (j, &capacity) in vars.public_inputs[..CAPACITY].iter().enumerate() {
+            yield_constr.constraint_first_row(input[RATE + j] - capacity);
+        }
+
+        // the overwrite flags are a boolean prefix, and squeezed elements
+        // are read from the rate of the output
+        for i in 0..RATE {
+            let overwrite = lv[COL_OVERWRITE_START + i];
+            let is_squeezed = lv[COL_IS_SQUEEZED_START + i];
+            yield_constr.constraint(overwrite * (overwrite - P::ONES));
+            if i > 0 {
+                yield_constr.constraint(overwrite * (P::ONES - lv[COL_OVERWRITE_START + i - 1]));
+            }
+            yield_constr.constraint(is_squeezed * (is_squeezed - P::ONES));
+            yield_constr.constraint(lv[COL_SQUEEZED_START + i] - is_squeezed * output[i]);
+        }
+
+        // the next input is the output with the next absorbed elements added
+        // to or written over its rate
+        for i in 0..STATE_SIZE {
+            let next = if i < RATE {
+                (P::ONES - nv[COL_OVERWRITE_START + i]) * output[i] + nv[COL_ABSORBED_START + i]
+            } else {
+                output[i]
+            };
+            yield_constr.constraint_transition(next_input[i] - next);
+        }
+
+        // the commitment adds the shape of every row to the accumulator, then
+        // compresses the absorbed and the squeezed elements of the row into it
+        for start in [
+            COL_ABSORBED_PERMUTATION_START,
+            COL_SQUEEZED_PERMUTATION_START,
+        ] {
+            eval_permutation(&POSEIDON2_PARAMS_128, &lv[start..], yield_constr);
+        }
+        let shape = |v: &[P]| {
+            (0..RATE).fold(P::ZEROS, |acc, i| {
+                acc + v[COL_OVERWRITE_START + i]
+                    + v[COL_IS_SQUEEZED_START + i] * FE::from_canonical_usize(8 << i)
+            })
+        };
+        let absorbed_input = &lv[COL_ABSORBED_PERMUTATION_START + COL_INPUT_START..];
+        let absorbed_output = &lv[COL_ABSORBED_PERMUTATION_START + COL_OUTPUT_START..];
+        let squeezed_input = &lv[COL_SQUEEZED_PERMUTATION_START + COL_INPUT_START..];
+        let squeezed_output = &lv[COL_SQUEEZED_PERMUTATION_START + COL_OUTPUT_START..];
+        let next_absorbed_input = &nv[COL_ABSORBED_PERMUTATION_START + COL_INPUT_START..];
+        for j in 0..DIGEST_SIZE {
+            let (row_shape, next_shape) = if j == 0 {
+                (shape(lv), shape(nv))
+            } else {
+                (P::ZEROS, P::ZEROS)
+            };
+            yield_constr.constraint_first_row(absorbed_input[j] - row_shape);
+            yield_constr
+                .constraint_transition(next_absorbed_input[j] - next_shape - squeezed_output[j]);
+            yield_constr.constraint(absorbed_input[DIGEST_SIZE + j] - lv[COL_ABSORBED_START + j]);
+            yield_constr.constraint(squeezed_input[j] - absorbed_output[j]);
+            yield_constr.constraint(squeezed_input[DIGEST_SIZE + j] - lv[COL_SQUEEZED_START + j]);
+            yield_constr.constraint_last_row(squeezed_output[j] - vars.public_inputs[CAPACITY + j]);
+        }
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::RATE;
+    use crate::duplex::generation::{generate_duplex_trace, squeezed_elements};
+    use crate::duplex::stark::Poseidon2DuplexStark;
+    use crate::duplex::{transcript_commitment, DuplexRow, CAPACITY};
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2DuplexStark<F, D>;
+
+    #[test]
+    fn poseidon2_duplex_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let rows: Vec<DuplexRow<F>> = (0..5)
+            .map(|i| DuplexRow {
+                absorbed: F::rand_array(),
+                overwritten: i % (RATE + 1),
+                squeezed: std::array::from_fn(|j| (i + j) % 2 == 0),
+            })
+            .collect();
+        let capacity: [F; CAPACITY] = F::rand_array();
+
+        let stark = S::default();
+        let (trace, public_inputs) = generate_duplex_trace(&capacity, &rows);
+        let squeezed = squeezed_elements(&trace);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        // the run does not start from another capacity
+        let mut forged = proof.clone();
+        forged.public_inputs[0] += F::ONE;
+        assert!(verify_stark_proof(stark, forged, &config).is_err());
+
+        // nor commit to another absorbed element, squeezed element or shape
+        let mut other_absorbed = rows.clone();
+        other_absorbed[1].absorbed[2] += F::ONE;
+        let mut other_squeezed = squeezed.clone();
+        other_squeezed[3] += F::ONE;
+        let mut other_shape = rows.clone();
+        other_shape[4].overwritten = 0;
+        for commitment in [
+            transcript_commitment(&other_absorbed, &squeezed),
+            transcript_commitment(&rows, &other_squeezed),
+            transcript_commitment(&other_shape, &squeezed),
+        ] {
+            let mut forged = proof.clone();
+            forged.public_inputs[CAPACITY..].copy_from_slice(&commitment);
+            assert!(verify_stark_proof(stark, forged, &config).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn poseidon2_duplex_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
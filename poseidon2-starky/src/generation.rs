@@ -1,171 +1,207 @@
 use crate::columns::{
-    COL_1ST_FULLROUND_STATE_START, COL_2ND_FULLROUND_STATE_START, COL_INPUT_START,
-    COL_OUTPUT_START, COL_PARTIAL_ROUND_END_STATE_START, COL_PARTIAL_ROUND_STATE_START, NUM_COLS,
-    ROUNDS_F, ROUNDS_P, STATE_SIZE,
+    col_2nd_fullround_state_start, col_partial_round_end_state_start,
+    col_partial_round_state_start, num_cols, COL_1ST_FULLROUND_STATE_START, COL_INPUT_START,
+    NUM_COLS, STATE_SIZE,
 };
+use crate::params::{Poseidon2Params, M4, POSEIDON2_PARAMS_128};
+use crate::stark::Poseidon2Stark;
 use ark_ff::{BigInteger, PrimeField};
-use num::bigint::BigUint;
+use permutation_air::generation::{fill_permutation_row, pad_trace};
 use plonky2::hash::hash_types::RichField;
 use std::convert::TryInto;
-use std::ops::AddAssign;
-use zkhash::fields::goldilocks::FpGoldiLocks;
-use zkhash::poseidon2::poseidon2::Poseidon2;
-use zkhash::poseidon2::poseidon2_instance_goldilocks::POSEIDON2_GOLDILOCKS_8_PARAMS;
 
 pub(crate) fn scalar_to_field<RF: RichField, PF: PrimeField>(scalar: &PF) -> RF {
-    RF::from_noncanonical_biguint(BigUint::from_bytes_le(&scalar.into_bigint().to_bytes_le()))
+    // `from_noncanonical_biguint` panics on zero, so fold the bytes instead
+    scalar
+        .into_bigint()
+        .to_bytes_le()
+        .iter()
+        .rev()
+        .fold(RF::ZERO, |acc, &byte| {
+            acc * RF::from_canonical_u16(256) + RF::from_canonical_u8(byte)
+        })
 }
 
 pub(crate) fn scalar_to_field_vec<RF: RichField, PF: PrimeField>(scalar: &[PF]) -> Vec<RF> {
     scalar.iter().map(|s| scalar_to_field(s)).collect()
 }
 
+#[cfg(test)]
 pub(crate) fn field_to_scalar<RF: RichField, PF: PrimeField>(field: &RF) -> PF {
     PF::from_le_bytes_mod_order(&field.to_canonical_biguint().to_bytes_le())
 }
 
+#[cfg(test)]
 pub(crate) fn field_to_scalar_vec<RF: RichField, PF: PrimeField>(field: &[RF]) -> Vec<PF> {
     field.iter().map(|f| field_to_scalar(f)).collect()
 }
 
 // Represent a row of the preimage
-#[derive(Debug, Clone, Default)]
-pub struct Row<Field: RichField> {
-    pub preimage: [Field; STATE_SIZE],
-}
+pub type Row<F> = permutation_air::generation::Row<F, STATE_SIZE>;
 
-/// Pad the trace to a power of 2.
-#[must_use]
-fn pad_trace<F: RichField>(mut trace: Vec<Vec<F>>) -> Vec<Vec<F>> {
-    let ext_trace_len = trace[0].len().next_power_of_two();
-
-    // All columns have their last value duplicated.
-    for row in &mut trace {
-        row.resize(ext_trace_len, *row.last().unwrap());
+fn matmul_external<Field: RichField>(state: &mut [Field; STATE_SIZE]) {
+    let mut out = [Field::ZERO; STATE_SIZE];
+    for i in 0..STATE_SIZE {
+        for j in 0..4 {
+            out[i] += state[i / 4 * 4 + j] * Field::from_canonical_usize(M4[i % 4][j]);
+        }
+    }
+    for i in 0..STATE_SIZE {
+        state[i] = out[i] + out[i % 4] + out[4 + i % 4];
     }
+}
 
-    trace
+fn matmul_internal<Field: RichField>(
+    state: &mut [Field; STATE_SIZE],
+    internal_diag_m_1: &[u64; STATE_SIZE],
+) {
+    let sum: Field = state.iter().copied().sum();
+    for i in 0..STATE_SIZE {
+        state[i] = state[i] * Field::from_canonical_u64(internal_diag_m_1[i]) + sum;
+    }
 }
 
-fn generate_1st_full_round_state<Field: RichField>(
+fn generate_1st_full_round_state<Field: RichField, const ROUNDS_F: usize, const ROUNDS_P: usize>(
+    params: &Poseidon2Params<ROUNDS_F, ROUNDS_P>,
     preimage: &[Field; STATE_SIZE],
 ) -> Vec<[Field; STATE_SIZE]> {
     let mut outputs = Vec::new();
-    let instance = Poseidon2::new(&POSEIDON2_GOLDILOCKS_8_PARAMS);
-    assert_eq!(instance.get_t(), STATE_SIZE);
-
-    let mut current_state = field_to_scalar_vec(preimage);
+    let mut current_state = *preimage;
 
     // Linear layer at beginning
-    instance.matmul_external(&mut current_state);
+    matmul_external(&mut current_state);
 
-    for r in 0..instance.params.rounds_f_beginning {
-        current_state = instance.add_rc(&current_state, &instance.params.round_constants[r]);
-        current_state = instance.sbox(&current_state);
-        instance.matmul_external(&mut current_state);
-        outputs.push(scalar_to_field_vec(&current_state).try_into().unwrap());
+    for r in 0..ROUNDS_F {
+        for (s, &rc) in current_state.iter_mut().zip(&params.round_constants()[r]) {
+            *s = (*s + Field::from_canonical_u64(rc)).exp_u64(params.sbox_degree() as u64);
+        }
+        matmul_external(&mut current_state);
+        outputs.push(current_state);
     }
 
     outputs
 }
 
-fn generate_partial_round_state<Field: RichField>(
+fn generate_partial_round_state<Field: RichField, const ROUNDS_F: usize, const ROUNDS_P: usize>(
+    params: &Poseidon2Params<ROUNDS_F, ROUNDS_P>,
     last_rount_output: &[Field; STATE_SIZE],
 ) -> Vec<[Field; STATE_SIZE]> {
     let mut outputs = Vec::new();
-    let instance = Poseidon2::new(&POSEIDON2_GOLDILOCKS_8_PARAMS);
-    assert_eq!(instance.get_t(), STATE_SIZE);
-
-    let mut current_state: Vec<FpGoldiLocks> = field_to_scalar_vec(last_rount_output);
+    let mut current_state = *last_rount_output;
 
-    let p_end = instance.params.rounds_f_beginning + instance.params.rounds_p;
-    for r in instance.params.rounds_f_beginning..p_end {
-        current_state[0].add_assign(&instance.params.round_constants[r][0]);
-        current_state[0] = instance.sbox_p(&current_state[0]);
-        instance.matmul_internal(&mut current_state, &instance.params.mat_internal_diag_m_1);
-        outputs.push(scalar_to_field_vec(&current_state).try_into().unwrap());
+    for r in ROUNDS_F..ROUNDS_F + ROUNDS_P {
+        current_state[0] += Field::from_canonical_u64(params.round_constants()[r][0]);
+        current_state[0] = current_state[0].exp_u64(params.sbox_degree() as u64);
+        matmul_internal(&mut current_state, params.internal_diag_m_1());
+        outputs.push(current_state);
     }
 
     outputs
 }
 
-fn generate_2st_full_round_state<Field: RichField>(
+fn generate_2st_full_round_state<Field: RichField, const ROUNDS_F: usize, const ROUNDS_P: usize>(
+    params: &Poseidon2Params<ROUNDS_F, ROUNDS_P>,
     last_rount_output: &[Field; STATE_SIZE],
 ) -> Vec<[Field; STATE_SIZE]> {
     let mut outputs = Vec::new();
-    let instance = Poseidon2::new(&POSEIDON2_GOLDILOCKS_8_PARAMS);
-    assert_eq!(instance.get_t(), STATE_SIZE);
+    let mut current_state = *last_rount_output;
 
-    let mut current_state = field_to_scalar_vec(last_rount_output);
-
-    let p_end = instance.params.rounds_f_beginning + instance.params.rounds_p;
-    for r in p_end..instance.params.rounds {
-        current_state = instance.add_rc(&current_state, &instance.params.round_constants[r]);
-        current_state = instance.sbox(&current_state);
-        instance.matmul_external(&mut current_state);
-        outputs.push(scalar_to_field_vec(&current_state).try_into().unwrap());
+    let p_end = ROUNDS_F + ROUNDS_P;
+    for r in p_end..p_end + ROUNDS_F {
+        for (s, &rc) in current_state.iter_mut().zip(&params.round_constants()[r]) {
+            *s = (*s + Field::from_canonical_u64(rc)).exp_u64(params.sbox_degree() as u64);
+        }
+        matmul_external(&mut current_state);
+        outputs.push(current_state);
     }
 
     outputs
 }
 
 /// Generate the outputs for a given preimage
-fn generate_outputs<Field: RichField>(preimage: &[Field; STATE_SIZE]) -> [Field; STATE_SIZE] {
-    let mut outputs = [Field::ZERO; STATE_SIZE];
-    let instance = Poseidon2::new(&POSEIDON2_GOLDILOCKS_8_PARAMS);
-    assert_eq!(instance.get_t(), STATE_SIZE);
-
-    let input = field_to_scalar_vec(preimage);
-    let perm = instance.permutation(&input);
+pub(crate) fn generate_outputs<Field: RichField, const ROUNDS_F: usize, const ROUNDS_P: usize>(
+    params: &Poseidon2Params<ROUNDS_F, ROUNDS_P>,
+    preimage: &[Field; STATE_SIZE],
+) -> [Field; STATE_SIZE] {
+    let first_full_round_state = generate_1st_full_round_state(params, preimage);
+    let partial_round_state =
+        generate_partial_round_state(params, first_full_round_state.last().unwrap());
+    let second_full_round_state =
+        generate_2st_full_round_state(params, partial_round_state.last().unwrap());
+    *second_full_round_state.last().unwrap()
+}
 
-    for i in 0..STATE_SIZE {
-        outputs[i] = scalar_to_field(&perm[i]);
+/// The row of `num_cols(ROUNDS_F, ROUNDS_P)` values proving the permutation
+/// of `preimage`
+pub(crate) fn generate_row_witness<F: RichField, const ROUNDS_F: usize, const ROUNDS_P: usize>(
+    params: &Poseidon2Params<ROUNDS_F, ROUNDS_P>,
+    preimage: &[F; STATE_SIZE],
+) -> Vec<F> {
+    let col_partial_round_state_start = col_partial_round_state_start(ROUNDS_F);
+    let col_partial_round_end_state_start = col_partial_round_end_state_start(ROUNDS_F, ROUNDS_P);
+    let col_2nd_fullround_state_start = col_2nd_fullround_state_start(ROUNDS_F, ROUNDS_P);
+    let mut row = vec![F::ZERO; num_cols(ROUNDS_F, ROUNDS_P)];
+
+    row[COL_INPUT_START..COL_INPUT_START + STATE_SIZE].copy_from_slice(preimage);
+
+    // Generate the full round states
+    let first_full_round_state = generate_1st_full_round_state(params, preimage);
+    let partial_round_state =
+        generate_partial_round_state(params, first_full_round_state.last().unwrap());
+    let second_full_round_state =
+        generate_2st_full_round_state(params, partial_round_state.last().unwrap());
+    for j in 0..ROUNDS_F {
+        let first = COL_1ST_FULLROUND_STATE_START + j * STATE_SIZE;
+        row[first..first + STATE_SIZE].copy_from_slice(&first_full_round_state[j]);
+        let second = col_2nd_fullround_state_start + j * STATE_SIZE;
+        row[second..second + STATE_SIZE].copy_from_slice(&second_full_round_state[j]);
     }
-    outputs
+    for j in 0..ROUNDS_P {
+        row[col_partial_round_state_start + j] = partial_round_state[j][0];
+    }
+    row[col_partial_round_end_state_start..col_partial_round_end_state_start + STATE_SIZE]
+        .copy_from_slice(&partial_round_state[ROUNDS_P - 1]);
+
+    row
+}
+
+/// The table of the 128-bit preset, whose
+/// [`PermutationAir`](permutation_air::PermutationAir) impl fills the
+/// permutation columns of the sub-tables. The extension degree only matters
+/// for proving.
+pub(crate) fn poseidon2_air<F: RichField>() -> Poseidon2Stark<F, 1> {
+    Poseidon2Stark::new(POSEIDON2_PARAMS_128.clone())
 }
 
-/// Function to generate the Poseidon2 trace
+/// Function to generate the Poseidon2 trace with the 128-bit preset
+#[must_use]
 pub fn generate_poseidon2_trace<F: RichField>(step_rows: &Vec<Row<F>>) -> [Vec<F>; NUM_COLS] {
+    generate_poseidon2_trace_with_params(&POSEIDON2_PARAMS_128, step_rows)
+}
+
+/// Function to generate the Poseidon2 trace for the given parameters
+#[must_use]
+pub fn generate_poseidon2_trace_with_params<
+    F: RichField,
+    const ROUNDS_F: usize,
+    const ROUNDS_P: usize,
+>(
+    params: &Poseidon2Params<ROUNDS_F, ROUNDS_P>,
+    step_rows: &Vec<Row<F>>,
+) -> [Vec<F>; num_cols(ROUNDS_F, ROUNDS_P)] {
     let trace_len = step_rows.len();
-    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; NUM_COLS];
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; num_cols(ROUNDS_F, ROUNDS_P)];
 
+    let air = Poseidon2Stark::<F, 1, ROUNDS_F, ROUNDS_P>::new(params.clone());
     for (i, row) in step_rows.iter().enumerate() {
-        for j in 0..STATE_SIZE {
-            trace[COL_INPUT_START + j][i] = row.preimage[j];
-        }
-        let outputs = generate_outputs(&row.preimage);
-        for j in 0..STATE_SIZE {
-            trace[COL_OUTPUT_START + j][i] = outputs[j];
-        }
-
-        // Generate the full round states
-        let first_full_round_state = generate_1st_full_round_state(&row.preimage);
-        let partial_round_state = generate_partial_round_state(
-            first_full_round_state.last().unwrap().try_into().unwrap(),
-        );
-        let second_full_round_state =
-            generate_2st_full_round_state(partial_round_state.last().unwrap().try_into().unwrap());
-        for j in 0..ROUNDS_F {
-            for k in 0..STATE_SIZE {
-                trace[COL_1ST_FULLROUND_STATE_START + j * STATE_SIZE + k][i] =
-                    first_full_round_state[j][k];
-                trace[COL_2ND_FULLROUND_STATE_START + j * STATE_SIZE + k][i] =
-                    second_full_round_state[j][k];
-            }
-        }
-        for j in 0..ROUNDS_P {
-            trace[COL_PARTIAL_ROUND_STATE_START + j][i] = partial_round_state[j][0];
-        }
-        for j in 0..STATE_SIZE {
-            trace[COL_PARTIAL_ROUND_END_STATE_START + j][i] = partial_round_state[ROUNDS_P - 1][j];
-        }
+        fill_permutation_row(&air, &mut trace, i, &row.preimage);
     }
 
     trace = pad_trace(trace);
     trace.try_into().unwrap_or_else(|v: Vec<Vec<F>>| {
         panic!(
             "Expected a Vec of length {} but it was {}",
-            NUM_COLS,
+            num_cols(ROUNDS_F, ROUNDS_P),
             v.len()
         )
     })
@@ -173,10 +209,14 @@ pub fn generate_poseidon2_trace<F: RichField>(step_rows: &Vec<Row<F>>) -> [Vec<F
 
 #[cfg(test)]
 mod test {
-    use crate::columns::{COL_OUTPUT_START, STATE_SIZE};
+    use crate::columns::{col_output_start, num_cols, COL_OUTPUT_START, STATE_SIZE};
     use crate::generation::{
         field_to_scalar_vec, generate_1st_full_round_state, generate_2st_full_round_state,
-        generate_outputs, generate_partial_round_state, scalar_to_field_vec, Row,
+        generate_outputs, generate_partial_round_state, generate_poseidon2_trace_with_params,
+        scalar_to_field_vec, Row,
+    };
+    use crate::params::{
+        Poseidon2Params, POSEIDON2_PARAMS_128, ROUNDS_F_REDUCED, ROUNDS_P_REDUCED,
     };
     use plonky2::field::goldilocks_field::GoldilocksField;
     use plonky2::field::types::{Field, PrimeField64, Sample};
@@ -185,6 +225,7 @@ mod test {
     use proptest::proptest;
     use zkhash::fields::goldilocks::FpGoldiLocks;
     use zkhash::poseidon2::poseidon2::Poseidon2;
+    use zkhash::poseidon2::poseidon2_instance_goldilocks::POSEIDON2_GOLDILOCKS_8_PARAMS;
 
     const D: usize = 2;
     type C = PoseidonGoldilocksConfig;
@@ -202,14 +243,15 @@ mod test {
 
     #[test]
     fn rounds_generation() {
+        let params = &*POSEIDON2_PARAMS_128;
         let preimage = (0..STATE_SIZE).map(|_| F::rand()).collect::<Vec<_>>();
         let output0: Vec<[F; STATE_SIZE]> =
-            generate_1st_full_round_state(&preimage.clone().try_into().unwrap());
+            generate_1st_full_round_state(params, &preimage.clone().try_into().unwrap());
         let output1: Vec<[F; STATE_SIZE]> =
-            generate_partial_round_state(output0.last().unwrap().try_into().unwrap());
+            generate_partial_round_state(params, output0.last().unwrap().try_into().unwrap());
         let output2: Vec<[F; STATE_SIZE]> =
-            generate_2st_full_round_state(output1.last().unwrap().try_into().unwrap());
-        let expected_output = generate_outputs(&preimage.try_into().unwrap());
+            generate_2st_full_round_state(params, output1.last().unwrap().try_into().unwrap());
+        let expected_output = generate_outputs(params, &preimage.try_into().unwrap());
         assert_eq!(expected_output, *output2.last().unwrap());
     }
 
@@ -229,7 +271,7 @@ mod test {
         for trace_item in &trace {
             assert_eq!(trace_item.len(), 16);
         }
-        let instance = Poseidon2::new(&super::POSEIDON2_GOLDILOCKS_8_PARAMS);
+        let instance = Poseidon2::new(&POSEIDON2_GOLDILOCKS_8_PARAMS);
         for (i, step_row) in step_rows.iter().enumerate().take(num_rows) {
             let input = step_row
                 .preimage
@@ -245,4 +287,30 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn generate_reduced_poseidon2_trace() {
+        let params = Poseidon2Params::reduced();
+        let step_rows = (0..5)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let trace = generate_poseidon2_trace_with_params(&params, &step_rows);
+        assert_eq!(trace.len(), num_cols(ROUNDS_F_REDUCED, ROUNDS_P_REDUCED));
+        for (i, step_row) in step_rows.iter().enumerate() {
+            let expected = generate_outputs(&params, &step_row.preimage);
+            for j in 0..STATE_SIZE {
+                assert_eq!(
+                    trace[col_output_start(ROUNDS_F_REDUCED, ROUNDS_P_REDUCED) + j][i],
+                    expected[j]
+                );
+            }
+        }
+        assert_ne!(
+            generate_outputs(&params, &step_rows[0].preimage),
+            generate_outputs(&*POSEIDON2_PARAMS_128, &step_rows[0].preimage)
+        );
+    }
 }
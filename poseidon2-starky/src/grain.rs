@@ -0,0 +1,410 @@
+//! Parameter generation following the Poseidon2 reference scripts
+//! (`poseidon2_rust_params.sage`): round numbers from the security bound, round
+//! constants from the Grain LFSR, and an internal diagonal sampled from the same
+//! LFSR until the internal matrix has no invariant subspace trails.
+
+use crate::columns::STATE_SIZE;
+use crate::params::Poseidon2Params;
+use anyhow::{ensure, Result};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::{Field, Field64, PrimeField64};
+
+type F = GoldilocksField;
+
+/// The number of bits of the Goldilocks modulus
+const FIELD_SIZE: usize = 64;
+
+/// The self-shrinking Grain LFSR used to generate Poseidon constants.
+#[allow(clippy::module_name_repetitions)]
+pub struct GrainLfsr {
+    state: [bool; 80],
+}
+
+impl GrainLfsr {
+    /// Seed the LFSR with the instance description of a prime field with an
+    /// `x^alpha` S-box. `rounds_f` counts the full rounds of both halves.
+    #[must_use]
+    pub fn new(state_size: usize, rounds_f: usize, rounds_p: usize) -> Self {
+        let mut state = [true; 80];
+        let fields = [
+            (1, 2), // field: GF(p)
+            (0, 4), // S-box: x^alpha
+            (FIELD_SIZE, 12),
+            (state_size, 12),
+            (rounds_f, 10),
+            (rounds_p, 10),
+        ];
+        let mut i = 0;
+        for (value, bits) in fields {
+            for k in (0..bits).rev() {
+                state[i] = value >> k & 1 == 1;
+                i += 1;
+            }
+        }
+        // the remaining 30 bits stay set
+
+        let mut lfsr = Self { state };
+        for _ in 0..160 {
+            lfsr.clock();
+        }
+        lfsr
+    }
+
+    fn clock(&mut self) -> bool {
+        let s = &self.state;
+        let new_bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.rotate_left(1);
+        self.state[79] = new_bit;
+        new_bit
+    }
+
+    /// The next output bit. Bits come in pairs and the second bit is only
+    /// output when the first one is set.
+    pub fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.clock();
+            let bit = self.clock();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// The next field element, sampled most significant bit first and
+    /// rejected while it is not below the modulus.
+    pub fn next_field_element(&mut self) -> F {
+        loop {
+            let value = (0..FIELD_SIZE).fold(0u64, |acc, _| acc << 1 | u64::from(self.next_bit()));
+            if value < F::ORDER {
+                return F::from_canonical_u64(value);
+            }
+        }
+    }
+}
+
+/// `log2(binomial(n, k))`
+#[allow(clippy::cast_precision_loss)]
+fn log2_binomial(n: usize, k: usize) -> f64 {
+    (1..=k)
+        .map(|i| ((n - k + i) as f64 / i as f64).log2())
+        .sum()
+}
+
+/// Whether `rounds_f` full and `rounds_p` partial rounds resist the attacks
+/// considered in the reference script at `security_level` bits.
+#[allow(clippy::cast_precision_loss)]
+fn rounds_are_secure(
+    state_size: usize,
+    rounds_f: usize,
+    rounds_p: usize,
+    sbox_degree: usize,
+    security_level: usize,
+) -> bool {
+    let t = state_size as f64;
+    let alpha = sbox_degree as f64;
+    let m = security_level as f64;
+    let rp = rounds_p as f64;
+    let log2_p = (F::ORDER as f64).log2();
+    let n = log2_p.ceil();
+
+    // statistical, interpolation and the three Groebner basis bounds
+    let rf_1 = if m <= (log2_p - (alpha - 1.0) / 2.0).floor() * (t + 1.0) {
+        6.0
+    } else {
+        10.0
+    };
+    let rf_2 = 1.0 + (2f64.log(alpha) * m.min(n)).ceil() + t.log(alpha).ceil() - rp;
+    let rf_3 = 2f64.log(alpha) * m.min(log2_p) - rp;
+    let rf_4 = t - 1.0 + 2f64.log(alpha) * (m / (t + 1.0)).min(log2_p / 2.0) - rp;
+    let rf_5 = (t - 2.0 + m / (2.0 * alpha.log2()) - rp) / (t - 1.0);
+    let rf_max = [rf_1, rf_2, rf_3, rf_4, rf_5]
+        .into_iter()
+        .map(f64::ceil)
+        .fold(f64::MIN, f64::max);
+
+    // the additional bound of https://eprint.iacr.org/2023/537
+    let r = state_size / 3;
+    let over =
+        (rounds_f - 1) * state_size + rounds_p + r + r * rounds_f / 2 + rounds_p + sbox_degree;
+    let under = r * rounds_f / 2 + rounds_p + sbox_degree;
+    let cost_gb4 = (2.0 * log2_binomial(over, under)).ceil();
+
+    rounds_f as f64 >= rf_max && cost_gb4 >= m
+}
+
+/// The number of full rounds (both halves) and partial rounds minimising the
+/// number of S-boxes for `security_level` bits, including the reference
+/// security margin of two full rounds and 7.5% more partial rounds.
+#[must_use]
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn round_numbers(
+    state_size: usize,
+    sbox_degree: usize,
+    security_level: usize,
+) -> (usize, usize) {
+    let mut best = (0, 0);
+    let mut min_cost = usize::MAX;
+    let mut min_cost_rounds_f = 0;
+
+    for rounds_p in 1..500 {
+        // the reference script keeps the margin-adjusted partial rounds for
+        // the rest of the inner loop
+        let mut rounds_p = rounds_p;
+        for rounds_f in (4..100).step_by(2) {
+            if !rounds_are_secure(state_size, rounds_f, rounds_p, sbox_degree, security_level) {
+                continue;
+            }
+            let rounds_f = rounds_f + 2;
+            rounds_p = (rounds_p as f64 * 1.075).ceil() as usize;
+            let cost = rounds_f * state_size + rounds_p;
+            if cost < min_cost || (cost == min_cost && rounds_f < min_cost_rounds_f) {
+                best = (rounds_f, rounds_p);
+                min_cost = cost;
+                min_cost_rounds_f = rounds_f;
+            }
+        }
+    }
+
+    best
+}
+
+/// Generate one constant per S-box: `STATE_SIZE` for full rounds and one for
+/// partial rounds, padded with zeros to the layout of [`Poseidon2Params`].
+pub fn generate_round_constants(
+    lfsr: &mut GrainLfsr,
+    rounds_f: usize,
+    rounds_p: usize,
+) -> Vec<[u64; STATE_SIZE]> {
+    let half = rounds_f / 2;
+    (0..rounds_f + rounds_p)
+        .map(|r| {
+            let mut rc = [0; STATE_SIZE];
+            let width = if (half..half + rounds_p).contains(&r) {
+                1
+            } else {
+                STATE_SIZE
+            };
+            for c in &mut rc[..width] {
+                *c = lfsr.next_field_element().to_canonical_u64();
+            }
+            rc
+        })
+        .collect()
+}
+
+type Matrix = [[F; STATE_SIZE]; STATE_SIZE];
+
+fn mat_mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut out = [[F::ZERO; STATE_SIZE]; STATE_SIZE];
+    for i in 0..STATE_SIZE {
+        for j in 0..STATE_SIZE {
+            out[i][j] = (0..STATE_SIZE).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+/// The characteristic polynomial, lowest coefficient first, computed with the
+/// Faddeev-LeVerrier algorithm.
+fn characteristic_polynomial(m: &Matrix) -> Vec<F> {
+    let n = STATE_SIZE;
+    let mut coeffs = vec![F::ZERO; n + 1];
+    coeffs[n] = F::ONE;
+    let mut mk = [[F::ZERO; STATE_SIZE]; STATE_SIZE];
+    for k in 1..=n {
+        mk = mat_mul(m, &mk);
+        for (i, row) in mk.iter_mut().enumerate() {
+            row[i] += coeffs[n - k + 1];
+        }
+        let product = mat_mul(m, &mk);
+        let trace: F = (0..n).map(|i| product[i][i]).sum();
+        coeffs[n - k] = -trace / F::from_canonical_usize(k);
+    }
+    coeffs
+}
+
+fn poly_trim(mut a: Vec<F>) -> Vec<F> {
+    while a.last() == Some(&F::ZERO) {
+        a.pop();
+    }
+    a
+}
+
+/// `a mod f` for a monic `f`
+fn poly_rem_monic(mut a: Vec<F>, f: &[F]) -> Vec<F> {
+    while a.len() >= f.len() {
+        let lead = a[a.len() - 1];
+        let shift = a.len() - f.len();
+        for (i, &c) in f.iter().enumerate() {
+            a[shift + i] -= lead * c;
+        }
+        a.pop();
+    }
+    poly_trim(a)
+}
+
+fn poly_mul_mod(a: &[F], b: &[F], f: &[F]) -> Vec<F> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut out = vec![F::ZERO; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+    poly_rem_monic(out, f)
+}
+
+fn poly_pow_mod(base: &[F], mut exp: u64, f: &[F]) -> Vec<F> {
+    let mut out = vec![F::ONE];
+    let mut base = base.to_vec();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            out = poly_mul_mod(&out, &base, f);
+        }
+        base = poly_mul_mod(&base, &base, f);
+        exp >>= 1;
+    }
+    out
+}
+
+fn poly_gcd(a: &[F], b: &[F]) -> Vec<F> {
+    let (mut a, mut b) = (poly_trim(a.to_vec()), poly_trim(b.to_vec()));
+    while !b.is_empty() {
+        let inv = b[b.len() - 1].inverse();
+        let monic: Vec<F> = b.iter().map(|&c| c * inv).collect();
+        let rem = poly_rem_monic(a, &monic);
+        a = b;
+        b = rem;
+    }
+    a
+}
+
+/// Rabin's irreducibility test for a monic polynomial of degree `n`:
+/// `x^(p^n) = x mod f` and `gcd(x^(p^(n/q)) - x, f) = 1` for every prime `q | n`.
+fn is_irreducible(f: &[F]) -> bool {
+    let n = f.len() - 1;
+    let x = vec![F::ZERO, F::ONE];
+
+    // frobenius[i] = x^(p^(i + 1)) mod f
+    let mut frobenius: Vec<Vec<F>> = Vec::with_capacity(n);
+    for _ in 0..n {
+        let power = poly_pow_mod(frobenius.last().unwrap_or(&x), F::ORDER, f);
+        frobenius.push(power);
+    }
+    if frobenius[n - 1] != x {
+        return false;
+    }
+
+    let x_minus = |mut a: Vec<F>| {
+        a.resize(a.len().max(2), F::ZERO);
+        a[1] -= F::ONE;
+        poly_trim(a)
+    };
+    (2..=n)
+        .filter(|&q| n % q == 0 && (2..q).all(|d| q % d != 0))
+        .all(|q| poly_gcd(f, &x_minus(frobenius[n / q - 1].clone())).len() == 1)
+}
+
+/// The internal matrix `diag(d) + J` must be invertible and the minimal
+/// polynomials of its first `2 * STATE_SIZE` powers irreducible of full
+/// degree, which rules out invariant subspace trails.
+#[must_use]
+pub fn internal_matrix_is_secure(diag: &[F; STATE_SIZE]) -> bool {
+    let mut m = [[F::ONE; STATE_SIZE]; STATE_SIZE];
+    for (i, row) in m.iter_mut().enumerate() {
+        row[i] = diag[i];
+    }
+
+    let mut power = m;
+    for _ in 0..2 * STATE_SIZE {
+        // an irreducible characteristic polynomial is also the minimal one
+        let charpoly = characteristic_polynomial(&power);
+        if charpoly[0] == F::ZERO || !is_irreducible(&charpoly) {
+            return false;
+        }
+        power = mat_mul(&m, &power);
+    }
+    true
+}
+
+/// Sample internal diagonals from the LFSR until one is secure, returned
+/// minus one as in [`Poseidon2Params::internal_diag_m_1`].
+pub fn generate_internal_diag_m_1(lfsr: &mut GrainLfsr) -> [u64; STATE_SIZE] {
+    loop {
+        let diag = [(); STATE_SIZE].map(|_| lfsr.next_field_element());
+        if internal_matrix_is_secure(&diag) {
+            return diag.map(|d| (d - F::ONE).to_canonical_u64());
+        }
+    }
+}
+
+/// Generate a full parameter set with the reference procedure.
+///
+/// # Errors
+/// Returns an error if the round counts do not fit the LFSR seed or the
+/// resulting parameters are rejected by [`Poseidon2Params::new`].
+pub fn generate_params<const ROUNDS_F: usize, const ROUNDS_P: usize>(
+    sbox_degree: usize,
+) -> Result<Poseidon2Params<ROUNDS_F, ROUNDS_P>> {
+    ensure!(
+        2 * ROUNDS_F < 1 << 10 && ROUNDS_P < 1 << 10,
+        "round numbers do not fit the Grain LFSR seed"
+    );
+    let mut lfsr = GrainLfsr::new(STATE_SIZE, 2 * ROUNDS_F, ROUNDS_P);
+    let round_constants = generate_round_constants(&mut lfsr, 2 * ROUNDS_F, ROUNDS_P);
+    let internal_diag_m_1 = generate_internal_diag_m_1(&mut lfsr);
+    Poseidon2Params::new(sbox_degree, round_constants, internal_diag_m_1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::{ROUNDS_F, ROUNDS_P, STATE_SIZE};
+    use crate::grain::{generate_params, internal_matrix_is_secure, is_irreducible, round_numbers};
+    use crate::params::{Poseidon2Params, POSEIDON2_PARAMS_128};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Field;
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn round_numbers_match_preset() {
+        assert_eq!(round_numbers(STATE_SIZE, 7, 128), (2 * ROUNDS_F, ROUNDS_P));
+    }
+
+    #[test]
+    fn generated_params_match_preset() {
+        let generated = generate_params::<ROUNDS_F, ROUNDS_P>(7).unwrap();
+        assert_eq!(
+            generated.round_constants(),
+            POSEIDON2_PARAMS_128.round_constants()
+        );
+        assert_eq!(
+            generated.internal_diag_m_1(),
+            POSEIDON2_PARAMS_128.internal_diag_m_1()
+        );
+        assert_eq!(generated, Poseidon2Params::security_128());
+    }
+
+    #[test]
+    fn weak_internal_matrices_are_rejected() {
+        // diag(2) + J has the eigenvalue 2 with multiplicity STATE_SIZE - 1
+        assert!(!internal_matrix_is_secure(&[F::TWO; STATE_SIZE]));
+        // diag(0) + J is singular
+        assert!(!internal_matrix_is_secure(&[F::ZERO; STATE_SIZE]));
+    }
+
+    #[test]
+    fn irreducibility() {
+        let x = F::from_canonical_u64;
+        // x^2 - 7 is irreducible since 7 is not a square mod p
+        assert!(is_irreducible(&[-x(7), x(0), x(1)]));
+        // x^2 - 4 = (x - 2)(x + 2)
+        assert!(!is_irreducible(&[-x(4), x(0), x(1)]));
+    }
+}
@@ -0,0 +1,479 @@
+//! Known-answer vectors for the width-8 Goldilocks instance, the only width
+//! the crate supports.
+//!
+//! The vectors were computed with the Poseidon2 reference implementation
+//! (zkhash, `Poseidon2::permutation`) instantiated with the constants of each
+//! parameter set, and checked in so that they do not move with the dependency.
+//! For the first vector of each set the intermediate round states are given as
+//! the trace row they produce in the layout of `columns.rs`.
+#![allow(clippy::unreadable_literal)]
+
+use crate::columns::{num_cols, NUM_COLS, STATE_SIZE};
+use crate::params::{ROUNDS_F_REDUCED, ROUNDS_P_REDUCED};
+
+struct Kat {
+    input: [u64; STATE_SIZE],
+    output: [u64; STATE_SIZE],
+}
+
+/// `Poseidon2Params::security_128()`
+const KATS_128: [Kat; 4] = [
+    Kat {
+        input: [
+            0x0000000000000000,
+            0x0000000000000001,
+            0x0000000000000002,
+            0x0000000000000003,
+            0x0000000000000004,
+            0x0000000000000005,
+            0x0000000000000006,
+            0x0000000000000007,
+        ],
+        output: [
+            0xc5fb1cfe0b4697bb,
+            0x4a4a32ff849af473,
+            0xd2fd266077f8efba,
+            0xf4ad9b74e833916d,
+            0xe6648eb0acc11463,
+            0x8d5529a930d75194,
+            0xe8c993aa10da6c90,
+            0xa73104a95b68031c,
+        ],
+    },
+    Kat {
+        input: [
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+        ],
+        output: [
+            0x3a7def562f511210,
+            0xab0afaf9756476a0,
+            0x8faf5cc269ff0a14,
+            0xd6818fc87ccd41ba,
+            0x8baed826fea3ff62,
+            0xe133a5f5d18335c6,
+            0x291171699652ccaa,
+            0xc63ff85a9e199a0d,
+        ],
+    },
+    Kat {
+        input: [
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+        ],
+        output: [
+            0x5caed410a9fd6849,
+            0x31151f18cf2b97fb,
+            0x0edda42264f38cda,
+            0xff14843277766957,
+            0x6421bfe1b7a30c8d,
+            0xe64b19fa554471ad,
+            0x2da3087bf1b4a9ec,
+            0xbd4766960009480e,
+        ],
+    },
+    Kat {
+        input: [
+            0x0123456789abcdef,
+            0xfedcba9876543210,
+            0xdeadbeefcafebabe,
+            0x0000000100000000,
+            0x00000000ffffffff,
+            0x8000000000000000,
+            0x13579bdf2468ace0,
+            0xffffffff00000000,
+        ],
+        output: [
+            0xed8789770d7e5024,
+            0xccb0b7d45b74e0cd,
+            0x0a8e3db4f28b3da2,
+            0x489e56710e41140f,
+            0x60439be7a020b2d6,
+            0xd2c8d7857e90c75a,
+            0x7f7270f75910bb2f,
+            0x708cf33558b2aeff,
+        ],
+    },
+];
+
+/// The trace row of `KATS_128[0]`
+const KAT_128_ROW: [u64; NUM_COLS] = [
+    0x0000000000000000,
+    0x0000000000000001,
+    0x0000000000000002,
+    0x0000000000000003,
+    0x0000000000000004,
+    0x0000000000000005,
+    0x0000000000000006,
+    0x0000000000000007,
+    0x1fb083b3af57a2dd,
+    0x412c29adc6ceb0f8,
+    0x4bbe7bfe5a00b7af,
+    0x3689d80e54f5cd67,
+    0xcf2a8820104a6cf1,
+    0x653c2c5fd6fefc3d,
+    0x71bd48cf85fe8202,
+    0xf69e72c580075273,
+    0xf58defa54a3cb667,
+    0xd1915810b612a151,
+    0xb0f4c14c5a2e9e4b,
+    0xaf7693070b1b9147,
+    0xc9f102d089981c25,
+    0x4be49c022a7bde35,
+    0x075cf945dfab8fea,
+    0xe121d8b916a6b89f,
+    0x3c88ebc02d135512,
+    0x7f97c48769be8c46,
+    0xce8294a961ccab2c,
+    0x1d01508fedf91e44,
+    0xb5f787253723f19a,
+    0xbe10f9b4ccd687ae,
+    0x87338feba09ed153,
+    0x2a2001b96fdde147,
+    0x34285457cce6e18c,
+    0x854b9da69c199558,
+    0x7e7d1058df1b64fc,
+    0x7857ebe43446e21c,
+    0xb405f953751963a8,
+    0x92b3dfe1867b5bee,
+    0x50f02d6cf0c23a32,
+    0x651692b4d625b925,
+    0x9625506a813ef46d,
+    0x4eb82a25164742cc,
+    0x096884aa4082cdfe,
+    0x5c0eee3bb33167f0,
+    0x25b055182fd9975d,
+    0xc3363810668506ba,
+    0x885a10a40bc3140d,
+    0x5befd25eae8ed426,
+    0x85bc166712ec9d6c,
+    0x4acedb1aab614652,
+    0x0a527dc4e3152f27,
+    0xd55413cbb49efef5,
+    0x34ab7e82274f2e49,
+    0x63382f8c97f05444,
+    0xdfb3b79a894feed9,
+    0x9cba82d300987b00,
+    0x95762271673c2c2c,
+    0x5d2c37d0672ae8db,
+    0x830db405ff579179,
+    0xcbb3cfbc3c8ff465,
+    0x87e82f63a2ec1c98,
+    0x96482433d8b9e80b,
+    0xafae090514dd969a,
+    0x4614f1001cc06125,
+    0x00a8b2be67d125df,
+    0x275b721227c2de74,
+    0xe3cb640f5b3bef6d,
+    0x6c6a09775a1ccf02,
+    0x1142fd9bffe427e9,
+    0x60d9b3a9a5023d2e,
+    0xf5abe852ae35fdb7,
+    0xfbe73e4eeb9d355d,
+    0x84d539340a1dd30a,
+    0x25dc1fe916059bec,
+    0x2e900a1ee8b846b5,
+    0x0ad2b118ea246a92,
+    0x7f56f005895da7c3,
+    0xa16ac7b475fe3547,
+    0x10841de76fb46e5e,
+    0x864cbfa4ee4f9915,
+    0xdaca1efe2a476ed5,
+    0x5330051b39f44e39,
+    0x5b3b58d3d4f08800,
+    0xef3fea0cdec4bf41,
+    0x02f48045e04b127c,
+    0x957e9a818622352b,
+    0x298bf99ba5fd5c8d,
+    0x05dc1e834d391264,
+    0xf1dd652af97aba4b,
+    0x8e60cf9c6cb45b65,
+    0xbe68de2cc98c9aa7,
+    0x9d7100b3f88a4ee8,
+    0x402055290ed64256,
+    0xc5fb1cfe0b4697bb,
+    0x4a4a32ff849af473,
+    0xd2fd266077f8efba,
+    0xf4ad9b74e833916d,
+    0xe6648eb0acc11463,
+    0x8d5529a930d75194,
+    0xe8c993aa10da6c90,
+    0xa73104a95b68031c,
+];
+
+/// `Poseidon2Params::reduced()`
+const KATS_REDUCED: [Kat; 4] = [
+    Kat {
+        input: [
+            0x0000000000000000,
+            0x0000000000000001,
+            0x0000000000000002,
+            0x0000000000000003,
+            0x0000000000000004,
+            0x0000000000000005,
+            0x0000000000000006,
+            0x0000000000000007,
+        ],
+        output: [
+            0x5f4dcfd1dfb62e21,
+            0xbf18b0697d08a192,
+            0x17e99a228f1f0eba,
+            0x5a7f746afce12b97,
+            0x5e10e5fc725d931b,
+            0xf27457b7418e1e61,
+            0x02180f3672c72639,
+            0xe58f5c5340630f1a,
+        ],
+    },
+    Kat {
+        input: [
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+            0x0000000000000000,
+        ],
+        output: [
+            0x359e8e734bff136c,
+            0x7a3f6a83fedc3113,
+            0x6aaa5ba664b15d99,
+            0x5d900541888d7d0b,
+            0x77a8d417e4d971c3,
+            0x74b3bdc83cbe3670,
+            0x42f6d5175cc20c00,
+            0x07ccc42b060e89a1,
+        ],
+    },
+    Kat {
+        input: [
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+            0xffffffff00000000,
+        ],
+        output: [
+            0xb8bc19240d02ef2d,
+            0x124122877ba310b2,
+            0x3cd6e478ff13783b,
+            0x2c5cf406793af51c,
+            0x5c86439ede78a720,
+            0x7a19e1ccaf343e3a,
+            0x697531ecf646d0f5,
+            0x086967eb6969d16f,
+        ],
+    },
+    Kat {
+        input: [
+            0x0123456789abcdef,
+            0xfedcba9876543210,
+            0xdeadbeefcafebabe,
+            0x0000000100000000,
+            0x00000000ffffffff,
+            0x8000000000000000,
+            0x13579bdf2468ace0,
+            0xffffffff00000000,
+        ],
+        output: [
+            0x49cd209cb10294cb,
+            0xaa645ada240da749,
+            0x9c99ecd2474d286c,
+            0x41211a7e5482a2ad,
+            0x980aa525d64d9d0a,
+            0x7ae12f5de0095679,
+            0x92432eaeaac0f6a3,
+            0x488f3be324be367b,
+        ],
+    },
+];
+
+/// The trace row of `KATS_REDUCED[0]`
+const KAT_REDUCED_ROW: [u64; num_cols(ROUNDS_F_REDUCED, ROUNDS_P_REDUCED)] = [
+    0x0000000000000000,
+    0x0000000000000001,
+    0x0000000000000002,
+    0x0000000000000003,
+    0x0000000000000004,
+    0x0000000000000005,
+    0x0000000000000006,
+    0x0000000000000007,
+    0x1fb083b3af57a2dd,
+    0x412c29adc6ceb0f8,
+    0x4bbe7bfe5a00b7af,
+    0x3689d80e54f5cd67,
+    0xcf2a8820104a6cf1,
+    0x653c2c5fd6fefc3d,
+    0x71bd48cf85fe8202,
+    0xf69e72c580075273,
+    0xf58defa54a3cb667,
+    0xd1915810b612a151,
+    0xb0f4c14c5a2e9e4b,
+    0xaf7693070b1b9147,
+    0xc9f102d089981c25,
+    0x4be49c022a7bde35,
+    0x075cf945dfab8fea,
+    0xe121d8b916a6b89f,
+    0x0e1513fb061aae56,
+    0xff7291221caaccc3,
+    0x30b8e8ff5c0a2253,
+    0x6d1b8b4fe15bce48,
+    0x6cde13df099c05bf,
+    0x63be6c7d847699ea,
+    0xc5a6f6de26d1ed06,
+    0x38e614ccbab18493,
+    0x441ab7abd68caefd,
+    0xf3563a3c71b6375a,
+    0x1c97d8900e51eb6f,
+    0xa41dd1817390f537,
+    0x1fe8f320abedf94e,
+    0xa7feb8b925f04404,
+    0xc274d368822e4678,
+    0x5205c6f028d6ed2c,
+    0x5e803af156874076,
+    0x78eae85513b1520a,
+    0xccbc99f12af58f53,
+    0xaf56323600077f8c,
+    0x0897e5cca117900f,
+    0xc5a1f1c5641cb7e9,
+    0xb043d426a85aeb04,
+    0x5f4dcfd1dfb62e21,
+    0xbf18b0697d08a192,
+    0x17e99a228f1f0eba,
+    0x5a7f746afce12b97,
+    0x5e10e5fc725d931b,
+    0xf27457b7418e1e61,
+    0x02180f3672c72639,
+    0xe58f5c5340630f1a,
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::{col_output_start, ROUNDS_F, ROUNDS_P, STATE_SIZE};
+    use crate::generation::{generate_outputs, generate_poseidon2_trace_with_params, Row};
+    use crate::kat::{Kat, KATS_128, KATS_REDUCED, KAT_128_ROW, KAT_REDUCED_ROW};
+    use crate::params::{Poseidon2Params, ROUNDS_F_REDUCED, ROUNDS_P_REDUCED};
+    use crate::stark::{trace_to_poly_values, Poseidon2Stark};
+    use anyhow::Result;
+    use plonky2::field::types::{Field, PrimeField64};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn to_field(values: &[u64; STATE_SIZE]) -> [F; STATE_SIZE] {
+        values.map(F::from_canonical_u64)
+    }
+
+    fn kat_rows(kats: &[Kat]) -> Vec<Row<F>> {
+        kats.iter()
+            .map(|kat| Row {
+                preimage: to_field(&kat.input),
+            })
+            .collect()
+    }
+
+    /// The trace has one row per vector: the first row must match the checked
+    /// in round states and every row must end in the expected output.
+    fn check_trace(trace: &[Vec<F>], kats: &[Kat], row: &[u64], output_start: usize) {
+        let first_row: Vec<u64> = trace.iter().map(|col| col[0].to_canonical_u64()).collect();
+        assert_eq!(first_row, row);
+        for (i, kat) in kats.iter().enumerate() {
+            let output: Vec<u64> = trace[output_start..output_start + STATE_SIZE]
+                .iter()
+                .map(|col| col[i].to_canonical_u64())
+                .collect();
+            assert_eq!(output, kat.output, "vector {i}");
+        }
+    }
+
+    fn config() -> StarkConfig {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+        config
+    }
+
+    #[test]
+    fn kats_native() {
+        let params = Poseidon2Params::security_128();
+        for kat in &KATS_128 {
+            let output = generate_outputs(&params, &to_field(&kat.input));
+            assert_eq!(output, to_field(&kat.output));
+        }
+
+        let params = Poseidon2Params::reduced();
+        for kat in &KATS_REDUCED {
+            let output = generate_outputs(&params, &to_field(&kat.input));
+            assert_eq!(output, to_field(&kat.output));
+        }
+    }
+
+    #[test]
+    fn kats_trace_and_stark_128() -> Result<()> {
+        type S = Poseidon2Stark<F, D>;
+        let stark = S::default();
+        let trace = generate_poseidon2_trace_with_params(&stark.params, &kat_rows(&KATS_128));
+        check_trace(
+            &trace,
+            &KATS_128,
+            &KAT_128_ROW,
+            col_output_start(ROUNDS_F, ROUNDS_P),
+        );
+
+        let config = config();
+        let proof = prove::<F, C, S, D>(
+            stark.clone(),
+            &config,
+            trace_to_poly_values(trace),
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn kats_trace_and_stark_reduced() -> Result<()> {
+        type S = Poseidon2Stark<F, D, ROUNDS_F_REDUCED, ROUNDS_P_REDUCED>;
+        let stark = S::new(Poseidon2Params::reduced());
+        let trace = generate_poseidon2_trace_with_params(&stark.params, &kat_rows(&KATS_REDUCED));
+        check_trace(
+            &trace,
+            &KATS_REDUCED,
+            &KAT_REDUCED_ROW,
+            col_output_start(ROUNDS_F_REDUCED, ROUNDS_P_REDUCED),
+        );
+
+        let config = config();
+        let proof = prove::<F, C, S, D>(
+            stark.clone(),
+            &config,
+            trace_to_poly_values(trace),
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+}
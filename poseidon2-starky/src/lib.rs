@@ -8,6 +8,22 @@
 #![deny(clippy::cargo)]
 #![allow(clippy::missing_panics_doc)]
 
+pub mod aggregation;
+pub mod bytes;
+pub mod challenger;
 pub mod columns;
+pub mod domain;
+pub mod duplex;
 pub mod generation;
+pub mod grain;
+#[cfg(test)]
+mod kat;
+pub mod merkle;
+pub mod mmr;
+pub mod params;
+pub mod poseidon;
+pub mod shrink;
+pub mod smt;
+pub mod sponge;
 pub mod stark;
+pub mod trace_io;
@@ -0,0 +1,41 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::NUM_COLS;
+
+/// The permutation compressing the children of the node of this row, laid
+/// out as in `crate::columns`
+pub(crate) const COL_PERMUTATION_START: usize = 0;
+
+/// The heap index of the node of this row, equal to the row number
+pub(crate) const COL_INDEX: usize = COL_PERMUTATION_START + NUM_COLS; // 101
+
+/// The parity of the index
+pub(crate) const COL_PARITY: usize = COL_INDEX + 1; // 102
+
+/// 1 in the first row, which holds the root instead of a node
+pub(crate) const COL_IS_FIRST: usize = COL_PARITY + 1; // 103
+
+/// The number of leaves, in every row
+pub(crate) const COL_NUM_LEAVES: usize = COL_IS_FIRST + 1; // 104
+
+/// The node of this row: the output of the permutation, or the left input in
+/// the first row
+pub(crate) const COL_NODE_START: usize = COL_NUM_LEAVES + 1; // 105
+
+/// The leaf with heap index `num_leaves + (index ^ 1)`
+pub(crate) const COL_LEAF_START: usize = COL_NODE_START + DIGEST_SIZE; // 109
+
+/// The heap indices of the left and the right child
+pub(crate) const COL_LEFT_INDEX: usize = COL_LEAF_START + DIGEST_SIZE; // 113
+pub(crate) const COL_RIGHT_INDEX: usize = COL_LEFT_INDEX + 1; // 114
+
+/// The even and the odd one of the node and the leaf of this row, as heap
+/// index followed by the digest
+pub(crate) const COL_EVEN_START: usize = COL_RIGHT_INDEX + 1; // 115
+pub(crate) const COL_ODD_START: usize = COL_EVEN_START + 1 + DIGEST_SIZE; // 120
+
+/// The permutation compressing the leaves of the rows above with the leaf of
+/// this row, laid out as in `crate::columns`
+pub(crate) const COL_LEAVES_PERMUTATION_START: usize = COL_ODD_START + 1 + DIGEST_SIZE; // 125
+
+/// The total number of columns
+pub(crate) const MERKLE_NUM_COLS: usize = COL_LEAVES_PERMUTATION_START + NUM_COLS; // 226
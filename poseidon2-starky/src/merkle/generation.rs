@@ -0,0 +1,138 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::{COL_OUTPUT_START, STATE_SIZE};
+use crate::generation::poseidon2_air;
+use crate::merkle::columns::{
+    COL_EVEN_START, COL_INDEX, COL_IS_FIRST, COL_LEAF_START, COL_LEAVES_PERMUTATION_START,
+    COL_LEFT_INDEX, COL_NODE_START, COL_NUM_LEAVES, COL_ODD_START, COL_PARITY,
+    COL_PERMUTATION_START, COL_RIGHT_INDEX, MERKLE_NUM_COLS,
+};
+use crate::merkle::MerkleTree;
+use permutation_air::generation::fill_permutation_row;
+use plonky2::hash::hash_types::RichField;
+
+/// The number of public inputs: the root and the commitment to the leaves
+pub const MERKLE_PUBLIC_INPUTS: usize = 2 * DIGEST_SIZE;
+
+/// Function to generate the trace of `tree`, one row per heap index, and the
+/// public inputs: the root and
+/// [`MerkleTree::leaves_commitment`].
+///
+/// Row `i > 0` compresses the children of node `i`. Row 0 has node 0, which
+/// is zero, as left child and the root as right child, and holds node 0
+/// instead of its output, so the root is the only node not consumed as a
+/// child. Every row also holds one leaf, so the trace has as many rows as
+/// the tree has leaves. A second permutation per row compresses the leaf of
+/// the row into the commitment to the leaves of the rows above.
+#[must_use]
+pub fn generate_merkle_trace<F: RichField>(
+    tree: &MerkleTree<F>,
+) -> ([Vec<F>; MERKLE_NUM_COLS], [F; MERKLE_PUBLIC_INPUTS]) {
+    let num_leaves = tree.num_leaves();
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; num_leaves]; MERKLE_NUM_COLS];
+
+    let air = poseidon2_air();
+    let mut commitment = [F::ZERO; DIGEST_SIZE];
+    for row in 0..num_leaves {
+        let mut input = [F::ZERO; STATE_SIZE];
+        input[..DIGEST_SIZE].copy_from_slice(&tree.node(2 * row));
+        input[DIGEST_SIZE..].copy_from_slice(&tree.node(2 * row + 1));
+        fill_permutation_row(&air, &mut trace[COL_PERMUTATION_START..], row, &input);
+        debug_assert!(
+            row == 0 || trace[COL_PERMUTATION_START + COL_OUTPUT_START][row] == tree.node(row)[0]
+        );
+
+        let node = (row, tree.node(row));
+        let leaf_index = num_leaves + (row ^ 1);
+        let leaf = (leaf_index, tree.node(leaf_index));
+        let (even, odd) = if row % 2 == 0 {
+            (node, leaf)
+        } else {
+            (leaf, node)
+        };
+
+        trace[COL_INDEX][row] = F::from_canonical_usize(row);
+        trace[COL_PARITY][row] = F::from_canonical_usize(row % 2);
+        trace[COL_IS_FIRST][row] = F::from_bool(row == 0);
+        trace[COL_NUM_LEAVES][row] = F::from_canonical_usize(num_leaves);
+        trace[COL_LEFT_INDEX][row] = F::from_canonical_usize(2 * row);
+        trace[COL_RIGHT_INDEX][row] = F::from_canonical_usize(2 * row + 1);
+        for (start, (index, digest)) in [(COL_EVEN_START, even), (COL_ODD_START, odd)] {
+            trace[start][row] = F::from_canonical_usize(index);
+            for j in 0..DIGEST_SIZE {
+                trace[start + 1 + j][row] = digest[j];
+            }
+        }
+        for j in 0..DIGEST_SIZE {
+            trace[COL_NODE_START + j][row] = node.1[j];
+            trace[COL_LEAF_START + j][row] = leaf.1[j];
+        }
+
+        let mut input = [F::ZERO; STATE_SIZE];
+        input[..DIGEST_SIZE].copy_from_slice(&commitment);
+        input[DIGEST_SIZE..].copy_from_slice(&leaf.1);
+        fill_permutation_row(
+            &air,
+            &mut trace[COL_LEAVES_PERMUTATION_START..],
+            row,
+            &input,
+        );
+        for j in 0..DIGEST_SIZE {
+            commitment[j] = trace[COL_LEAVES_PERMUTATION_START + COL_OUTPUT_START + j][row];
+        }
+    }
+    debug_assert_eq!(commitment, tree.leaves_commitment());
+
+    let trace = trace.try_into().unwrap_or_else(|v: Vec<Vec<F>>| {
+        panic!(
+            "Expected a Vec of length {} but it was {}",
+            MERKLE_NUM_COLS,
+            v.len()
+        )
+    });
+    let mut public_inputs = [F::ZERO; MERKLE_PUBLIC_INPUTS];
+    public_inputs[..DIGEST_SIZE].copy_from_slice(&tree.root());
+    public_inputs[DIGEST_SIZE..].copy_from_slice(&commitment);
+    (trace, public_inputs)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bytes::DIGEST_SIZE;
+    use crate::columns::COL_INPUT_START;
+    use crate::merkle::columns::{COL_LEAF_START, COL_NODE_START, COL_PERMUTATION_START};
+    use crate::merkle::generation::generate_merkle_trace;
+    use crate::merkle::MerkleTree;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn generate_merkle_trace_nodes() {
+        let leaves: Vec<[F; 4]> = (0..6).map(|_| F::rand_array()).collect();
+        let tree = MerkleTree::new(&leaves);
+
+        let (trace, public_inputs) = generate_merkle_trace(&tree);
+        let root = tree.root();
+        assert_eq!(trace[0].len(), 8);
+        assert_eq!(public_inputs[..DIGEST_SIZE], root);
+        assert_eq!(public_inputs[DIGEST_SIZE..], tree.leaves_commitment());
+        for row in 0..8 {
+            for j in 0..DIGEST_SIZE {
+                assert_eq!(trace[COL_LEAF_START + j][row], tree.leaves()[row ^ 1][j]);
+                if row > 0 {
+                    assert_eq!(trace[COL_NODE_START + j][row], tree.node(row)[j]);
+                }
+            }
+        }
+        // the first row takes the root as right child
+        for j in 0..DIGEST_SIZE {
+            assert_eq!(
+                trace[COL_PERMUTATION_START + COL_INPUT_START + DIGEST_SIZE + j][0],
+                root[j]
+            );
+        }
+    }
+}
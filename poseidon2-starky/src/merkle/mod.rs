@@ -0,0 +1,139 @@
+//! Poseidon2 Merkle trees over a vector of leaves, with a STARK proving every
+//! internal node of a tree.
+//!
+//! Leaves are padded with zero leaves to a power of two, at least 2. Nodes
+//! are numbered as in a binary heap: the root is node 1, the children of
+//! node `i` are nodes `2i` and `2i + 1`, and the `n` leaves are nodes `n` to
+//! `2n - 1`. Children are compressed with [`compress`], as in the sparse
+//! Merkle tree of [`crate::smt`].
+#![allow(clippy::module_name_repetitions)]
+
+pub mod columns;
+pub mod generation;
+pub mod stark;
+
+use crate::bytes::DIGEST_SIZE;
+use crate::smt::{compress, Digest};
+use plonky2::hash::hash_types::RichField;
+
+/// A Merkle tree with all its nodes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree<F> {
+    /// The nodes in heap order; node 0 is unused and zero
+    nodes: Vec<Digest<F>>,
+}
+
+impl<F: RichField> MerkleTree<F> {
+    /// Build the tree of `leaves`, padded with zero leaves
+    #[must_use]
+    pub fn new(leaves: &[Digest<F>]) -> Self {
+        let num_leaves = leaves.len().max(2).next_power_of_two();
+        let mut nodes = vec![[F::ZERO; DIGEST_SIZE]; 2 * num_leaves];
+        nodes[num_leaves..num_leaves + leaves.len()].copy_from_slice(leaves);
+        for i in (1..num_leaves).rev() {
+            nodes[i] = compress(&nodes[2 * i], &nodes[2 * i + 1]);
+        }
+        Self { nodes }
+    }
+
+    /// The number of leaves after padding
+    #[must_use]
+    pub fn num_leaves(&self) -> usize {
+        self.nodes.len() / 2
+    }
+
+    /// The leaves after padding
+    #[must_use]
+    pub fn leaves(&self) -> &[Digest<F>] {
+        &self.nodes[self.num_leaves()..]
+    }
+
+    /// The node with heap index `index`
+    #[must_use]
+    pub fn node(&self, index: usize) -> Digest<F> {
+        self.nodes[index]
+    }
+
+    #[must_use]
+    pub fn root(&self) -> Digest<F> {
+        self.nodes[1]
+    }
+
+    /// The commitment to the leaves exposed by
+    /// [`Poseidon2MerkleStark`](stark::Poseidon2MerkleStark): the leaves
+    /// compressed one by one into an accumulator starting at zero, in the
+    /// order of the rows of the trace, which hold leaves 1, 0, 3, 2, ...
+    #[must_use]
+    pub fn leaves_commitment(&self) -> Digest<F> {
+        let leaves = self.leaves();
+        (0..leaves.len()).fold([F::ZERO; DIGEST_SIZE], |acc, i| {
+            compress(&acc, &leaves[i ^ 1])
+        })
+    }
+
+    /// The siblings of the path from leaf `leaf` to the root
+    #[must_use]
+    pub fn siblings(&self, leaf: usize) -> Vec<Digest<F>> {
+        let mut index = self.num_leaves() + leaf;
+        let mut siblings = Vec::new();
+        while index > 1 {
+            siblings.push(self.nodes[index ^ 1]);
+            index >>= 1;
+        }
+        siblings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::merkle::MerkleTree;
+    use crate::smt::{compress, SparseMerkleTree};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn small_trees() {
+        let leaves: Vec<[F; 4]> = (0..4).map(|_| F::rand_array()).collect();
+        let tree = MerkleTree::new(&leaves);
+        assert_eq!(
+            tree.root(),
+            compress(
+                &compress(&leaves[0], &leaves[1]),
+                &compress(&leaves[2], &leaves[3])
+            )
+        );
+
+        // a single leaf is paired with a zero leaf
+        let single = MerkleTree::new(&leaves[..1]);
+        assert_eq!(single.num_leaves(), 2);
+        assert_eq!(single.root(), compress(&leaves[0], &[F::ZERO; 4]));
+    }
+
+    #[test]
+    fn padded_leaves_are_zero() {
+        let leaves: Vec<[F; 4]> = (0..5).map(|_| F::rand_array()).collect();
+        let tree = MerkleTree::new(&leaves);
+        let mut padded = leaves;
+        padded.resize(8, [F::ZERO; 4]);
+        assert_eq!(tree.num_leaves(), 8);
+        assert_eq!(tree, MerkleTree::new(&padded));
+        assert_eq!(tree.leaves(), padded);
+    }
+
+    #[test]
+    fn matches_a_full_sparse_merkle_tree_path() {
+        // the sparse tree with the same leaves at keys 0..8 has the tree of
+        // the leaves at the bottom of its left-most path
+        let leaves: Vec<[F; 4]> = (0..8).map(|_| F::rand_array()).collect();
+        let tree = MerkleTree::new(&leaves);
+        let mut smt = SparseMerkleTree::new();
+        let mut witness = None;
+        for (key, leaf) in (0..).zip(&leaves) {
+            witness = Some(smt.update(key, *leaf));
+        }
+        let witness = witness.unwrap();
+        assert_eq!(witness.siblings[..3], tree.siblings(7));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::{COL_INPUT_START, COL_OUTPUT_START};
+use crate::merkle::columns::{
+    COL_EVEN_START, COL_INDEX, COL_IS_FIRST, COL_LEAF_START, COL_LEAVES_PERMUTATION_START,
+    COL_LEFT_INDEX, COL_NODE_START, COL_NUM_LEAVES, COL_ODD_START, COL_PARITY,
+    COL_PERMUTATION_START, COL_RIGHT_INDEX, MERKLE_NUM_COLS,
+};
+use crate::merkle::generation::MERKLE_PUBLIC_INPUTS;
+use crate::params::POSEIDON2_PARAMS_128;
+use crate::stark::eval_permutation;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::permutation::PermutationPair;
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+/// Every internal node of a Merkle tree: row `i` compresses the children of
+/// node `i`, and a permutation argument matches the children consumed by
+/// all rows, `(2i, left)` and `(2i + 1, right)`, with the nodes and leaves
+/// held by all rows, `(i, node)` and `(num_leaves + (i ^ 1), leaf)`. Since
+/// row 0 consumes the root as node 1, the first public input is the root of
+/// the leaves in the trace. A second permutation per row compresses the leaves
+/// of the rows one by one, and the second public input is the result,
+/// [`MerkleTree::leaves_commitment`](crate::merkle::MerkleTree::leaves_commitment),
+/// which binds the proof to the leaf vector.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Poseidon2MerkleStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Poseidon2MerkleStark<F, D> {
+    const COLUMNS: usize = MERKLE_NUM_COLS;
+    const PUBLIC_INPUTS: usize = MERKLE_PUBLIC_INPUTS;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let lv = vars.local_values;
+        let nv = vars.next_values;
+        eval_permutation(
+            &POSEIDON2_PARAMS_128,
+            &lv[COL_PERMUTATION_START..],
+            yield_constr,
+        );
+        let input = &lv[COL_PERMUTATION_START + COL_INPUT_START..];
+        let output = &lv[COL_PERMUTATION_START + COL_OUTPUT_START..];
+
+        // the rows are numbered from 0 and only the first row is flagged
+        let index = lv[COL_INDEX];
+        let parity = lv[COL_PARITY];
+        let is_first = lv[COL_IS_FIRST];
+        yield_constr.constraint_first_row(index);
+        yield_constr.constraint_first_row(parity);
+        yield_constr.constraint_first_row(is_first - P::ONES);
+        yield_constr.constraint_transition(nv[COL_INDEX] - index - P::ONES);
+        yield_constr.constraint_transition(nv[COL_PARITY] + parity - P::ONES);
+        yield_constr.constraint_transition(nv[COL_IS_FIRST]);
+        yield_constr.constraint_transition(nv[COL_NUM_LEAVES] - lv[COL_NUM_LEAVES]);
+
+        // the children of the node of this row
+        let two = FE::from_canonical_u64(2);
+        yield_constr.constraint(lv[COL_LEFT_INDEX] - index * two);
+        yield_constr.constraint(lv[COL_RIGHT_INDEX] - index * two - P::ONES);
+
+        // the node is the parent of the children, except for the first row,
+        // whose right child is the root
+        for j in 0..DIGEST_SIZE {
+            yield_constr
+                .constraint(lv[COL_NODE_START + j] - output[j] - is_first * (input[j] - output[j]));
+            yield_constr.constraint_first_row(input[DIGEST_SIZE + j] - vars.public_inputs[j]);
+        }
+
+        // the node and the leaf of this row, sorted by parity
+        let leaf_index = lv[COL_NUM_LEAVES] + index + P::ONES - parity * two;
+        let even_index = index + parity * (leaf_index - index);
+        let odd_index = leaf_index + parity * (index - leaf_index);
+        yield_constr.constraint(lv[COL_EVEN_START] - even_index);
+        yield_constr.constraint(lv[COL_ODD_START] - odd_index);
+        for j in 0..DIGEST_SIZE {
+            let node = lv[COL_NODE_START + j];
+            let leaf = lv[COL_LEAF_START + j];
+            yield_constr.constraint(lv[COL_EVEN_START + 1 + j] - (node + parity * (leaf - node)));
+            yield_constr.constraint(lv[COL_ODD_START + 1 + j] - (leaf + parity * (node - leaf)));
+        }
+
+        // the commitment to the leaves compresses the leaf of every row,
+        // starting from zero
+        eval_permutation(
+            &POSEIDON2_PARAMS_128,
+            &lv[COL_LEAVES_PERMUTATION_START..],
+            yield_constr,
+        );
+        let leaves_input = &lv[COL_LEAVES_PERMUTATION_START + COL_INPUT_START..];
+        let leaves_output = &lv[COL_LEAVES_PERMUTATION_START + COL_OUTPUT_START..];
+        let next_leaves_input = &nv[COL_LEAVES_PERMUTATION_START + COL_INPUT_START..];
+        for j in 0..DIGEST_SIZE {
+            yield_constr.constraint_first_row(leaves_input[j]);
+            yield_constr.constraint(leaves_input[DIGEST_SIZE + j] - lv[COL_LEAF_START + j]);
+            yield_constr.constraint_transition(next_leaves_input[j] - leaves_output[j]);
+            yield_constr
+                .constraint_last_row(leaves_output[j] - vars.public_inputs[DIGEST_SIZE + j]);
+        }
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn permutation_pairs(&self) -> Vec<PermutationPair> {
+        // left children are even nodes and right children odd ones
+        let input = COL_PERMUTATION_START + COL_INPUT_START;
+        [
+            (COL_LEFT_INDEX, input, COL_EVEN_START),
+            (COL_RIGHT_INDEX, input + DIGEST_SIZE, COL_ODD_START),
+        ]
+        .into_iter()
+        .map(|(child_index, child, held)| PermutationPair {
+            column_pairs: std::iter::once((child_index, held))
+                .chain((0..DIGEST_SIZE).map(|j| (child + j, held + 1 + j)))
+                .collect(),
+        })
+        .collect()
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bytes::DIGEST_SIZE;
+    use crate::merkle::generation::generate_merkle_trace;
+    use crate::merkle::stark::Poseidon2MerkleStark;
+    use crate::merkle::MerkleTree;
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2MerkleStark<F, D>;
+
+    fn prove_tree(num_leaves: usize) -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let leaves: Vec<[F; 4]> = (0..num_leaves).map(|_| F::rand_array()).collect();
+        let tree = MerkleTree::new(&leaves);
+        let stark = S::default();
+        let (trace, public_inputs) = generate_merkle_trace(&tree);
+        assert_eq!(public_inputs[..DIGEST_SIZE], tree.root());
+        assert_eq!(public_inputs[DIGEST_SIZE..], tree.leaves_commitment());
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        // the same root with the commitment of other leaves is rejected
+        let mut other_leaves = leaves;
+        other_leaves[0][0] += F::ONE;
+        let other_commitment = MerkleTree::new(&other_leaves).leaves_commitment();
+        let mut forged = proof;
+        forged.public_inputs[DIGEST_SIZE..].copy_from_slice(&other_commitment);
+        assert!(verify_stark_proof(stark, forged, &config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn poseidon2_merkle_constraints() -> Result<()> {
+        prove_tree(16)
+    }
+
+    #[test]
+    fn poseidon2_merkle_padded_leaves() -> Result<()> {
+        prove_tree(11)
+    }
+
+    #[test]
+    fn poseidon2_merkle_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::NUM_COLS;
+use crate::mmr::MAX_HEIGHT;
+
+/// The permutation of the row, laid out as in `crate::columns`: it merges a
+/// peak with the carry, bags a peak or compresses the size with the bagged
+/// peaks
+pub(crate) const COL_PERMUTATION_START: usize = 0;
+
+/// The peak slots before the row, as in [`MerkleMountainRange::slots`]
+///
+/// [`MerkleMountainRange::slots`]: crate::mmr::MerkleMountainRange::slots
+pub(crate) const COL_SLOTS_START: usize = COL_PERMUTATION_START + NUM_COLS; // 101
+
+/// The starting point of the slot of `height`
+#[must_use]
+pub(crate) const fn col_slot(height: usize) -> usize {
+    COL_SLOTS_START + height * DIGEST_SIZE
+}
+
+/// The number of leaves before the row
+pub(crate) const COL_SIZE: usize = col_slot(MAX_HEIGHT); // 229
+
+/// The little-endian bits of the number of leaves
+pub(crate) const COL_SIZE_BITS_START: usize = COL_SIZE + 1; // 230
+
+/// The height processed by the row, one-hot
+pub(crate) const COL_HEIGHT_START: usize = COL_SIZE_BITS_START + MAX_HEIGHT; // 262
+
+/// The size bit of that height: 1 if there is a peak
+pub(crate) const COL_HAS_PEAK: usize = COL_HEIGHT_START + MAX_HEIGHT; // 294
+
+/// The slot of that height
+pub(crate) const COL_PEAK_START: usize = COL_HAS_PEAK + 1; // 295
+
+/// The node being appended, or the peaks bagged so far
+pub(crate) const COL_CARRY_START: usize = COL_PEAK_START + DIGEST_SIZE; // 299
+
+/// 1 once a peak was bagged
+pub(crate) const COL_BAG_STARTED: usize = COL_CARRY_START + DIGEST_SIZE; // 303
+
+/// The phase of the row, one-hot: an append step, padding, a bagging step
+/// or the root, in this order
+pub(crate) const COL_IS_APPEND: usize = COL_BAG_STARTED + 1; // 304
+pub(crate) const COL_IS_PADDING: usize = COL_IS_APPEND + 1; // 305
+pub(crate) const COL_IS_BAG: usize = COL_IS_PADDING + 1; // 306
+pub(crate) const COL_IS_ROOT: usize = COL_IS_BAG + 1; // 307
+
+/// The total number of columns
+pub(crate) const MMR_NUM_COLS: usize = COL_IS_ROOT + 1; // 308
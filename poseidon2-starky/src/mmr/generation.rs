@@ -0,0 +1,195 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::STATE_SIZE;
+use crate::generation::poseidon2_air;
+use crate::mmr::columns::{
+    col_slot, COL_BAG_STARTED, COL_CARRY_START, COL_HAS_PEAK, COL_HEIGHT_START, COL_IS_APPEND,
+    COL_PEAK_START, COL_PERMUTATION_START, COL_SIZE, COL_SIZE_BITS_START, MMR_NUM_COLS,
+};
+use crate::mmr::{MerkleMountainRange, MAX_HEIGHT, PEAKS_PUBLIC_INPUTS};
+use crate::smt::{compress, Digest};
+use permutation_air::generation::fill_permutation_row;
+use plonky2::hash::hash_types::RichField;
+
+/// The public inputs: the peaks before and after the appends, then the root
+/// after them
+pub const MMR_PUBLIC_INPUTS: usize = 2 * PEAKS_PUBLIC_INPUTS + DIGEST_SIZE;
+
+/// The phases of the rows, in the order of the phase columns
+#[derive(Clone, Copy)]
+enum Phase {
+    Append = 0,
+    Padding = 1,
+    Bag = 2,
+    Root = 3,
+}
+
+/// A row of the trace without its permutation columns, and the input of its
+/// permutation, which compresses `left` with the carry
+fn row<F: RichField>(
+    mmr: &MerkleMountainRange<F>,
+    height: Option<usize>,
+    carry: &Digest<F>,
+    bag_started: bool,
+    phase: Phase,
+    left: &Digest<F>,
+) -> (Vec<F>, [F; STATE_SIZE]) {
+    let mut row = vec![F::ZERO; MMR_NUM_COLS];
+
+    let mut input = [F::ZERO; STATE_SIZE];
+    input[..DIGEST_SIZE].copy_from_slice(left);
+    input[DIGEST_SIZE..].copy_from_slice(carry);
+
+    for (height, slot) in mmr.slots.iter().enumerate() {
+        row[col_slot(height)..col_slot(height) + DIGEST_SIZE].copy_from_slice(slot);
+        row[COL_SIZE_BITS_START + height] = F::from_bool(mmr.size >> height & 1 == 1);
+    }
+    row[COL_SIZE] = F::from_canonical_u32(mmr.size);
+    if let Some(height) = height {
+        row[COL_HEIGHT_START + height] = F::ONE;
+        row[COL_HAS_PEAK] = F::from_bool(mmr.size >> height & 1 == 1);
+        row[COL_PEAK_START..COL_PEAK_START + DIGEST_SIZE].copy_from_slice(&mmr.slots[height]);
+    }
+    row[COL_CARRY_START..COL_CARRY_START + DIGEST_SIZE].copy_from_slice(carry);
+    row[COL_BAG_STARTED] = F::from_bool(bag_started);
+    row[COL_IS_APPEND + phase as usize] = F::ONE;
+    (row, input)
+}
+
+/// Function to generate the trace appending `leaves` to `mmr`, and the public
+/// inputs.
+///
+/// Every append takes one row per peak it merges and one row filling the
+/// empty slot. Padding rows follow, then one bagging row per height and the
+/// root row, so the trace has at least 64 rows.
+#[must_use]
+pub fn generate_mmr_trace<F: RichField>(
+    mmr: &MerkleMountainRange<F>,
+    leaves: &[Digest<F>],
+) -> ([Vec<F>; MMR_NUM_COLS], [F; MMR_PUBLIC_INPUTS]) {
+    let mut rows = Vec::new();
+    let mut new = mmr.clone();
+
+    for leaf in leaves {
+        // the slots before each step, with the merged peaks removed
+        let mut step = new.clone();
+        let mut carry = *leaf;
+        for height in 0..MAX_HEIGHT {
+            let peak = step.slots[height];
+            rows.push(row(
+                &step,
+                Some(height),
+                &carry,
+                false,
+                Phase::Append,
+                &peak,
+            ));
+            if step.size >> height & 1 == 0 {
+                break;
+            }
+            carry = compress(&peak, &carry);
+            step.slots[height] = [F::ZERO; DIGEST_SIZE];
+        }
+        new.append(*leaf);
+    }
+
+    let trace_len = (rows.len() + MAX_HEIGHT + 1).next_power_of_two();
+    let zero = [F::ZERO; DIGEST_SIZE];
+    let padding = row(&new, Some(0), &zero, false, Phase::Padding, &new.slots[0]);
+    rows.resize(trace_len - MAX_HEIGHT - 1, padding);
+
+    let mut bagged = zero;
+    let mut bag_started = false;
+    for height in 0..MAX_HEIGHT {
+        let peak = new.slots[height];
+        rows.push(row(
+            &new,
+            Some(height),
+            &bagged,
+            bag_started,
+            Phase::Bag,
+            &peak,
+        ));
+        if new.size >> height & 1 == 1 {
+            bagged = if bag_started {
+                compress(&peak, &bagged)
+            } else {
+                peak
+            };
+            bag_started = true;
+        }
+    }
+    let mut size = zero;
+    size[0] = F::from_canonical_u32(new.size);
+    rows.push(row(&new, None, &bagged, bag_started, Phase::Root, &size));
+    debug_assert_eq!(bagged, new.bag_peaks());
+
+    let mut public_inputs = [F::ZERO; MMR_PUBLIC_INPUTS];
+    public_inputs[..PEAKS_PUBLIC_INPUTS].copy_from_slice(&mmr.public_inputs());
+    public_inputs[PEAKS_PUBLIC_INPUTS..2 * PEAKS_PUBLIC_INPUTS]
+        .copy_from_slice(&new.public_inputs());
+    public_inputs[2 * PEAKS_PUBLIC_INPUTS..].copy_from_slice(&new.root());
+
+    let mut trace: Vec<Vec<F>> = (0..MMR_NUM_COLS)
+        .map(|col| rows.iter().map(|(row, _)| row[col]).collect())
+        .collect();
+    let air = poseidon2_air();
+    for (i, (_, input)) in rows.iter().enumerate() {
+        fill_permutation_row(&air, &mut trace[COL_PERMUTATION_START..], i, input);
+    }
+    let trace = trace.try_into().unwrap_or_else(|v: Vec<Vec<F>>| {
+        panic!(
+            "Expected a Vec of length {} but it was {}",
+            MMR_NUM_COLS,
+            v.len()
+        )
+    });
+    (trace, public_inputs)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::columns::COL_OUTPUT_START;
+    use crate::mmr::columns::{COL_IS_APPEND, COL_PERMUTATION_START};
+    use crate::mmr::generation::{generate_mmr_trace, MMR_PUBLIC_INPUTS};
+    use crate::mmr::{MerkleMountainRange, PEAKS_PUBLIC_INPUTS};
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn generate_mmr_trace_rows() {
+        let mut mmr = MerkleMountainRange::new();
+        for _ in 0..7 {
+            mmr.append(F::rand_array());
+        }
+        let leaves: Vec<[F; 4]> = (0..2).map(|_| F::rand_array()).collect();
+        let (trace, public_inputs) = generate_mmr_trace(&mmr, &leaves);
+
+        // appending to 7 leaves merges 3 peaks, then 8 has no peak of height 0
+        let append_rows = trace[COL_IS_APPEND]
+            .iter()
+            .filter(|&&flag| flag == F::ONE)
+            .count();
+        assert_eq!(append_rows, 4 + 1);
+        assert_eq!(trace[0].len(), 64);
+
+        let mut new = mmr.clone();
+        for leaf in &leaves {
+            new.append(*leaf);
+        }
+        assert_eq!(public_inputs[..PEAKS_PUBLIC_INPUTS], mmr.public_inputs());
+        assert_eq!(
+            public_inputs[PEAKS_PUBLIC_INPUTS..MMR_PUBLIC_INPUTS - 4],
+            new.public_inputs()
+        );
+        for j in 0..4 {
+            assert_eq!(
+                trace[COL_PERMUTATION_START + COL_OUTPUT_START + j][63],
+                new.root()[j]
+            );
+        }
+    }
+}
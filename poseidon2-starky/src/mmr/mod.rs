@@ -0,0 +1,164 @@
+//! A Merkle Mountain Range over the Poseidon2 permutation, with a STARK
+//! proving that appending leaves moves the peaks from one set to another.
+//!
+//! The MMR keeps one perfect tree, a *peak*, for every set bit of the number
+//! of leaves, so the peaks are kept in [`MAX_HEIGHT`] slots by height, with
+//! zero in the empty slots. Appending a leaf works like incrementing a binary
+//! counter: the leaf is merged with the peaks of heights 0, 1, ... as long as
+//! they exist, and the result fills the first empty slot. The root bags the
+//! peaks from the lowest to the highest, `compress(higher, bagged)`, and
+//! compresses the number of leaves with the result.
+#![allow(clippy::module_name_repetitions)]
+
+pub mod columns;
+pub mod generation;
+pub mod stark;
+
+use crate::bytes::DIGEST_SIZE;
+use crate::smt::{compress, Digest};
+use plonky2::hash::hash_types::RichField;
+
+/// The number of peak slots; an MMR holds fewer than `2^MAX_HEIGHT` leaves
+pub const MAX_HEIGHT: usize = 32;
+
+/// The number of public inputs of a peak set: the number of leaves followed
+/// by the peak slots
+pub const PEAKS_PUBLIC_INPUTS: usize = 1 + MAX_HEIGHT * DIGEST_SIZE;
+
+/// A Merkle Mountain Range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleMountainRange<F> {
+    size: u32,
+    /// The peak of every height, zero if there is none
+    slots: [Digest<F>; MAX_HEIGHT],
+}
+
+impl<F: RichField> Default for MerkleMountainRange<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: RichField> MerkleMountainRange<F> {
+    /// The empty MMR
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            size: 0,
+            slots: [[F::ZERO; DIGEST_SIZE]; MAX_HEIGHT],
+        }
+    }
+
+    /// The number of leaves
+    #[must_use]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The peak slots by height
+    #[must_use]
+    pub fn slots(&self) -> &[Digest<F>; MAX_HEIGHT] {
+        &self.slots
+    }
+
+    /// The peaks as `(height, peak)`, from the highest to the lowest
+    #[must_use]
+    pub fn peaks(&self) -> Vec<(usize, Digest<F>)> {
+        (0..MAX_HEIGHT)
+            .rev()
+            .filter(|&height| self.size >> height & 1 == 1)
+            .map(|height| (height, self.slots[height]))
+            .collect()
+    }
+
+    pub fn append(&mut self, leaf: Digest<F>) {
+        let mut carry = leaf;
+        let mut height = 0;
+        while self.size >> height & 1 == 1 {
+            carry = compress(&self.slots[height], &carry);
+            self.slots[height] = [F::ZERO; DIGEST_SIZE];
+            height += 1;
+        }
+        self.slots[height] = carry;
+        self.size = self.size.checked_add(1).expect("the MMR is full");
+    }
+
+    /// The peaks bagged from the lowest to the highest, zero without peaks
+    #[must_use]
+    pub fn bag_peaks(&self) -> Digest<F> {
+        self.peaks()
+            .into_iter()
+            .rev()
+            .map(|(_, peak)| peak)
+            .reduce(|bagged, peak| compress(&peak, &bagged))
+            .unwrap_or([F::ZERO; DIGEST_SIZE])
+    }
+
+    #[must_use]
+    pub fn root(&self) -> Digest<F> {
+        let mut size = [F::ZERO; DIGEST_SIZE];
+        size[0] = F::from_canonical_u32(self.size);
+        compress(&size, &self.bag_peaks())
+    }
+
+    /// The public inputs describing the peaks: the number of leaves followed
+    /// by the peak slots
+    #[must_use]
+    pub fn public_inputs(&self) -> [F; PEAKS_PUBLIC_INPUTS] {
+        let mut public_inputs = [F::ZERO; PEAKS_PUBLIC_INPUTS];
+        public_inputs[0] = F::from_canonical_u32(self.size);
+        for (chunk, slot) in public_inputs[1..]
+            .chunks_exact_mut(DIGEST_SIZE)
+            .zip(&self.slots)
+        {
+            chunk.copy_from_slice(slot);
+        }
+        public_inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::merkle::MerkleTree;
+    use crate::mmr::MerkleMountainRange;
+    use crate::smt::compress;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn peaks_are_merkle_roots() {
+        let leaves: Vec<[F; 4]> = (0..13).map(|_| F::rand_array()).collect();
+        let mut mmr = MerkleMountainRange::new();
+        for leaf in &leaves {
+            mmr.append(*leaf);
+        }
+
+        // 13 = 8 + 4 + 1
+        let peaks = mmr.peaks();
+        assert_eq!(
+            peaks.iter().map(|&(height, _)| height).collect::<Vec<_>>(),
+            [3, 2, 0]
+        );
+        assert_eq!(peaks[0].1, MerkleTree::new(&leaves[..8]).root());
+        assert_eq!(peaks[1].1, MerkleTree::new(&leaves[8..12]).root());
+        assert_eq!(peaks[2].1, leaves[12]);
+        assert_eq!(
+            mmr.bag_peaks(),
+            compress(&peaks[0].1, &compress(&peaks[1].1, &peaks[2].1))
+        );
+    }
+
+    #[test]
+    fn root_commits_to_the_size() {
+        let empty = MerkleMountainRange::<F>::new();
+        assert_eq!(empty.root(), compress(&[F::ZERO; 4], &[F::ZERO; 4]));
+
+        // a single zero leaf bags to zero, like the empty MMR
+        let mut one = MerkleMountainRange::new();
+        one.append([F::ZERO; 4]);
+        assert_eq!(one.bag_peaks(), empty.bag_peaks());
+        assert_ne!(one.root(), empty.root());
+    }
+}
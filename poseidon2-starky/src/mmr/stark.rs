@@ -0,0 +1,266 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::{COL_INPUT_START, COL_OUTPUT_START};
+use crate::mmr::columns::{
+    col_slot, COL_BAG_STARTED, COL_CARRY_START, COL_HAS_PEAK, COL_HEIGHT_START, COL_IS_APPEND,
+    COL_IS_BAG, COL_IS_PADDING, COL_IS_ROOT, COL_PEAK_START, COL_PERMUTATION_START, COL_SIZE,
+    COL_SIZE_BITS_START, MMR_NUM_COLS,
+};
+use crate::mmr::generation::MMR_PUBLIC_INPUTS;
+use crate::mmr::{MAX_HEIGHT, PEAKS_PUBLIC_INPUTS};
+use crate::params::POSEIDON2_PARAMS_128;
+use crate::stark::eval_permutation;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+/// The public inputs: the peaks before the appends, the peaks after them and
+/// the root after them
+pub(crate) const PI_OLD_PEAKS_START: usize = 0;
+pub(crate) const PI_NEW_PEAKS_START: usize = PI_OLD_PEAKS_START + PEAKS_PUBLIC_INPUTS;
+pub(crate) const PI_ROOT_START: usize = PI_NEW_PEAKS_START + PEAKS_PUBLIC_INPUTS;
+
+/// Appends to a Merkle Mountain Range. Each append step merges the peak of
+/// its height into the carry, or fills the empty slot of its height with the
+/// carry and counts the leaf. After padding, one bagging step per height
+/// folds the peaks into the carry, and the last row compresses the number of
+/// leaves with it into the root.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Poseidon2MmrStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Poseidon2MmrStark<F, D> {
+    const COLUMNS: usize = MMR_NUM_COLS;
+    const PUBLIC_INPUTS: usize = MMR_PUBLIC_INPUTS;
+
+    #[allow(clippy::too_many_lines)]
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let lv = vars.local_values;
+        let nv = vars.next_values;
+        eval_permutation(
+            &POSEIDON2_PARAMS_128,
+            &lv[COL_PERMUTATION_START..],
+            yield_constr,
+        );
+        let input = &lv[COL_PERMUTATION_START + COL_INPUT_START..];
+        let output = &lv[COL_PERMUTATION_START + COL_OUTPUT_START..];
+        let carry = &lv[COL_CARRY_START..COL_CARRY_START + DIGEST_SIZE];
+        let next_carry = &nv[COL_CARRY_START..COL_CARRY_START + DIGEST_SIZE];
+        let peak = &lv[COL_PEAK_START..COL_PEAK_START + DIGEST_SIZE];
+
+        // the phases are one-hot and come in order: appends, padding,
+        // bagging and the root in the last row
+        let [is_append, is_padding, is_bag, is_root] =
+            [COL_IS_APPEND, COL_IS_PADDING, COL_IS_BAG, COL_IS_ROOT].map(|col| lv[col]);
+        let [next_is_append, next_is_padding, next_is_bag, next_is_root] =
+            [COL_IS_APPEND, COL_IS_PADDING, COL_IS_BAG, COL_IS_ROOT].map(|col| nv[col]);
+        for flag in [is_append, is_padding, is_bag, is_root] {
+            yield_constr.constraint(flag * (flag - P::ONES));
+        }
+        yield_constr.constraint(is_append + is_padding + is_bag + is_root - P::ONES);
+        yield_constr.constraint_transition(is_padding * next_is_append);
+        yield_constr.constraint_transition(is_bag * (next_is_append + next_is_padding));
+        yield_constr.constraint_transition(next_is_root * (P::ONES - is_bag));
+        yield_constr.constraint_transition(is_root);
+        yield_constr.constraint_last_row(is_root - P::ONES);
+
+        // the size and its bits, and the empty slots are zero
+        let mut size = P::ZEROS;
+        for height in 0..MAX_HEIGHT {
+            let bit = lv[COL_SIZE_BITS_START + height];
+            yield_constr.constraint(bit * (bit - P::ONES));
+            size += bit * FE::from_canonical_u64(1 << height);
+            for j in 0..DIGEST_SIZE {
+                yield_constr.constraint((P::ONES - bit) * lv[col_slot(height) + j]);
+            }
+        }
+        yield_constr.constraint(size - lv[COL_SIZE]);
+
+        // the height of append and bagging steps is one-hot, and selects the
+        // size bit and the slot of that height
+        let is_step = is_append + is_bag;
+        let mut num_heights = P::ZEROS;
+        let mut has_peak = P::ZEROS;
+        let mut selected = [P::ZEROS; DIGEST_SIZE];
+        for height in 0..MAX_HEIGHT {
+            let flag = lv[COL_HEIGHT_START + height];
+            yield_constr.constraint(flag * (flag - P::ONES));
+            num_heights += flag;
+            has_peak += flag * lv[COL_SIZE_BITS_START + height];
+            for (j, s) in selected.iter_mut().enumerate() {
+                *s += flag * lv[col_slot(height) + j];
+            }
+        }
+        yield_constr.constraint(is_step * (num_heights - P::ONES));
+        yield_constr.constraint_first_row(is_bag);
+        yield_constr.constraint_first_row(is_step * (lv[COL_HEIGHT_START] - P::ONES));
+        yield_constr.constraint(has_peak - lv[COL_HAS_PEAK]);
+        for j in 0..DIGEST_SIZE {
+            yield_constr.constraint(selected[j] - peak[j]);
+        }
+        let has_peak = lv[COL_HAS_PEAK];
+
+        // steps compress the peak with the carry, and the root row the size
+        // with the bagged peaks
+        for j in 0..DIGEST_SIZE {
+            yield_constr.constraint(input[DIGEST_SIZE + j] - carry[j]);
+            yield_constr.constraint(is_step * (input[j] - peak[j]));
+            let size = if j == 0 { lv[COL_SIZE] } else { P::ZEROS };
+            yield_constr.constraint(is_root * (input[j] - size));
+        }
+
+        // a merge moves to the next height; a new append or bagging starts
+        // at height 0
+        let is_merge = is_append * has_peak;
+        yield_constr.constraint_transition(is_merge * (next_is_append - P::ONES));
+        let shifts = is_merge + is_bag * next_is_bag;
+        let starts = next_is_append - is_merge + next_is_bag - is_bag * next_is_bag;
+        yield_constr.constraint_transition(shifts * nv[COL_HEIGHT_START]);
+        yield_constr.constraint_transition(starts * (nv[COL_HEIGHT_START] - P::ONES));
+        for height in 1..MAX_HEIGHT {
+            let next_flag = nv[COL_HEIGHT_START + height];
+            yield_constr
+                .constraint_transition(shifts * (next_flag - lv[COL_HEIGHT_START + height - 1]));
+            yield_constr.constraint_transition(starts * next_flag);
+        }
+        // the root follows the bagging step of the last height
+        let is_last_height = lv[COL_HEIGHT_START + MAX_HEIGHT - 1];
+        yield_constr.constraint_transition(is_bag * (is_last_height - next_is_root));
+
+        // a merge empties the slot and carries the merged node, the last step
+        // of an append fills the slot and counts the leaf, and everything
+        // else keeps the peaks
+        for height in 0..MAX_HEIGHT {
+            let flag = is_append * lv[COL_HEIGHT_START + height];
+            for j in 0..DIGEST_SIZE {
+                let slot = lv[col_slot(height) + j];
+                yield_constr.constraint_transition(
+                    nv[col_slot(height) + j]
+                        - slot
+                        - flag * ((P::ONES - has_peak) * carry[j] - slot),
+                );
+            }
+        }
+        yield_constr
+            .constraint_transition(nv[COL_SIZE] - lv[COL_SIZE] - is_append * (P::ONES - has_peak));
+        for j in 0..DIGEST_SIZE {
+            yield_constr.constraint_transition(is_merge * (next_carry[j] - output[j]));
+        }
+
+        // bagging starts from zero; each peak is bagged into the carry, the
+        // first one as it is
+        let enters_bag = next_is_bag - is_bag * next_is_bag;
+        let bag_started = lv[COL_BAG_STARTED];
+        for j in 0..DIGEST_SIZE {
+            yield_constr.constraint_transition(enters_bag * next_carry[j]);
+            let bagged = bag_started * output[j] + (P::ONES - bag_started) * peak[j];
+            let next = has_peak * bagged + (P::ONES - has_peak) * carry[j];
+            yield_constr.constraint_transition(is_bag * (next_carry[j] - next));
+        }
+        yield_constr.constraint_transition(enters_bag * nv[COL_BAG_STARTED]);
+        yield_constr.constraint_transition(
+            is_bag * (nv[COL_BAG_STARTED] - bag_started - has_peak + bag_started * has_peak),
+        );
+
+        // the peaks before and after match the public inputs, and so does
+        // the root
+        let old_peaks = &vars.public_inputs[PI_OLD_PEAKS_START..];
+        let new_peaks = &vars.public_inputs[PI_NEW_PEAKS_START..];
+        yield_constr.constraint_first_row(lv[COL_SIZE] - old_peaks[0]);
+        yield_constr.constraint_last_row(lv[COL_SIZE] - new_peaks[0]);
+        for i in 0..MAX_HEIGHT * DIGEST_SIZE {
+            yield_constr.constraint_first_row(lv[col_slot(0) + i] - old_peaks[1 + i]);
+            yield_constr.constraint_last_row(lv[col_slot(0) + i] - new_peaks[1 + i]);
+        }
+        for (&root, &public_input) in output.iter().zip(&vars.public_inputs[PI_ROOT_START..]) {
+            yield_constr.constraint_last_row(root - public_input);
+        }
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mmr::generation::generate_mmr_trace;
+    use crate::mmr::stark::Poseidon2MmrStark;
+    use crate::mmr::MerkleMountainRange;
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2MmrStark<F, D>;
+
+    fn prove_appends(initial_size: usize, num_leaves: usize) -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let mut mmr = MerkleMountainRange::new();
+        for _ in 0..initial_size {
+            mmr.append(F::rand_array());
+        }
+        let leaves: Vec<[F; 4]> = (0..num_leaves).map(|_| F::rand_array()).collect();
+        let stark = S::default();
+        let (trace, public_inputs) = generate_mmr_trace(&mmr, &leaves);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn poseidon2_mmr_constraints() -> Result<()> {
+        prove_appends(13, 20)
+    }
+
+    #[test]
+    fn poseidon2_mmr_from_empty() -> Result<()> {
+        prove_appends(0, 3)
+    }
+
+    #[test]
+    fn poseidon2_mmr_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
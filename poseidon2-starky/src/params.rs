@@ -0,0 +1,241 @@
+use crate::columns::{ROUNDS_F, ROUNDS_P, STATE_SIZE};
+use crate::generation::scalar_to_field_vec;
+use anyhow::{ensure, Result};
+use lazy_static::lazy_static;
+use num::integer::gcd;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::{Field, Field64, PrimeField64};
+use zkhash::fields::goldilocks::FpGoldiLocks;
+use zkhash::poseidon2::poseidon2_instance_goldilocks::{MAT_DIAG8_M_1, RC8};
+
+// used in the linear layer
+pub(crate) const M4: [[usize; 4]; 4] = [
+    [5, 7, 1, 3], //
+    [4, 6, 1, 1], //
+    [1, 3, 5, 7], //
+    [1, 1, 4, 6],
+];
+
+/// Rounds of the reduced-rounds preset, meant for cryptanalysis experiments only
+pub const ROUNDS_F_REDUCED: usize = 2;
+/// Partial rounds of the reduced-rounds preset, meant for cryptanalysis
+/// experiments only
+pub const ROUNDS_P_REDUCED: usize = 8;
+
+lazy_static! {
+    /// The 128-bit preset, shared by the tables built on the default layout
+    pub(crate) static ref POSEIDON2_PARAMS_128: Poseidon2Params<ROUNDS_F, ROUNDS_P> =
+        Poseidon2Params::security_128();
+}
+
+/// Parameters of a width-8 Poseidon2 instance over Goldilocks.
+///
+/// `ROUNDS_F` is the number of full rounds in *each* of the two halves and
+/// `ROUNDS_P` the number of partial rounds. Constants are canonical `u64`s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Poseidon2Params<const ROUNDS_F: usize, const ROUNDS_P: usize> {
+    sbox_degree: usize,
+    round_constants: Vec<[u64; STATE_SIZE]>,
+    internal_diag_m_1: [u64; STATE_SIZE],
+}
+
+impl<const ROUNDS_F: usize, const ROUNDS_P: usize> Poseidon2Params<ROUNDS_F, ROUNDS_P> {
+    /// The total number of rounds
+    pub const ROUNDS: usize = 2 * ROUNDS_F + ROUNDS_P;
+
+    /// Create a parameter set, rejecting values the permutation or its AIR
+    /// cannot use.
+    ///
+    /// `round_constants` has one entry per round; partial rounds only use the
+    /// first element, so the remaining ones must be zero. `internal_diag_m_1`
+    /// is the diagonal of the internal matrix minus one, as in zkhash.
+    ///
+    /// # Errors
+    /// Returns an error if the S-box is not a permutation of the field, a
+    /// number of rounds is zero, the round constants do not match the number
+    /// of rounds, a constant is not canonical, or the internal matrix is
+    /// singular.
+    pub fn new(
+        sbox_degree: usize,
+        round_constants: Vec<[u64; STATE_SIZE]>,
+        internal_diag_m_1: [u64; STATE_SIZE],
+    ) -> Result<Self> {
+        ensure!(ROUNDS_F > 0, "at least one full round per half is required");
+        ensure!(ROUNDS_P > 0, "at least one partial round is required");
+        ensure!(
+            sbox_degree >= 3 && gcd(sbox_degree as u64, GoldilocksField::ORDER - 1) == 1,
+            "x^{sbox_degree} is not a permutation of the Goldilocks field"
+        );
+        ensure!(
+            round_constants.len() == Self::ROUNDS,
+            "expected {} rounds of constants but got {}",
+            Self::ROUNDS,
+            round_constants.len()
+        );
+        for (r, rc) in round_constants.iter().enumerate() {
+            ensure!(
+                rc.iter().all(|&c| c < GoldilocksField::ORDER),
+                "round constant of round {r} is not canonical"
+            );
+            let is_partial = (ROUNDS_F..ROUNDS_F + ROUNDS_P).contains(&r);
+            ensure!(
+                !is_partial || rc[1..].iter().all(|&c| c == 0),
+                "partial round {r} has constants beyond the first element"
+            );
+        }
+        ensure!(
+            internal_diag_m_1
+                .iter()
+                .all(|&c| c < GoldilocksField::ORDER),
+            "internal diagonal is not canonical"
+        );
+        ensure!(
+            internal_matrix_det(&internal_diag_m_1) != GoldilocksField::ZERO,
+            "internal matrix is singular"
+        );
+
+        Ok(Self {
+            sbox_degree,
+            round_constants,
+            internal_diag_m_1,
+        })
+    }
+
+    #[must_use]
+    pub fn sbox_degree(&self) -> usize {
+        self.sbox_degree
+    }
+
+    #[must_use]
+    pub fn round_constants(&self) -> &[[u64; STATE_SIZE]] {
+        &self.round_constants
+    }
+
+    #[must_use]
+    pub fn internal_diag_m_1(&self) -> &[u64; STATE_SIZE] {
+        &self.internal_diag_m_1
+    }
+}
+
+impl Poseidon2Params<ROUNDS_F, ROUNDS_P> {
+    /// The 128-bit security preset, equal to zkhash's `POSEIDON2_GOLDILOCKS_8_PARAMS`
+    #[must_use]
+    pub fn security_128() -> Self {
+        Self::new(7, rc8_to_u64(0..RC8.len()), diag8_to_u64())
+            .expect("zkhash Poseidon2 Goldilocks-8 parameters are consistent")
+    }
+}
+
+impl Default for Poseidon2Params<ROUNDS_F, ROUNDS_P> {
+    fn default() -> Self {
+        Self::security_128()
+    }
+}
+
+impl Poseidon2Params<ROUNDS_F_REDUCED, ROUNDS_P_REDUCED> {
+    /// A reduced-rounds variant reusing the first constants of each round
+    /// kind of the 128-bit preset. It offers no security.
+    #[must_use]
+    pub fn reduced() -> Self {
+        let p_end = ROUNDS_F + ROUNDS_P;
+        let mut round_constants = rc8_to_u64(0..ROUNDS_F_REDUCED);
+        round_constants.extend(rc8_to_u64(ROUNDS_F..ROUNDS_F + ROUNDS_P_REDUCED));
+        round_constants.extend(rc8_to_u64(p_end..p_end + ROUNDS_F_REDUCED));
+        Self::new(7, round_constants, diag8_to_u64())
+            .expect("reduced Poseidon2 parameters are consistent")
+    }
+}
+
+fn scalars_to_u64(scalars: &[FpGoldiLocks]) -> [u64; STATE_SIZE] {
+    scalar_to_field_vec::<GoldilocksField, _>(scalars)
+        .iter()
+        .map(PrimeField64::to_canonical_u64)
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+fn rc8_to_u64(rounds: std::ops::Range<usize>) -> Vec<[u64; STATE_SIZE]> {
+    RC8[rounds].iter().map(|rc| scalars_to_u64(rc)).collect()
+}
+
+fn diag8_to_u64() -> [u64; STATE_SIZE] {
+    scalars_to_u64(&MAT_DIAG8_M_1)
+}
+
+/// The determinant of `diag(d) + J`, where `J` is the all-ones matrix:
+/// `prod(d) + sum_i prod_{j != i}(d_j)`.
+fn internal_matrix_det(diag_m_1: &[u64; STATE_SIZE]) -> GoldilocksField {
+    let d: Vec<GoldilocksField> = diag_m_1
+        .iter()
+        .map(|&c| GoldilocksField::from_canonical_u64(c))
+        .collect();
+    let mut det = d.iter().copied().product::<GoldilocksField>();
+    for i in 0..STATE_SIZE {
+        det += d
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, &c)| c)
+            .product::<GoldilocksField>();
+    }
+    det
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::{ROUNDS_F, ROUNDS_P, STATE_SIZE};
+    use crate::params::{Poseidon2Params, ROUNDS_F_REDUCED, ROUNDS_P_REDUCED};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Field64;
+
+    type Params = Poseidon2Params<ROUNDS_F, ROUNDS_P>;
+
+    #[test]
+    fn presets_are_consistent() {
+        let params = Params::security_128();
+        assert_eq!(params.round_constants().len(), Params::ROUNDS);
+        assert_eq!(params.sbox_degree(), 7);
+
+        let reduced = Poseidon2Params::reduced();
+        assert_eq!(
+            reduced.round_constants().len(),
+            2 * ROUNDS_F_REDUCED + ROUNDS_P_REDUCED
+        );
+        assert_eq!(reduced.internal_diag_m_1(), params.internal_diag_m_1());
+    }
+
+    #[test]
+    fn rejects_inconsistent_parameters() {
+        let params = Params::security_128();
+        let rc = params.round_constants().to_vec();
+        let diag = *params.internal_diag_m_1();
+
+        // x^3 and x^5 are not permutations of Goldilocks
+        assert!(Params::new(3, rc.clone(), diag).is_err());
+        assert!(Params::new(5, rc.clone(), diag).is_err());
+        assert!(Params::new(11, rc.clone(), diag).is_ok());
+
+        // wrong number of rounds
+        assert!(Params::new(7, rc[1..].to_vec(), diag).is_err());
+        assert!(Poseidon2Params::<ROUNDS_F, 21>::new(7, rc.clone(), diag).is_err());
+
+        // the layout needs a partial round
+        let full_rc = [&rc[..ROUNDS_F], &rc[ROUNDS_F + ROUNDS_P..]].concat();
+        assert!(Poseidon2Params::<ROUNDS_F, 0>::new(7, full_rc, diag).is_err());
+
+        // partial rounds only have one constant
+        let mut bad_rc = rc.clone();
+        bad_rc[ROUNDS_F][1] = 1;
+        assert!(Params::new(7, bad_rc, diag).is_err());
+
+        // non-canonical constants
+        let mut bad_rc = rc.clone();
+        bad_rc[0][0] = GoldilocksField::ORDER;
+        assert!(Params::new(7, bad_rc, diag).is_err());
+
+        // singular internal matrix: diag(0, ..., 0) + J has rank one
+        assert!(Params::new(7, rc, [0; STATE_SIZE]).is_err());
+    }
+}
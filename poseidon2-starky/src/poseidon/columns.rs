@@ -0,0 +1,34 @@
+use plonky2::hash::poseidon::{HALF_N_FULL_ROUNDS, N_PARTIAL_ROUNDS, SPONGE_WIDTH};
+
+/// The size of the state
+pub const STATE_SIZE: usize = SPONGE_WIDTH;
+
+/// Rounds of plonky2's instance: full rounds in each half and partial rounds
+pub(crate) const ROUNDS_F: usize = HALF_N_FULL_ROUNDS;
+pub(crate) const ROUNDS_P: usize = N_PARTIAL_ROUNDS;
+
+/// The starting point of the column input
+pub(crate) const COL_INPUT_START: usize = 0;
+
+/// The starting point of the state after each 1st full round
+pub(crate) const COL_1ST_FULLROUND_STATE_START: usize = COL_INPUT_START + STATE_SIZE; // 12
+
+/// The value of state[0] after each partial round
+pub(crate) const COL_PARTIAL_ROUND_STATE_START: usize =
+    COL_1ST_FULLROUND_STATE_START + STATE_SIZE * ROUNDS_F; // 60
+
+/// The starting point of the state after the partial rounds. Its first
+/// element is the state[0] of the last partial round.
+pub(crate) const COL_PARTIAL_ROUND_END_STATE_START: usize =
+    COL_PARTIAL_ROUND_STATE_START + ROUNDS_P - 1; // 81
+
+/// The starting point of the state after each 2nd full round
+pub(crate) const COL_2ND_FULLROUND_STATE_START: usize =
+    COL_PARTIAL_ROUND_END_STATE_START + STATE_SIZE; // 93
+
+/// The starting point of the column output
+/// This is the same as the last state after the 2nd full round
+pub const COL_OUTPUT_START: usize = COL_2ND_FULLROUND_STATE_START + STATE_SIZE * (ROUNDS_F - 1); // 129
+
+/// The total number of columns
+pub(crate) const NUM_COLS: usize = COL_2ND_FULLROUND_STATE_START + STATE_SIZE * ROUNDS_F; // 141
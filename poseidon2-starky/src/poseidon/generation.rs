@@ -0,0 +1,122 @@
+use crate::poseidon::columns::{
+    COL_1ST_FULLROUND_STATE_START, COL_2ND_FULLROUND_STATE_START, COL_INPUT_START,
+    COL_PARTIAL_ROUND_END_STATE_START, COL_PARTIAL_ROUND_STATE_START, NUM_COLS, ROUNDS_F, ROUNDS_P,
+    STATE_SIZE,
+};
+use permutation_air::generation::pad_trace;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::poseidon::Poseidon;
+
+// Represent a row of the preimage
+#[derive(Debug, Clone, Default)]
+pub struct Row<Field: RichField> {
+    pub preimage: [Field; STATE_SIZE],
+}
+
+/// The states after every round of the permutation, in the order of
+/// `poseidon_naive`: full rounds, partial rounds and full rounds again
+fn generate_round_states<F: RichField>(preimage: &[F; STATE_SIZE]) -> Vec<[F; STATE_SIZE]> {
+    let mut outputs = Vec::new();
+    let mut state = *preimage;
+
+    for round_ctr in 0..2 * ROUNDS_F + ROUNDS_P {
+        <F as Poseidon>::constant_layer(&mut state, round_ctr);
+        if (ROUNDS_F..ROUNDS_F + ROUNDS_P).contains(&round_ctr) {
+            state[0] = <F as Poseidon>::sbox_monomial(state[0]);
+        } else {
+            <F as Poseidon>::sbox_layer(&mut state);
+        }
+        state = <F as Poseidon>::mds_layer(&state);
+        outputs.push(state);
+    }
+
+    outputs
+}
+
+/// Write the permutation columns of a single row into a column-major trace
+fn fill_permutation_row<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    preimage: &[F; STATE_SIZE],
+) {
+    for j in 0..STATE_SIZE {
+        trace[COL_INPUT_START + j][row] = preimage[j];
+    }
+
+    let states = generate_round_states(preimage);
+    let (first_full, rest) = states.split_at(ROUNDS_F);
+    let (partial, second_full) = rest.split_at(ROUNDS_P);
+    for j in 0..ROUNDS_F {
+        for k in 0..STATE_SIZE {
+            trace[COL_1ST_FULLROUND_STATE_START + j * STATE_SIZE + k][row] = first_full[j][k];
+            trace[COL_2ND_FULLROUND_STATE_START + j * STATE_SIZE + k][row] = second_full[j][k];
+        }
+    }
+    for j in 0..ROUNDS_P {
+        trace[COL_PARTIAL_ROUND_STATE_START + j][row] = partial[j][0];
+    }
+    for j in 0..STATE_SIZE {
+        trace[COL_PARTIAL_ROUND_END_STATE_START + j][row] = partial[ROUNDS_P - 1][j];
+    }
+}
+
+/// Function to generate the trace of plonky2's Poseidon permutation
+#[must_use]
+pub fn generate_poseidon_trace<F: RichField>(step_rows: &[Row<F>]) -> [Vec<F>; NUM_COLS] {
+    let trace_len = step_rows.len();
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; NUM_COLS];
+
+    for (i, row) in step_rows.iter().enumerate() {
+        fill_permutation_row(&mut trace, i, &row.preimage);
+    }
+
+    trace = pad_trace(trace);
+    trace.try_into().unwrap_or_else(|v: Vec<Vec<F>>| {
+        panic!("Expected a Vec of length {NUM_COLS} but it was {}", v.len())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::poseidon::columns::{COL_OUTPUT_START, STATE_SIZE};
+    use crate::poseidon::generation::{generate_poseidon_trace, generate_round_states, Row};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Field64, Sample};
+    use plonky2::hash::poseidon::Poseidon;
+    use proptest::prelude::*;
+
+    type F = GoldilocksField;
+
+    proptest! {
+        #[test]
+        fn round_states_match_plonky2(input in prop::array::uniform12(0..F::ORDER)) {
+            let preimage = input.map(F::from_canonical_u64);
+            let states = generate_round_states(&preimage);
+            prop_assert_eq!(*states.last().unwrap(), F::poseidon(preimage));
+        }
+    }
+
+    #[test]
+    fn generate_poseidon_trace_outputs() {
+        let step_rows: Vec<Row<F>> = (0..5)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let trace = generate_poseidon_trace(&step_rows);
+        for column in &trace {
+            assert_eq!(column.len(), 8);
+        }
+        for (i, step_row) in step_rows.iter().enumerate() {
+            let expected = F::poseidon(step_row.preimage);
+            for j in 0..STATE_SIZE {
+                assert_eq!(
+                    trace[COL_OUTPUT_START + j][i],
+                    expected[j],
+                    "Mismatch at row {i}, position {j}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! The original Poseidon permutation as used by plonky2.
+//!
+//! plonky2 hashes with Poseidon over 12 Goldilocks elements, with 4 + 4 full
+//! rounds and 22 partial rounds, and its Merkle caps are built with it.
+//! [`stark::PoseidonStark`] reproduces
+//! `plonky2::hash::poseidon::Poseidon::poseidon` exactly, so that a proof can
+//! attest to those hashes. The round constants and the MDS matrix are taken
+//! from plonky2 itself.
+#![allow(clippy::module_name_repetitions)]
+
+pub mod columns;
+pub mod generation;
+pub mod stark;
@@ -0,0 +1,209 @@
+use crate::poseidon::columns::{
+    COL_1ST_FULLROUND_STATE_START, COL_2ND_FULLROUND_STATE_START,
+    COL_PARTIAL_ROUND_END_STATE_START, COL_PARTIAL_ROUND_STATE_START, NUM_COLS, ROUNDS_F, ROUNDS_P,
+    STATE_SIZE,
+};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::poseidon::{Poseidon, ALL_ROUND_CONSTANTS};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+/// The S-box degree of plonky2's Poseidon
+const SBOX_DEGREE: usize = 7;
+
+// linear layer: the circulant matrix plus the diagonal of `Poseidon`
+// (degree = 1)
+fn mds_constraints<F: RichField + Extendable<D>, const D: usize, FE, P, const D2: usize>(
+    state: &[P; STATE_SIZE],
+) -> [P; STATE_SIZE]
+where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let mut out = [P::ZEROS; STATE_SIZE];
+
+    for (r, out) in out.iter_mut().enumerate() {
+        for (i, &c) in <F as Poseidon>::MDS_MATRIX_CIRC.iter().enumerate() {
+            *out += state[(i + r) % STATE_SIZE] * FE::from_canonical_u64(c);
+        }
+        *out += state[r] * FE::from_canonical_u64(<F as Poseidon>::MDS_MATRIX_DIAG[r]);
+    }
+
+    out
+}
+
+// degree: 1
+fn add_rc_constraints<F: RichField + Extendable<D>, const D: usize, FE, P, const D2: usize>(
+    state: &[P; STATE_SIZE],
+    round_ctr: usize,
+) -> [P; STATE_SIZE]
+where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let mut out = [P::ZEROS; STATE_SIZE];
+
+    for i in 0..STATE_SIZE {
+        out[i] = state[i] + FE::from_canonical_u64(ALL_ROUND_CONSTANTS[i + STATE_SIZE * round_ctr]);
+    }
+
+    out
+}
+
+// degree: 7
+fn sbox_p_constraints<P: PackedField>(state: &P) -> P {
+    let mut out = P::ONES;
+
+    for _ in 0..SBOX_DEGREE {
+        out = out.mul(*state);
+    }
+
+    out
+}
+
+/// Constraints of a single Poseidon permutation laid out as in
+/// `poseidon/columns.rs`. After every stored round the state is replaced by
+/// its columns, which keeps the degree at 7.
+fn eval_permutation<F: RichField + Extendable<D>, const D: usize, FE, P, const D2: usize>(
+    lv: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let mut state: [P; STATE_SIZE] = lv[0..STATE_SIZE].try_into().unwrap();
+
+    // first full rounds
+    for r in 0..ROUNDS_F {
+        state = add_rc_constraints(&state, r);
+        state = state.map(|s| sbox_p_constraints(&s));
+        state = mds_constraints(&state);
+        for i in 0..STATE_SIZE {
+            yield_constr
+                .constraint(state[i] - lv[COL_1ST_FULLROUND_STATE_START + r * STATE_SIZE + i]);
+            state[i] = lv[COL_1ST_FULLROUND_STATE_START + r * STATE_SIZE + i];
+        }
+    }
+
+    // partial rounds: the constants are added to the whole state but only
+    // state[0] goes through the S-box
+    for i in 0..ROUNDS_P {
+        let r = ROUNDS_F + i;
+        state = add_rc_constraints(&state, r);
+        state[0] = sbox_p_constraints(&state[0]);
+        state = mds_constraints(&state);
+        yield_constr.constraint(state[0] - lv[COL_PARTIAL_ROUND_STATE_START + i]);
+        state[0] = lv[COL_PARTIAL_ROUND_STATE_START + i];
+    }
+
+    // the state before last full rounds
+    for i in 0..STATE_SIZE {
+        yield_constr.constraint(state[i] - lv[COL_PARTIAL_ROUND_END_STATE_START + i]);
+        state[i] = lv[COL_PARTIAL_ROUND_END_STATE_START + i];
+    }
+
+    // last full rounds
+    for i in 0..ROUNDS_F {
+        let r = ROUNDS_F + ROUNDS_P + i;
+        state = add_rc_constraints(&state, r);
+        state = state.map(|s| sbox_p_constraints(&s));
+        state = mds_constraints(&state);
+        for j in 0..STATE_SIZE {
+            yield_constr
+                .constraint(state[j] - lv[COL_2ND_FULLROUND_STATE_START + i * STATE_SIZE + j]);
+            state[j] = lv[COL_2ND_FULLROUND_STATE_START + i * STATE_SIZE + j];
+        }
+    }
+}
+
+/// The table of plonky2's Poseidon permutation, one row per permutation.
+#[derive(Copy, Clone, Default)]
+pub struct PoseidonStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for PoseidonStark<F, D> {
+    const COLUMNS: usize = NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_permutation(vars.local_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        SBOX_DEGREE
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::poseidon::generation::{generate_poseidon_trace, Row};
+    use crate::poseidon::stark::PoseidonStark;
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = PoseidonStark<F, D>;
+
+    #[test]
+    fn poseidon_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let step_rows: Vec<Row<F>> = (0..12)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let stark = S::default();
+        let trace = generate_poseidon_trace(&step_rows);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn poseidon_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
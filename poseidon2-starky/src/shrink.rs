@@ -0,0 +1,267 @@
+//! Shrinking a starky proof, such as a [`Poseidon2Stark`] proof, into a
+//! small plonky2 proof.
+//!
+//! The first layer verifies the starky proof in a plonky2 circuit, whose size
+//! grows with the trace length. Every further layer verifies the proof of the
+//! layer below, until the degree of the circuit stops changing: the final
+//! circuit is a plonky2 recursive verifier of a fixed degree, for example
+//! `2^12` with the standard recursion config. The public inputs of every
+//! layer are the [`statement`] of the starky proof, so the final proof has
+//! the same public inputs and a verification cost that does not depend on
+//! the trace length. The circuits only depend on public parameters, so a
+//! verifier pins the final one with [`final_verifier_data`].
+//!
+//! [`Poseidon2Stark`]: crate::stark::Poseidon2Stark
+
+use crate::aggregation::{
+    leaf_circuit, node_circuit, statement, AggregationConfig, PublicInputsMode,
+};
+use anyhow::{anyhow, ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData, VerifierCircuitData};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+use plonky2::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use starky::config::StarkConfig;
+use starky::proof::{StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget};
+use starky::recursive_verifier::set_stark_proof_with_pis_target;
+use starky::stark::Stark;
+use starky::verifier::verify_stark_proof;
+
+/// The layers of a shrink pipeline
+#[derive(Debug, Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ShrinkConfig {
+    /// The config of every plonky2 circuit of the pipeline
+    pub circuit_config: CircuitConfig,
+    /// The maximum number of layers on top of the one verifying the starky
+    /// proof. [`shrink`] fails if the degree has not reached its fixed point
+    /// by then.
+    pub max_layers: usize,
+}
+
+impl Default for ShrinkConfig {
+    fn default() -> Self {
+        Self {
+            circuit_config: CircuitConfig::standard_recursion_config(),
+            max_layers: 4,
+        }
+    }
+}
+
+/// The last layer of a shrink pipeline
+pub struct ShrunkProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    pub proof: ProofWithPublicInputs<F, C, D>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ShrunkProof<F, C, D>
+{
+    /// Verify the proof against `verifier_data`, as returned by
+    /// [`final_verifier_data`], and check that its public inputs are
+    /// `statement`
+    ///
+    /// # Errors
+    /// Returns an error if the proof does not verify or proves another
+    /// statement.
+    pub fn verify(
+        &self,
+        statement: &[F],
+        verifier_data: &VerifierCircuitData<F, C, D>,
+    ) -> Result<()> {
+        ensure!(
+            self.proof.public_inputs == statement,
+            "public inputs do not match the statement"
+        );
+        verifier_data.verify(self.proof.clone())
+    }
+}
+
+/// The circuits of a shrink pipeline with their proof targets
+struct Circuits<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    /// The circuit verifying the starky proof, exposing its statement
+    leaf: CircuitData<F, C, D>,
+    leaf_target: StarkProofWithPublicInputsTarget<D>,
+    /// The circuits verifying the proof of the layer below
+    layers: Vec<(CircuitData<F, C, D>, ProofWithPublicInputsTarget<D>)>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> Circuits<F, C, D> {
+    fn last(&self) -> &CircuitData<F, C, D> {
+        self.layers.last().map_or(&self.leaf, |(data, _)| data)
+    }
+}
+
+/// The circuits of the shrink pipeline of proofs of `stark` with
+/// `degree_bits`: the one verifying the starky proof, then the layers on top
+/// of it up to the fixed point of the degree
+fn circuits<F, C, S, const D: usize>(
+    stark: &S,
+    stark_config: &StarkConfig,
+    degree_bits: usize,
+    config: &ShrinkConfig,
+) -> Result<Circuits<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Clone,
+    C::Hasher: AlgebraicHasher<F>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+{
+    // a single leaf exposing the statement of the proof, then nodes with a
+    // single child
+    let leaf_config = AggregationConfig {
+        circuit_config: config.circuit_config.clone(),
+        leaf_batch: 1,
+        arity: 2,
+        public_inputs: PublicInputsMode::Concatenate,
+    };
+    let layer_config = AggregationConfig {
+        arity: 1,
+        ..leaf_config.clone()
+    };
+    let (leaf, mut leaf_targets) =
+        leaf_circuit::<F, C, S, D>(stark, stark_config, degree_bits, &leaf_config);
+
+    let mut verifier_data = leaf.verifier_data();
+    let mut layers = Vec::new();
+    for layer in 0..=config.max_layers {
+        let (data, mut targets) = node_circuit(&verifier_data, &layer_config);
+        if layer > 0 && data.common.degree_bits() == verifier_data.common.degree_bits() {
+            return Ok(Circuits {
+                leaf,
+                leaf_target: leaf_targets.remove(0),
+                layers,
+            });
+        }
+        if layer == config.max_layers {
+            break;
+        }
+        verifier_data = data.verifier_data();
+        layers.push((data, targets.remove(0)));
+    }
+    Err(anyhow!(
+        "the degree did not reach a fixed point within {} layers",
+        config.max_layers
+    ))
+}
+
+/// The data to verify the final proof of the shrink pipeline of proofs of
+/// `stark` with `degree_bits`, derived without the proof
+///
+/// # Errors
+/// Returns an error if the degree does not reach its fixed point within
+/// `config.max_layers` layers.
+pub fn final_verifier_data<F, C, S, const D: usize>(
+    stark: &S,
+    stark_config: &StarkConfig,
+    degree_bits: usize,
+    config: &ShrinkConfig,
+) -> Result<VerifierCircuitData<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Clone,
+    C::Hasher: AlgebraicHasher<F>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+{
+    let circuits = circuits::<F, C, S, D>(stark, stark_config, degree_bits, config)?;
+    Ok(circuits.last().verifier_data())
+}
+
+/// Verify `proof` of `stark` in a plonky2 circuit and compress the result
+/// with plonky2 layers until the degree of the circuit reaches a fixed point
+///
+/// # Errors
+/// Returns an error if the starky proof does not verify, proving fails or the
+/// degree does not reach its fixed point within `config.max_layers` layers.
+pub fn shrink<F, C, S, const D: usize>(
+    stark: &S,
+    stark_config: &StarkConfig,
+    proof: &StarkProofWithPublicInputs<F, C, D>,
+    config: &ShrinkConfig,
+) -> Result<ShrunkProof<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D> + Clone,
+    C::Hasher: AlgebraicHasher<F>,
+    [(); S::COLUMNS]:,
+    [(); S::PUBLIC_INPUTS]:,
+    [(); C::Hasher::HASH_SIZE]:,
+{
+    // a bad proof would only surface as an unsatisfiable witness
+    verify_stark_proof(stark.clone(), proof.clone(), stark_config)?;
+    let degree_bits = proof.proof.recover_degree_bits(stark_config);
+    let circuits = circuits::<F, C, S, D>(stark, stark_config, degree_bits, config)?;
+
+    let mut witness = PartialWitness::new();
+    set_stark_proof_with_pis_target(&mut witness, &circuits.leaf_target, proof);
+    let mut wrapped = circuits.leaf.prove(witness)?;
+    debug_assert_eq!(wrapped.public_inputs, statement(proof));
+    for (data, target) in &circuits.layers {
+        let mut witness = PartialWitness::new();
+        witness.set_proof_with_pis_target(target, &wrapped);
+        wrapped = data.prove(witness)?;
+    }
+
+    Ok(ShrunkProof { proof: wrapped })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aggregation::statement;
+    use crate::columns::STATE_SIZE;
+    use crate::shrink::{final_verifier_data, shrink, ShrinkConfig};
+    use crate::stark::Poseidon2Stark;
+    use anyhow::Result;
+    use permutation_air::conformance::random_rows;
+    use permutation_air::generation::generate_trace;
+    use permutation_air::prover::{config_for_degree, prove_trace};
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use starky::stark::Stark;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2Stark<F, D>;
+
+    #[test]
+    fn shrink_poseidon2_proof() -> Result<()> {
+        let stark = S::default();
+        let mut stark_config = config_for_degree(stark.constraint_degree());
+        // fewer queries keep the recursive verifier small in debug builds
+        stark_config.fri_config.num_query_rounds = 10;
+        let trace = generate_trace(&stark, &random_rows::<F, STATE_SIZE>(8));
+        let proof = prove_trace::<F, C, S, D>(stark.clone(), &stark_config, trace)?;
+
+        // without layers on top of the first one, the degree is not fixed
+        let no_layers = ShrinkConfig {
+            max_layers: 0,
+            ..ShrinkConfig::default()
+        };
+        assert!(shrink(&stark, &stark_config, &proof, &no_layers).is_err());
+
+        let config = ShrinkConfig::default();
+        let shrunk = shrink(&stark, &stark_config, &proof, &config)?;
+        let degree_bits = proof.proof.recover_degree_bits(&stark_config);
+        let verifier_data = final_verifier_data(&stark, &stark_config, degree_bits, &config)?;
+        assert_eq!(
+            verifier_data.common.config,
+            CircuitConfig::standard_recursion_config()
+        );
+        assert_eq!(verifier_data.common.degree_bits(), 12);
+        shrunk.verify(&statement(&proof), &verifier_data)?;
+
+        // the final circuit has the same shape for longer traces, but not
+        // the same verifier data
+        let longer = final_verifier_data(&stark, &stark_config, degree_bits + 1, &config)?;
+        assert_eq!(longer.common.degree_bits(), 12);
+        assert!(shrunk.verify(&statement(&proof), &longer).is_err());
+        Ok(())
+    }
+}
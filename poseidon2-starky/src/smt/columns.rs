@@ -0,0 +1,55 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::NUM_COLS;
+
+/// The permutation compressing the path before the update, laid out as in
+/// `crate::columns`
+pub(crate) const COL_OLD_PERMUTATION_START: usize = 0;
+
+/// The permutation compressing the path after the update
+pub(crate) const COL_NEW_PERMUTATION_START: usize = COL_OLD_PERMUTATION_START + NUM_COLS; // 101
+
+/// The sibling at this level, shared by both paths
+pub(crate) const COL_SIBLING_START: usize = COL_NEW_PERMUTATION_START + NUM_COLS; // 202
+
+/// The node of the old path at this level, the old leaf at level 0
+pub(crate) const COL_OLD_NODE_START: usize = COL_SIBLING_START + DIGEST_SIZE; // 206
+
+/// The node of the new path at this level, the new leaf at level 0
+pub(crate) const COL_NEW_NODE_START: usize = COL_OLD_NODE_START + DIGEST_SIZE; // 210
+
+/// The key bit of this level: 1 if the node is the right child
+pub(crate) const COL_BIT: usize = COL_NEW_NODE_START + DIGEST_SIZE; // 214
+
+/// The level of the row, 0 for the leaves
+pub(crate) const COL_LEVEL: usize = COL_BIT + 1; // 215
+
+/// 1 in the last row of an update, whose outputs are the roots
+pub(crate) const COL_IS_ROOT: usize = COL_LEVEL + 1; // 216
+
+/// 2^level
+pub(crate) const COL_POWER: usize = COL_IS_ROOT + 1; // 217
+
+/// The key bits up to this level, as a number
+pub(crate) const COL_KEY_ACC: usize = COL_POWER + 1; // 218
+
+/// The key of the update, in every row of the update
+pub(crate) const COL_KEY: usize = COL_KEY_ACC + 1; // 219
+
+/// The root before the update, in every row of the update
+pub(crate) const COL_PREV_ROOT_START: usize = COL_KEY + 1; // 220
+
+/// The permutation compressing the old node into the commitment to the
+/// updates before this one, with the key added, laid out as in
+/// `crate::columns`
+pub(crate) const COL_OLD_VALUE_PERMUTATION_START: usize = COL_PREV_ROOT_START + DIGEST_SIZE; // 224
+
+/// The permutation compressing the new node into the output of the previous
+/// one, laid out as in `crate::columns`
+pub(crate) const COL_NEW_VALUE_PERMUTATION_START: usize =
+    COL_OLD_VALUE_PERMUTATION_START + NUM_COLS; // 325
+
+/// The commitment to the updates up to this one, in every row of the update
+pub(crate) const COL_COMMITMENT_START: usize = COL_NEW_VALUE_PERMUTATION_START + NUM_COLS; // 426
+
+/// The total number of columns
+pub(crate) const SMT_NUM_COLS: usize = COL_COMMITMENT_START + DIGEST_SIZE; // 430
@@ -0,0 +1,202 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::{COL_OUTPUT_START, STATE_SIZE};
+use crate::generation::poseidon2_air;
+use crate::smt::columns::{
+    COL_BIT, COL_COMMITMENT_START, COL_IS_ROOT, COL_KEY, COL_KEY_ACC, COL_LEVEL,
+    COL_NEW_NODE_START, COL_NEW_PERMUTATION_START, COL_NEW_VALUE_PERMUTATION_START,
+    COL_OLD_NODE_START, COL_OLD_PERMUTATION_START, COL_OLD_VALUE_PERMUTATION_START, COL_POWER,
+    COL_PREV_ROOT_START, COL_SIBLING_START, SMT_NUM_COLS,
+};
+use crate::smt::{Digest, UpdateWitness, TREE_DEPTH};
+use permutation_air::generation::fill_permutation_row;
+use plonky2::hash::hash_types::RichField;
+
+/// The number of public inputs: the root before the batch, the root after it
+/// and the [`updates_commitment`](crate::smt::updates_commitment)
+pub const SMT_PUBLIC_INPUTS: usize = 3 * DIGEST_SIZE;
+
+/// The input of the permutation compressing `node` with `sibling`
+fn compression_input<F: RichField>(
+    node: &Digest<F>,
+    sibling: &Digest<F>,
+    is_right: bool,
+) -> [F; STATE_SIZE] {
+    let (left, right) = if is_right {
+        (sibling, node)
+    } else {
+        (node, sibling)
+    };
+    let mut input = [F::ZERO; STATE_SIZE];
+    input[..DIGEST_SIZE].copy_from_slice(left);
+    input[DIGEST_SIZE..].copy_from_slice(right);
+    input
+}
+
+/// Append the [`TREE_DEPTH`] rows of a single update to a column-major trace,
+/// and return the commitment to the updates up to this one.
+///
+/// Every row compresses its old and new node into `commitment`, the
+/// commitment to the updates before this one, with the key added. The leaf row
+/// compresses the old and the new value, which gives the commitment of the
+/// update.
+fn fill_update_rows<F: RichField>(
+    trace: &mut [Vec<F>],
+    update: &UpdateWitness<F>,
+    commitment: &Digest<F>,
+) -> Digest<F> {
+    let mut old_node = update.old_value;
+    let mut new_node = update.new_value;
+    let mut key_acc = 0u64;
+    let mut new_commitment = *commitment;
+
+    let air = poseidon2_air();
+    for (level, sibling) in update.siblings.iter().enumerate() {
+        let row = trace[0].len();
+        for col in trace.iter_mut() {
+            col.push(F::ZERO);
+        }
+
+        let is_right = update.key >> level & 1 == 1;
+        key_acc |= u64::from(is_right) << level;
+        for (start, node) in [
+            (COL_OLD_PERMUTATION_START, &old_node),
+            (COL_NEW_PERMUTATION_START, &new_node),
+        ] {
+            fill_permutation_row(
+                &air,
+                &mut trace[start..],
+                row,
+                &compression_input(node, sibling, is_right),
+            );
+        }
+        let mut input = compression_input(commitment, &old_node, false);
+        input[0] += F::from_canonical_u32(update.key);
+        fill_permutation_row(
+            &air,
+            &mut trace[COL_OLD_VALUE_PERMUTATION_START..],
+            row,
+            &input,
+        );
+        for j in 0..DIGEST_SIZE {
+            input[j] = trace[COL_OLD_VALUE_PERMUTATION_START + COL_OUTPUT_START + j][row];
+        }
+        input[DIGEST_SIZE..].copy_from_slice(&new_node);
+        fill_permutation_row(
+            &air,
+            &mut trace[COL_NEW_VALUE_PERMUTATION_START..],
+            row,
+            &input,
+        );
+        if level == 0 {
+            for (j, x) in new_commitment.iter_mut().enumerate() {
+                *x = trace[COL_NEW_VALUE_PERMUTATION_START + COL_OUTPUT_START + j][row];
+            }
+        }
+
+        for j in 0..DIGEST_SIZE {
+            trace[COL_SIBLING_START + j][row] = sibling[j];
+            trace[COL_OLD_NODE_START + j][row] = old_node[j];
+            trace[COL_NEW_NODE_START + j][row] = new_node[j];
+            trace[COL_PREV_ROOT_START + j][row] = update.old_root[j];
+            old_node[j] = trace[COL_OLD_PERMUTATION_START + COL_OUTPUT_START + j][row];
+            new_node[j] = trace[COL_NEW_PERMUTATION_START + COL_OUTPUT_START + j][row];
+        }
+
+        trace[COL_BIT][row] = F::from_bool(is_right);
+        trace[COL_LEVEL][row] = F::from_canonical_usize(level);
+        trace[COL_IS_ROOT][row] = F::from_bool(level == TREE_DEPTH - 1);
+        trace[COL_POWER][row] = F::from_canonical_u64(1 << level);
+        trace[COL_KEY_ACC][row] = F::from_canonical_u64(key_acc);
+        trace[COL_KEY][row] = F::from_canonical_u32(update.key);
+        for j in 0..DIGEST_SIZE {
+            trace[COL_COMMITMENT_START + j][row] = new_commitment[j];
+        }
+    }
+    new_commitment
+}
+
+/// Function to generate the trace of a batch of updates, each one starting
+/// from the root of the previous one, and the public inputs: the root before
+/// the first update, the root after the last one and the commitment to the
+/// updates.
+///
+/// The trace is padded to a power of two with [`UpdateWitness::noop`] of the
+/// last update, which leaves the root unchanged.
+#[must_use]
+pub fn generate_smt_trace<F: RichField>(
+    updates: &[UpdateWitness<F>],
+) -> ([Vec<F>; SMT_NUM_COLS], [F; SMT_PUBLIC_INPUTS]) {
+    assert!(!updates.is_empty(), "a batch needs at least one update");
+    let mut trace: Vec<Vec<F>> = vec![Vec::new(); SMT_NUM_COLS];
+
+    let mut commitment = [F::ZERO; DIGEST_SIZE];
+    for update in updates {
+        commitment = fill_update_rows(&mut trace, update, &commitment);
+    }
+    let last = &updates[updates.len() - 1];
+    let noop = last.noop();
+    let ext_trace_len = trace[0].len().next_power_of_two();
+    while trace[0].len() < ext_trace_len {
+        commitment = fill_update_rows(&mut trace, &noop, &commitment);
+    }
+
+    let mut public_inputs = [F::ZERO; SMT_PUBLIC_INPUTS];
+    public_inputs[..DIGEST_SIZE].copy_from_slice(&updates[0].old_root);
+    public_inputs[DIGEST_SIZE..2 * DIGEST_SIZE].copy_from_slice(&last.new_root);
+    public_inputs[2 * DIGEST_SIZE..].copy_from_slice(&commitment);
+
+    let trace = trace.try_into().unwrap_or_else(|v: Vec<Vec<F>>| {
+        panic!(
+            "Expected a Vec of length {} but it was {}",
+            SMT_NUM_COLS,
+            v.len()
+        )
+    });
+    (trace, public_inputs)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bytes::DIGEST_SIZE;
+    use crate::columns::COL_OUTPUT_START;
+    use crate::smt::columns::{COL_IS_ROOT, COL_NEW_PERMUTATION_START, COL_OLD_PERMUTATION_START};
+    use crate::smt::generation::generate_smt_trace;
+    use crate::smt::{updates_commitment, SparseMerkleTree, TREE_DEPTH};
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn generate_smt_trace_roots() {
+        let mut tree = SparseMerkleTree::<F>::new();
+        let updates: Vec<_> = (0..3)
+            .map(|key| tree.update(key, F::rand_array()))
+            .collect();
+
+        let (trace, public_inputs) = generate_smt_trace(&updates);
+        assert_eq!(trace[0].len(), 4 * TREE_DEPTH);
+        for (i, update) in updates.iter().enumerate() {
+            let row = (i + 1) * TREE_DEPTH - 1;
+            assert_eq!(trace[COL_IS_ROOT][row], F::ONE);
+            for j in 0..DIGEST_SIZE {
+                assert_eq!(
+                    trace[COL_OLD_PERMUTATION_START + COL_OUTPUT_START + j][row],
+                    update.old_root[j]
+                );
+                assert_eq!(
+                    trace[COL_NEW_PERMUTATION_START + COL_OUTPUT_START + j][row],
+                    update.new_root[j]
+                );
+            }
+        }
+        assert_eq!(public_inputs[..DIGEST_SIZE], updates[0].old_root);
+        assert_eq!(public_inputs[DIGEST_SIZE..2 * DIGEST_SIZE], tree.root());
+        assert_eq!(
+            public_inputs[2 * DIGEST_SIZE..],
+            updates_commitment(&updates)
+        );
+    }
+}
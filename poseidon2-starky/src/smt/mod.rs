@@ -0,0 +1,260 @@
+//! A sparse Merkle tree over the Poseidon2 permutation, with a STARK proving
+//! batches of leaf updates.
+//!
+//! The tree has [`TREE_DEPTH`] levels above the leaves and a leaf for every
+//! `u32` key, whose bits from the least significant one select the path from
+//! the leaf to the root. Leaves hold [`DIGEST_SIZE`] field elements, and the
+//! empty leaf is zero, so deleting a key sets its leaf back to zero. Two
+//! children are compressed by permuting their concatenation and keeping the
+//! first [`DIGEST_SIZE`] elements of the output.
+#![allow(clippy::module_name_repetitions)]
+
+pub mod columns;
+pub mod generation;
+pub mod stark;
+
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::STATE_SIZE;
+use crate::generation::generate_outputs;
+use crate::params::POSEIDON2_PARAMS_128;
+use plonky2::hash::hash_types::RichField;
+use std::collections::HashMap;
+
+/// The number of levels above the leaves
+pub const TREE_DEPTH: usize = 32;
+
+/// A leaf or an inner node
+pub type Digest<F> = [F; DIGEST_SIZE];
+
+/// Compress two children into their parent
+#[must_use]
+pub fn compress<F: RichField>(left: &Digest<F>, right: &Digest<F>) -> Digest<F> {
+    let mut input = [F::ZERO; STATE_SIZE];
+    input[..DIGEST_SIZE].copy_from_slice(left);
+    input[DIGEST_SIZE..].copy_from_slice(right);
+    generate_outputs(&POSEIDON2_PARAMS_128, &input)[..DIGEST_SIZE]
+        .try_into()
+        .unwrap()
+}
+
+/// Everything needed to prove that setting `key` to `new_value` turns
+/// `old_root` into `new_root`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateWitness<F> {
+    pub key: u32,
+    pub old_value: Digest<F>,
+    pub new_value: Digest<F>,
+    /// The siblings of the path, from the leaf level up; they are the same
+    /// before and after the update
+    pub siblings: [Digest<F>; TREE_DEPTH],
+    pub old_root: Digest<F>,
+    pub new_root: Digest<F>,
+}
+
+/// The root of the path from `value` at `key` with `siblings`
+#[must_use]
+pub fn path_root<F: RichField>(
+    key: u32,
+    value: &Digest<F>,
+    siblings: &[Digest<F>; TREE_DEPTH],
+) -> Digest<F> {
+    siblings
+        .iter()
+        .enumerate()
+        .fold(*value, |node, (level, sibling)| {
+            if key >> level & 1 == 0 {
+                compress(&node, sibling)
+            } else {
+                compress(sibling, &node)
+            }
+        })
+}
+
+impl<F: RichField> UpdateWitness<F> {
+    /// Check the old and the new path against the roots
+    #[must_use]
+    pub fn verify(&self) -> bool {
+        path_root(self.key, &self.old_value, &self.siblings) == self.old_root
+            && path_root(self.key, &self.new_value, &self.siblings) == self.new_root
+    }
+
+    /// The update writing the new value again, which leaves the root
+    /// unchanged and pads a batch
+    #[must_use]
+    pub fn noop(&self) -> Self {
+        Self {
+            old_value: self.new_value,
+            old_root: self.new_root,
+            ..self.clone()
+        }
+    }
+}
+
+/// The commitment to a batch of updates exposed by
+/// [`Poseidon2SmtStark`](stark::Poseidon2SmtStark). The batch is padded to a
+/// power of two with [`UpdateWitness::noop`] of the last update, as in the
+/// trace. For every update, the key is added to an accumulator starting at
+/// zero, then the old and the new value are compressed into it.
+///
+/// # Panics
+/// Panics if `updates` is empty.
+#[must_use]
+pub fn updates_commitment<F: RichField>(updates: &[UpdateWitness<F>]) -> Digest<F> {
+    let last = updates.last().expect("a batch needs at least one update");
+    let noop = last.noop();
+    (0..updates.len().next_power_of_two()).fold([F::ZERO; DIGEST_SIZE], |mut acc, i| {
+        let update = updates.get(i).unwrap_or(&noop);
+        acc[0] += F::from_canonical_u32(update.key);
+        compress(&compress(&acc, &update.old_value), &update.new_value)
+    })
+}
+
+/// A sparse Merkle tree storing only the nodes that differ from the empty
+/// subtree of their level
+#[derive(Debug, Clone)]
+pub struct SparseMerkleTree<F> {
+    /// The non-empty nodes by level, 0 for the leaves, and index in the level
+    nodes: HashMap<(usize, u64), Digest<F>>,
+    /// The root of an empty subtree of every height, up to the whole tree
+    empty: [Digest<F>; TREE_DEPTH + 1],
+}
+
+impl<F: RichField> Default for SparseMerkleTree<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: RichField> SparseMerkleTree<F> {
+    /// The empty tree
+    #[must_use]
+    pub fn new() -> Self {
+        let mut empty = [[F::ZERO; DIGEST_SIZE]; TREE_DEPTH + 1];
+        for level in 0..TREE_DEPTH {
+            empty[level + 1] = compress(&empty[level], &empty[level]);
+        }
+        Self {
+            nodes: HashMap::new(),
+            empty,
+        }
+    }
+
+    fn node(&self, level: usize, index: u64) -> Digest<F> {
+        self.nodes
+            .get(&(level, index))
+            .copied()
+            .unwrap_or(self.empty[level])
+    }
+
+    #[must_use]
+    pub fn root(&self) -> Digest<F> {
+        self.node(TREE_DEPTH, 0)
+    }
+
+    /// The leaf at `key`, zero if the key is absent
+    #[must_use]
+    pub fn get(&self, key: u32) -> Digest<F> {
+        self.node(0, u64::from(key))
+    }
+
+    /// Set the leaf at `key` to `value`, returning the witness of the update
+    pub fn update(&mut self, key: u32, value: Digest<F>) -> UpdateWitness<F> {
+        let old_value = self.get(key);
+        let old_root = self.root();
+        let mut siblings = [[F::ZERO; DIGEST_SIZE]; TREE_DEPTH];
+
+        let mut index = u64::from(key);
+        let mut node = value;
+        for (level, sibling) in siblings.iter_mut().enumerate() {
+            self.set(level, index, node);
+            *sibling = self.node(level, index ^ 1);
+            node = if index & 1 == 0 {
+                compress(&node, sibling)
+            } else {
+                compress(sibling, &node)
+            };
+            index >>= 1;
+        }
+        self.set(TREE_DEPTH, 0, node);
+
+        UpdateWitness {
+            key,
+            old_value,
+            new_value: value,
+            siblings,
+            old_root,
+            new_root: node,
+        }
+    }
+
+    /// Remove `key`, returning the witness of the update
+    pub fn delete(&mut self, key: u32) -> UpdateWitness<F> {
+        self.update(key, [F::ZERO; DIGEST_SIZE])
+    }
+
+    fn set(&mut self, level: usize, index: u64, node: Digest<F>) {
+        if node == self.empty[level] {
+            self.nodes.remove(&(level, index));
+        } else {
+            self.nodes.insert((level, index), node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smt::{SparseMerkleTree, UpdateWitness, TREE_DEPTH};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn inserts_updates_and_deletes() {
+        let mut tree = SparseMerkleTree::<F>::new();
+        let empty_root = tree.root();
+        let keys = [0, 1, 7, u32::MAX];
+
+        let mut witnesses = Vec::new();
+        for key in keys {
+            witnesses.push(tree.update(key, F::rand_array()));
+        }
+        let value = F::rand_array();
+        witnesses.push(tree.update(7, value));
+        assert_eq!(tree.get(7), value);
+        for key in keys {
+            witnesses.push(tree.delete(key));
+        }
+
+        // every update starts from the root of the previous one
+        assert!(witnesses.iter().all(UpdateWitness::verify));
+        assert!(witnesses
+            .windows(2)
+            .all(|pair| pair[0].new_root == pair[1].old_root));
+        assert_eq!(witnesses[0].old_root, empty_root);
+        assert_eq!(tree.root(), empty_root);
+        assert_eq!(tree.nodes.len(), 0);
+    }
+
+    #[test]
+    fn root_does_not_depend_on_the_order() {
+        let leaves: Vec<(u32, [F; 4])> = [0, 3, 6].map(|key| (key, F::rand_array())).to_vec();
+        let mut forward = SparseMerkleTree::new();
+        let mut backward = SparseMerkleTree::new();
+        for &(key, value) in &leaves {
+            forward.update(key, value);
+        }
+        for &(key, value) in leaves.iter().rev() {
+            backward.update(key, value);
+        }
+        assert_eq!(forward.root(), backward.root());
+        assert_ne!(forward.root(), SparseMerkleTree::<F>::new().root());
+    }
+
+    #[test]
+    fn empty_root_is_the_empty_subtree_of_full_height() {
+        let tree = SparseMerkleTree::<F>::new();
+        assert_eq!(tree.root(), tree.empty[TREE_DEPTH]);
+        assert_eq!(tree.get(5), [F::ZERO; 4]);
+    }
+}
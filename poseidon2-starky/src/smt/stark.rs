@@ -0,0 +1,287 @@
+use crate::bytes::DIGEST_SIZE;
+use crate::columns::{COL_INPUT_START, COL_OUTPUT_START};
+use crate::params::POSEIDON2_PARAMS_128;
+use crate::smt::columns::{
+    COL_BIT, COL_COMMITMENT_START, COL_IS_ROOT, COL_KEY, COL_KEY_ACC, COL_LEVEL,
+    COL_NEW_NODE_START, COL_NEW_PERMUTATION_START, COL_NEW_VALUE_PERMUTATION_START,
+    COL_OLD_NODE_START, COL_OLD_PERMUTATION_START, COL_OLD_VALUE_PERMUTATION_START, COL_POWER,
+    COL_PREV_ROOT_START, COL_SIBLING_START, SMT_NUM_COLS,
+};
+use crate::smt::generation::SMT_PUBLIC_INPUTS;
+use crate::smt::TREE_DEPTH;
+use crate::stark::eval_permutation;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+/// The public inputs: the root before the batch, the root after it, then the
+/// commitment to the updates
+pub(crate) const PI_OLD_ROOT_START: usize = 0;
+pub(crate) const PI_NEW_ROOT_START: usize = PI_OLD_ROOT_START + DIGEST_SIZE;
+pub(crate) const PI_COMMITMENT_START: usize = PI_NEW_ROOT_START + DIGEST_SIZE;
+
+/// The permutation starting at `start` compresses the node starting at
+/// `node_start` with the sibling, in the order given by the key bit
+// degree: 2
+fn compression_input_constraints<P: PackedField>(
+    lv: &[P],
+    start: usize,
+    node_start: usize,
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let bit = lv[COL_BIT];
+    let input = &lv[start + COL_INPUT_START..];
+    for j in 0..DIGEST_SIZE {
+        let node = lv[node_start + j];
+        let sibling = lv[COL_SIBLING_START + j];
+        yield_constr.constraint(input[j] - (node + bit * (sibling - node)));
+        yield_constr.constraint(input[DIGEST_SIZE + j] - (sibling + bit * (node - sibling)));
+    }
+}
+
+/// The key is added to the commitment to the updates before, then the old
+/// and the new node are compressed into it
+fn eval_commitment<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    public_inputs: &[P::Scalar],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let is_root = lv[COL_IS_ROOT];
+    let not_root = P::ONES - is_root;
+    let key = |v: &[P], j| if j == 0 { v[COL_KEY] } else { P::ZEROS };
+    let old_value_input = &lv[COL_OLD_VALUE_PERMUTATION_START + COL_INPUT_START..];
+    let old_value_output = &lv[COL_OLD_VALUE_PERMUTATION_START + COL_OUTPUT_START..];
+    let new_value_input = &lv[COL_NEW_VALUE_PERMUTATION_START + COL_INPUT_START..];
+    let new_value_output = &lv[COL_NEW_VALUE_PERMUTATION_START + COL_OUTPUT_START..];
+    let next_old_value_input = &nv[COL_OLD_VALUE_PERMUTATION_START + COL_INPUT_START..];
+    let next_new_value_output = &nv[COL_NEW_VALUE_PERMUTATION_START + COL_OUTPUT_START..];
+    for j in 0..DIGEST_SIZE {
+        let commitment = lv[COL_COMMITMENT_START + j];
+        let next_commitment = nv[COL_COMMITMENT_START + j];
+        yield_constr.constraint(old_value_input[DIGEST_SIZE + j] - lv[COL_OLD_NODE_START + j]);
+        yield_constr.constraint(new_value_input[j] - old_value_output[j]);
+        yield_constr.constraint(new_value_input[DIGEST_SIZE + j] - lv[COL_NEW_NODE_START + j]);
+
+        // the commitment before the first update is zero, and the leaf row
+        // of an update gives its commitment
+        yield_constr.constraint_first_row(old_value_input[j] - key(lv, j));
+        yield_constr.constraint_first_row(commitment - new_value_output[j]);
+        yield_constr
+            .constraint_transition(not_root * (next_old_value_input[j] - old_value_input[j]));
+        yield_constr.constraint_transition(not_root * (next_commitment - commitment));
+        yield_constr
+            .constraint_transition(is_root * (next_old_value_input[j] - key(nv, j) - commitment));
+        yield_constr.constraint_transition(is_root * (next_commitment - next_new_value_output[j]));
+        yield_constr.constraint_last_row(commitment - public_inputs[PI_COMMITMENT_START + j]);
+    }
+}
+
+/// Batches of sparse Merkle tree updates: every row compresses one level of
+/// the old and the new path of an update, with the same sibling, and the
+/// roots chain from update to update.
+///
+/// Two more permutations per row compress the key, the old node and the new
+/// node into the commitment to the updates before. At the leaf, these are the
+/// key and the values of the update, which gives the
+/// [`updates_commitment`](crate::smt::updates_commitment) in the public
+/// inputs after the roots.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Poseidon2SmtStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Poseidon2SmtStark<F, D> {
+    const COLUMNS: usize = SMT_NUM_COLS;
+    const PUBLIC_INPUTS: usize = SMT_PUBLIC_INPUTS;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let lv = vars.local_values;
+        let nv = vars.next_values;
+        for start in [
+            COL_OLD_PERMUTATION_START,
+            COL_NEW_PERMUTATION_START,
+            COL_OLD_VALUE_PERMUTATION_START,
+            COL_NEW_VALUE_PERMUTATION_START,
+        ] {
+            eval_permutation(&POSEIDON2_PARAMS_128, &lv[start..], yield_constr);
+        }
+        compression_input_constraints(
+            lv,
+            COL_OLD_PERMUTATION_START,
+            COL_OLD_NODE_START,
+            yield_constr,
+        );
+        compression_input_constraints(
+            lv,
+            COL_NEW_PERMUTATION_START,
+            COL_NEW_NODE_START,
+            yield_constr,
+        );
+
+        let bit = lv[COL_BIT];
+        let is_root = lv[COL_IS_ROOT];
+        yield_constr.constraint(bit * (bit - P::ONES));
+        yield_constr.constraint(is_root * (is_root - P::ONES));
+        // an update covers the levels 0 to TREE_DEPTH - 1
+        yield_constr
+            .constraint(is_root * (lv[COL_LEVEL] - FE::from_canonical_usize(TREE_DEPTH - 1)));
+
+        // the trace starts at the leaf of the first update, from the old root
+        yield_constr.constraint_first_row(lv[COL_LEVEL]);
+        yield_constr.constraint_first_row(lv[COL_POWER] - P::ONES);
+        yield_constr.constraint_first_row(lv[COL_KEY_ACC] - bit);
+        for j in 0..DIGEST_SIZE {
+            yield_constr.constraint_first_row(
+                lv[COL_PREV_ROOT_START + j] - vars.public_inputs[PI_OLD_ROOT_START + j],
+            );
+        }
+
+        // the key bits add up to the key, and the old path ends at the root
+        // before the update
+        let old_output = &lv[COL_OLD_PERMUTATION_START + COL_OUTPUT_START..];
+        let new_output = &lv[COL_NEW_PERMUTATION_START + COL_OUTPUT_START..];
+        yield_constr.constraint(is_root * (lv[COL_KEY_ACC] - lv[COL_KEY]));
+        for j in 0..DIGEST_SIZE {
+            yield_constr.constraint(is_root * (old_output[j] - lv[COL_PREV_ROOT_START + j]));
+        }
+
+        // within an update, the outputs are the nodes of the next level
+        let not_root = P::ONES - is_root;
+        let next_bit = nv[COL_BIT];
+        yield_constr.constraint_transition(not_root * (nv[COL_LEVEL] - lv[COL_LEVEL] - P::ONES));
+        yield_constr.constraint_transition(
+            not_root * (nv[COL_POWER] - lv[COL_POWER] * FE::from_canonical_u64(2)),
+        );
+        yield_constr.constraint_transition(
+            not_root * (nv[COL_KEY_ACC] - lv[COL_KEY_ACC] - next_bit * nv[COL_POWER]),
+        );
+        yield_constr.constraint_transition(not_root * (nv[COL_KEY] - lv[COL_KEY]));
+        for j in 0..DIGEST_SIZE {
+            yield_constr
+                .constraint_transition(not_root * (nv[COL_OLD_NODE_START + j] - old_output[j]));
+            yield_constr
+                .constraint_transition(not_root * (nv[COL_NEW_NODE_START + j] - new_output[j]));
+            yield_constr.constraint_transition(
+                not_root * (nv[COL_PREV_ROOT_START + j] - lv[COL_PREV_ROOT_START + j]),
+            );
+        }
+
+        // after a root, the next update starts at its leaf from the new root
+        yield_constr.constraint_transition(is_root * nv[COL_LEVEL]);
+        yield_constr.constraint_transition(is_root * (nv[COL_POWER] - P::ONES));
+        yield_constr.constraint_transition(is_root * (nv[COL_KEY_ACC] - next_bit));
+        for j in 0..DIGEST_SIZE {
+            yield_constr
+                .constraint_transition(is_root * (nv[COL_PREV_ROOT_START + j] - new_output[j]));
+        }
+
+        // the trace ends with the root after the last update
+        yield_constr.constraint_last_row(is_root - P::ONES);
+        for (&root, &public_input) in new_output
+            .iter()
+            .zip(&vars.public_inputs[PI_NEW_ROOT_START..PI_COMMITMENT_START])
+        {
+            yield_constr.constraint_last_row(root - public_input);
+        }
+
+        eval_commitment(lv, nv, vars.public_inputs, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::smt::generation::generate_smt_trace;
+    use crate::smt::stark::{Poseidon2SmtStark, PI_COMMITMENT_START};
+    use crate::smt::{updates_commitment, SparseMerkleTree};
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2SmtStark<F, D>;
+
+    #[test]
+    fn poseidon2_smt_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        // inserts, an update of an existing key and a delete
+        let mut tree = SparseMerkleTree::<F>::new();
+        let mut updates: Vec<_> = [3, 12, u32::MAX]
+            .into_iter()
+            .map(|key| tree.update(key, F::rand_array()))
+            .collect();
+        updates.push(tree.update(12, F::rand_array()));
+        updates.push(tree.delete(3));
+
+        let stark = S::default();
+        let (trace, public_inputs) = generate_smt_trace(&updates);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        // the same roots with the commitment of other updates are rejected
+        let mut other_key = updates.clone();
+        other_key[1].key ^= 1;
+        let mut other_old_value = updates.clone();
+        other_old_value[3].old_value[0] += F::ONE;
+        let mut other_new_value = updates.clone();
+        other_new_value[0].new_value[3] += F::ONE;
+        for other in [other_key, other_old_value, other_new_value] {
+            let mut forged = proof.clone();
+            forged.public_inputs[PI_COMMITMENT_START..]
+                .copy_from_slice(&updates_commitment(&other));
+            assert!(verify_stark_proof(stark, forged, &config).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn poseidon2_smt_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
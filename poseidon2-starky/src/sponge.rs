@@ -0,0 +1,390 @@
+//! The SAFE sponge API over the Poseidon2 permutation.
+//!
+//! A [`Sponge`] is started with an [`IoPattern`], the sequence of absorb and
+//! squeeze calls it expects, and a domain separator. Consecutive calls of the
+//! same kind are aggregated and each one is encoded as a big-endian `u32`,
+//! `0x8000_0000 + n` for `ABSORB(n)` and `m` for `SQUEEZE(m)`. The first
+//! [`TAG_SIZE`] elements of [`hash_bytes`] of these words followed by the
+//! domain separator form the tag, which starts the capacity:
+//! `[tag_0, tag_1, 0, 0]`.
+//!
+//! Absorbing adds elements to the [`RATE`] elements of the state and permutes
+//! once the rate is full. Squeezing permutes and then reads the rate, so an
+//! absorb is never squeezed back without a permutation. Every call is checked
+//! against the pattern, and [`Sponge::finish`] checks that the whole pattern
+//! was used. Every permutation is recorded as a [`DuplexRow`], so a sponge
+//! run can be proved by [`Poseidon2DuplexStark`] from the trace of
+//! [`generate_duplex_trace`]. The public inputs are
+//! [`IoPattern::initial_capacity`] followed by the [`transcript_commitment`]
+//! of the rows and the squeezed elements. The first capacity is the tag, the
+//! capacity carries from each permutation into the next, and the squeezed
+//! elements are read from the rate, so a proof binds the tag to the absorbed
+//! and the squeezed elements.
+//!
+//! [`Poseidon2DuplexStark`]: crate::duplex::stark::Poseidon2DuplexStark
+//! [`generate_duplex_trace`]: crate::duplex::generation::generate_duplex_trace
+//! [`transcript_commitment`]: crate::duplex::transcript_commitment
+
+use crate::bytes::{hash_bytes, RATE};
+use crate::columns::STATE_SIZE;
+use crate::duplex::{DuplexRow, CAPACITY};
+use crate::generation::generate_outputs;
+use crate::params::POSEIDON2_PARAMS_128;
+use anyhow::{bail, ensure, Result};
+use plonky2::hash::hash_types::RichField;
+
+/// The number of capacity elements holding the tag
+pub const TAG_SIZE: usize = 2;
+
+/// The largest length of a single call
+const MAX_CALL_LENGTH: u32 = (1 << 31) - 1;
+
+/// A call to a [`Sponge`] and the number of elements it absorbs or squeezes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum SpongeOp {
+    Absorb(u32),
+    Squeeze(u32),
+}
+
+impl SpongeOp {
+    fn length(self) -> u32 {
+        match self {
+            Self::Absorb(n) | Self::Squeeze(n) => n,
+        }
+    }
+
+    fn with_length(self, length: u32) -> Self {
+        match self {
+            Self::Absorb(_) => Self::Absorb(length),
+            Self::Squeeze(_) => Self::Squeeze(length),
+        }
+    }
+
+    fn same_kind(self, other: Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::Absorb(_), Self::Absorb(_)) | (Self::Squeeze(_), Self::Squeeze(_))
+        )
+    }
+
+    fn encode(self) -> u32 {
+        match self {
+            Self::Absorb(n) => 0x8000_0000 + n,
+            Self::Squeeze(m) => m,
+        }
+    }
+}
+
+/// The aggregated sequence of calls a [`Sponge`] expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoPattern(Vec<SpongeOp>);
+
+impl IoPattern {
+    /// Aggregate `ops` into a pattern
+    ///
+    /// # Errors
+    /// Returns an error if a call is empty or an aggregated call has
+    /// `2^31` elements or more.
+    pub fn new(ops: &[SpongeOp]) -> Result<Self> {
+        let mut aggregated: Vec<SpongeOp> = Vec::new();
+        for &op in ops {
+            ensure!(op.length() > 0, "empty call {op:?} in the IO pattern");
+            match aggregated.last_mut() {
+                Some(last) if last.same_kind(op) => {
+                    let length = last.length().saturating_add(op.length());
+                    ensure!(
+                        length <= MAX_CALL_LENGTH,
+                        "call of {length} elements in the IO pattern"
+                    );
+                    *last = last.with_length(length);
+                }
+                _ => {
+                    ensure!(
+                        op.length() <= MAX_CALL_LENGTH,
+                        "call {op:?} in the IO pattern is too long"
+                    );
+                    aggregated.push(op);
+                }
+            }
+        }
+        Ok(Self(aggregated))
+    }
+
+    /// The aggregated calls
+    #[must_use]
+    pub fn ops(&self) -> &[SpongeOp] {
+        &self.0
+    }
+
+    /// The tag of the pattern under `domain_separator`
+    #[must_use]
+    pub fn tag<F: RichField>(&self, domain_separator: &[u8]) -> [F; TAG_SIZE] {
+        let mut bytes: Vec<u8> = self
+            .0
+            .iter()
+            .flat_map(|op| op.encode().to_be_bytes())
+            .collect();
+        bytes.extend_from_slice(domain_separator);
+        hash_bytes::<F>(&bytes)[..TAG_SIZE].try_into().unwrap()
+    }
+
+    /// The capacity a sponge with this pattern starts from under
+    /// `domain_separator`: `[tag_0, tag_1, 0, 0]`
+    #[must_use]
+    pub fn initial_capacity<F: RichField>(&self, domain_separator: &[u8]) -> [F; CAPACITY] {
+        let mut capacity = [F::ZERO; CAPACITY];
+        capacity[..TAG_SIZE].copy_from_slice(&self.tag(domain_separator));
+        capacity
+    }
+}
+
+/// A sponge checking its calls against an [`IoPattern`]
+#[derive(Debug, Clone)]
+pub struct Sponge<F: RichField> {
+    state: [F; STATE_SIZE],
+    pattern: IoPattern,
+    /// The index of the current call in the pattern
+    op_index: usize,
+    /// The number of elements of the current call already used
+    op_used: u32,
+    absorb_pos: usize,
+    squeeze_pos: usize,
+    /// The elements absorbed since the last permutation
+    pending: DuplexRow<F>,
+    rows: Vec<DuplexRow<F>>,
+}
+
+impl<F: RichField> Sponge<F> {
+    /// Start a sponge expecting the calls of `pattern`
+    #[must_use]
+    pub fn new(pattern: IoPattern, domain_separator: &[u8]) -> Self {
+        let mut state = [F::ZERO; STATE_SIZE];
+        state[RATE..].copy_from_slice(&pattern.initial_capacity(domain_separator));
+        Self {
+            state,
+            pattern,
+            op_index: 0,
+            op_used: 0,
+            absorb_pos: 0,
+            squeeze_pos: RATE,
+            pending: DuplexRow::default(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Use `length` elements of the current call, which must be like `op`
+    fn advance(&mut self, op: SpongeOp, length: usize) -> Result<()> {
+        let Some(&expected) = self.pattern.0.get(self.op_index) else {
+            bail!("{op:?} after the end of the IO pattern");
+        };
+        ensure!(
+            expected.same_kind(op),
+            "{op:?} where the IO pattern expects {expected:?}"
+        );
+        let left = expected.length() - self.op_used;
+        let length = u32::try_from(length).ok().filter(|&n| n <= left);
+        let Some(length) = length else {
+            bail!("{op:?} exceeds the {left} elements left in {expected:?}");
+        };
+        self.op_used += length;
+        if self.op_used == expected.length() {
+            self.op_index += 1;
+            self.op_used = 0;
+        }
+        Ok(())
+    }
+
+    fn permute(&mut self) {
+        self.rows.push(std::mem::take(&mut self.pending));
+        self.state = generate_outputs(&POSEIDON2_PARAMS_128, &self.state);
+    }
+
+    /// Absorb `inputs`
+    ///
+    /// # Errors
+    /// Returns an error if the IO pattern does not expect `inputs.len()`
+    /// more absorbed elements here.
+    pub fn absorb(&mut self, inputs: &[F]) -> Result<()> {
+        if inputs.is_empty() {
+            return Ok(());
+        }
+        self.advance(SpongeOp::Absorb(0), inputs.len())?;
+        for &input in inputs {
+            if self.absorb_pos == RATE {
+                self.permute();
+                self.absorb_pos = 0;
+            }
+            self.state[self.absorb_pos] += input;
+            self.pending.absorbed[self.absorb_pos] = input;
+            self.absorb_pos += 1;
+        }
+        self.squeeze_pos = RATE;
+        Ok(())
+    }
+
+    /// Squeeze `length` elements
+    ///
+    /// # Errors
+    /// Returns an error if the IO pattern does not expect `length` more
+    /// squeezed elements here.
+    pub fn squeeze(&mut self, length: usize) -> Result<Vec<F>> {
+        if length == 0 {
+            return Ok(Vec::new());
+        }
+        self.advance(SpongeOp::Squeeze(0), length)?;
+        let mut outputs = Vec::with_capacity(length);
+        for _ in 0..length {
+            if self.squeeze_pos == RATE {
+                self.permute();
+                self.squeeze_pos = 0;
+                self.absorb_pos = 0;
+            }
+            outputs.push(self.state[self.squeeze_pos]);
+            // a squeeze always follows a permutation
+            self.rows.last_mut().unwrap().squeezed[self.squeeze_pos] = true;
+            self.squeeze_pos += 1;
+        }
+        Ok(outputs)
+    }
+
+    /// The rows of the permutations so far
+    #[must_use]
+    pub fn rows(&self) -> &[DuplexRow<F>] {
+        &self.rows
+    }
+
+    /// End the sponge and return the rows of its permutations, to be proved
+    /// with [`generate_duplex_trace`](crate::duplex::generation::generate_duplex_trace)
+    /// from [`IoPattern::initial_capacity`]. Elements absorbed after the last
+    /// squeeze are not part of any permutation.
+    ///
+    /// # Errors
+    /// Returns an error if calls of the IO pattern are left.
+    pub fn finish(self) -> Result<Vec<DuplexRow<F>>> {
+        ensure!(
+            self.op_index == self.pattern.0.len(),
+            "the IO pattern still expects {:?}",
+            &self.pattern.0[self.op_index..]
+        );
+        Ok(self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::duplex::generation::generate_duplex_trace;
+    use crate::duplex::stark::Poseidon2DuplexStark;
+    use crate::duplex::{transcript_commitment, CAPACITY};
+    use crate::sponge::{IoPattern, Sponge, SpongeOp};
+    use crate::stark::trace_to_poly_values;
+    use anyhow::Result;
+    use permutation_air::prover::config_for_degree;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::prover::prove;
+    use starky::stark::Stark;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Poseidon2DuplexStark<F, D>;
+
+    #[test]
+    fn interleaved_calls_follow_the_pattern() -> Result<()> {
+        let pattern = IoPattern::new(&[
+            SpongeOp::Absorb(3),
+            SpongeOp::Absorb(3),
+            SpongeOp::Squeeze(5),
+            SpongeOp::Absorb(1),
+            SpongeOp::Squeeze(1),
+        ])?;
+        assert_eq!(
+            pattern.ops(),
+            [
+                SpongeOp::Absorb(6),
+                SpongeOp::Squeeze(5),
+                SpongeOp::Absorb(1),
+                SpongeOp::Squeeze(1)
+            ]
+        );
+
+        let inputs = F::rand_vec(7);
+        let mut sponge = Sponge::new(pattern.clone(), b"test");
+        sponge.absorb(&inputs[..2])?;
+        sponge.absorb(&inputs[2..6])?;
+        let first = sponge.squeeze(5)?;
+        sponge.absorb(&inputs[6..])?;
+        let second = sponge.squeeze(1)?;
+        let rows = sponge.finish()?;
+        assert_eq!(rows.len(), 4);
+
+        // splitting calls differently squeezes the same elements
+        let mut sponge = Sponge::new(pattern.clone(), b"test");
+        sponge.absorb(&inputs[..6])?;
+        assert_eq!([sponge.squeeze(2)?, sponge.squeeze(3)?].concat(), first);
+        sponge.absorb(&inputs[6..])?;
+        assert_eq!(sponge.squeeze(1)?, second);
+
+        // another domain separator squeezes other elements
+        let mut sponge = Sponge::new(pattern.clone(), b"other");
+        sponge.absorb(&inputs[..6])?;
+        assert_ne!(sponge.squeeze(5)?, first);
+
+        // the proof covers the run from the tag to the squeezed elements
+        let squeezed = [first, second].concat();
+        let (trace, public_inputs) =
+            generate_duplex_trace(&pattern.initial_capacity(b"test"), &rows);
+        assert_eq!(public_inputs[..CAPACITY], pattern.initial_capacity(b"test"));
+        assert_eq!(
+            public_inputs[CAPACITY..],
+            transcript_commitment(&rows, &squeezed)
+        );
+
+        let stark = S::default();
+        let config = config_for_degree(stark.constraint_degree());
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_to_poly_values(trace),
+            public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        // the run does not start from the tag of another domain separator
+        let mut forged = proof.clone();
+        forged.public_inputs[..CAPACITY].copy_from_slice(&pattern.initial_capacity(b"other"));
+        assert!(verify_stark_proof(stark, forged, &config).is_err());
+
+        // nor squeeze other elements
+        let mut other = squeezed;
+        other[0] += F::ONE;
+        let mut forged = proof;
+        forged.public_inputs[CAPACITY..].copy_from_slice(&transcript_commitment(&rows, &other));
+        assert!(verify_stark_proof(stark, forged, &config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn deviating_calls_fail() -> Result<()> {
+        let pattern = IoPattern::new(&[SpongeOp::Absorb(2), SpongeOp::Squeeze(1)])?;
+        let inputs = F::rand_vec(3);
+
+        let mut sponge = Sponge::new(pattern, b"");
+        assert!(sponge.squeeze(1).is_err());
+        assert!(sponge.absorb(&inputs).is_err());
+        sponge.absorb(&inputs[..1])?;
+        assert!(sponge.clone().finish().is_err());
+        sponge.absorb(&inputs[1..2])?;
+        sponge.squeeze(1)?;
+        assert!(sponge.absorb(&inputs[..1]).is_err());
+        sponge.finish()?;
+
+        assert!(IoPattern::new(&[SpongeOp::Squeeze(0)]).is_err());
+        assert!(IoPattern::new(&[SpongeOp::Absorb(1 << 30), SpongeOp::Absorb(1 << 30)]).is_err());
+        Ok(())
+    }
+}
@@ -1,53 +1,23 @@
 use crate::columns::{
-    COL_1ST_FULLROUND_STATE_START, COL_2ND_FULLROUND_STATE_START,
-    COL_PARTIAL_ROUND_END_STATE_START, COL_PARTIAL_ROUND_STATE_START, NUM_COLS, ROUNDS_F, ROUNDS_P,
-    SBOX_DEGREE, STATE_SIZE,
+    col_2nd_fullround_state_start, col_output_start, col_partial_round_end_state_start,
+    col_partial_round_state_start, num_cols, COL_1ST_FULLROUND_STATE_START, COL_INPUT_START,
+    STATE_SIZE,
 };
-use ark_ff::{BigInteger, PrimeField};
-use num::BigUint;
+use crate::generation::{generate_outputs, generate_row_witness};
+use crate::params::{Poseidon2Params, M4};
+use permutation_air::PermutationAir;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
-use plonky2::field::polynomial::PolynomialValues;
-use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use starky::stark::Stark;
 use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 use std::marker::PhantomData;
-use zkhash::fields::goldilocks::FpGoldiLocks;
-use zkhash::poseidon2::poseidon2_instance_goldilocks::{MAT_DIAG8_M_1, RC8};
-
-// used in the linear layer
-const M4: [[usize; 4]; 4] = [
-    [5, 7, 1, 3], //
-    [4, 6, 1, 1], //
-    [1, 3, 5, 7], //
-    [1, 1, 4, 6],
-];
-
-fn scalar_to_fe<F: RichField + Extendable<D>, const D: usize, FE, const D2: usize, PF: PrimeField>(
-    scalar: PF,
-) -> FE
-where
-    FE: FieldExtension<D2, BaseField = F>,
-{
-    FE::from_canonical_u64(
-        F::from_noncanonical_biguint(BigUint::from_bytes_le(&scalar.into_bigint().to_bytes_le()))
-            .to_canonical_u64(),
-    )
-}
 
 // linear layer (degree = 1)
-fn matmul_external8_constraints<
-    F: RichField + Extendable<D>,
-    const D: usize,
-    FE,
-    P,
-    const D2: usize,
->(
-    state: &[P; 8],
-) -> [P; 8]
+fn matmul_external8_constraints<F: RichField, FE, P, const D2: usize>(state: &[P; 8]) -> [P; 8]
 where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
@@ -77,14 +47,9 @@ where
 }
 
 // degree: 1
-fn matmul_internal8_constraints<
-    F: RichField + Extendable<D>,
-    const D: usize,
-    FE,
-    P,
-    const D2: usize,
->(
+fn matmul_internal8_constraints<F: RichField, FE, P, const D2: usize>(
     state: &[P; 8],
+    internal_diag_m_1: &[u64; STATE_SIZE],
 ) -> [P; 8]
 where
     FE: FieldExtension<D2, BaseField = F>,
@@ -99,7 +64,7 @@ where
     }
 
     for i in 0..STATE_SIZE {
-        out[i] = state[i] * scalar_to_fe::<F, D, FE, D2, FpGoldiLocks>(MAT_DIAG8_M_1[i]);
+        out[i] = state[i] * FE::from_canonical_u64(internal_diag_m_1[i]);
         out[i] += sum;
     }
 
@@ -107,9 +72,9 @@ where
 }
 
 // degree: 1
-fn add_rc_constraints<F: RichField + Extendable<D>, const D: usize, FE, P, const D2: usize>(
+fn add_rc_constraints<F: RichField, FE, P, const D2: usize>(
     state: &[P; 8],
-    r: usize,
+    round_constants: &[u64; STATE_SIZE],
 ) -> [P; 8]
 where
     FE: FieldExtension<D2, BaseField = F>,
@@ -119,16 +84,14 @@ where
     let mut out = [P::ZEROS; 8];
 
     for i in 0..8 {
-        out[i] = state[i] + scalar_to_fe::<F, D, FE, D2, FpGoldiLocks>(RC8[r][i]);
+        out[i] = state[i] + FE::from_canonical_u64(round_constants[i]);
     }
 
     out
 }
 
-// degree: SBOX_DEGREE (7)
-fn sbox_p_constraints<F: RichField + Extendable<D>, const D: usize, FE, P, const D2: usize>(
-    state: &P,
-) -> P
+// degree: sbox_degree (7 for the presets)
+fn sbox_p_constraints<F: RichField, FE, P, const D2: usize>(state: &P, sbox_degree: usize) -> P
 where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
@@ -136,21 +99,247 @@ where
     assert_eq!(STATE_SIZE, 8);
     let mut out = P::ONES;
 
-    for _ in 0..SBOX_DEGREE {
+    for _ in 0..sbox_degree {
         out = out.mul(*state);
     }
 
     out
 }
 
-#[derive(Copy, Clone, Default)]
+/// Constraints of a single Poseidon2 permutation laid out as in `columns.rs`,
+/// starting at the first column of `lv`
+pub(crate) fn eval_permutation<
+    F: RichField,
+    FE,
+    P,
+    const D2: usize,
+    const ROUNDS_F: usize,
+    const ROUNDS_P: usize,
+>(
+    params: &Poseidon2Params<ROUNDS_F, ROUNDS_P>,
+    lv: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let col_partial_round_state_start = col_partial_round_state_start(ROUNDS_F);
+    let col_partial_round_end_state_start = col_partial_round_end_state_start(ROUNDS_F, ROUNDS_P);
+    let col_2nd_fullround_state_start = col_2nd_fullround_state_start(ROUNDS_F, ROUNDS_P);
+    let round_constants = params.round_constants();
+    let sbox_degree = params.sbox_degree();
+
+    let mut state = matmul_external8_constraints(lv[0..STATE_SIZE].try_into().unwrap());
+
+    // first full rounds
+    for r in 0..ROUNDS_F {
+        state = add_rc_constraints(&state, &round_constants[r]);
+        #[allow(clippy::needless_range_loop)]
+        for i in 0..STATE_SIZE {
+            state[i] = sbox_p_constraints(&state[i], sbox_degree);
+        }
+        state = matmul_external8_constraints(&state);
+        for i in 0..STATE_SIZE {
+            yield_constr
+                .constraint(state[i] - lv[COL_1ST_FULLROUND_STATE_START + r * STATE_SIZE + i]);
+            state[i] = lv[COL_1ST_FULLROUND_STATE_START + r * STATE_SIZE + i];
+        }
+    }
+
+    // partial rounds
+    for i in 0..ROUNDS_P {
+        let r = ROUNDS_F + i;
+        state[0] += FE::from_canonical_u64(round_constants[r][0]);
+        state[0] = sbox_p_constraints(&state[0], sbox_degree);
+        state = matmul_internal8_constraints(&state, params.internal_diag_m_1());
+        yield_constr.constraint(state[0] - lv[col_partial_round_state_start + i]);
+        state[0] = lv[col_partial_round_state_start + i];
+    }
+
+    // the state before last full rounds
+    for i in 0..STATE_SIZE {
+        yield_constr.constraint(state[i] - lv[col_partial_round_end_state_start + i]);
+        state[i] = lv[col_partial_round_end_state_start + i];
+    }
+
+    // last full rounds
+    for i in 0..ROUNDS_F {
+        let r = ROUNDS_F + ROUNDS_P + i;
+        state = add_rc_constraints(&state, &round_constants[r]);
+        #[allow(clippy::needless_range_loop)]
+        for j in 0..STATE_SIZE {
+            state[j] = sbox_p_constraints(&state[j], sbox_degree);
+        }
+        state = matmul_external8_constraints(&state);
+        for j in 0..STATE_SIZE {
+            yield_constr
+                .constraint(state[j] - lv[col_2nd_fullround_state_start + i * STATE_SIZE + j]);
+            state[j] = lv[col_2nd_fullround_state_start + i * STATE_SIZE + j];
+        }
+    }
+}
+
+// linear layer (degree = 1), in-circuit version of
+// `matmul_external8_constraints`
+fn matmul_external8_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &[ExtensionTarget<D>; STATE_SIZE],
+) -> [ExtensionTarget<D>; STATE_SIZE] {
+    let mut out = [builder.zero_extension(); STATE_SIZE];
+
+    for i in 0..8 {
+        for j in 0..4 {
+            let (x, m) = if i < 4 {
+                (state[j], M4[i][j])
+            } else {
+                (state[j + 4], M4[i - 4][j])
+            };
+            out[i] = builder.mul_const_add_extension(F::from_canonical_usize(m), x, out[i]);
+        }
+    }
+
+    let stored: [_; 4] = std::array::from_fn(|i| builder.add_extension(out[i], out[4 + i]));
+    for i in 0..8 {
+        out[i] = builder.add_extension(out[i], stored[i % 4]);
+    }
+
+    out
+}
+
+// degree: 1
+fn matmul_internal8_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &[ExtensionTarget<D>; STATE_SIZE],
+    internal_diag_m_1: &[u64; STATE_SIZE],
+) -> [ExtensionTarget<D>; STATE_SIZE] {
+    let sum = builder.add_many_extension(state);
+    std::array::from_fn(|i| {
+        builder.mul_const_add_extension(F::from_canonical_u64(internal_diag_m_1[i]), state[i], sum)
+    })
+}
+
+// degree: sbox_degree
+fn sbox_p_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: ExtensionTarget<D>,
+    sbox_degree: usize,
+) -> ExtensionTarget<D> {
+    builder.exp_u64_extension(state, sbox_degree as u64)
+}
+
+/// Recursive version of [`eval_permutation`], used to verify a
+/// [`Poseidon2Stark`] proof inside a plonky2 circuit
+pub(crate) fn eval_permutation_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    const ROUNDS_F: usize,
+    const ROUNDS_P: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    params: &Poseidon2Params<ROUNDS_F, ROUNDS_P>,
+    lv: &[ExtensionTarget<D>],
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+) {
+    let col_partial_round_state_start = col_partial_round_state_start(ROUNDS_F);
+    let col_partial_round_end_state_start = col_partial_round_end_state_start(ROUNDS_F, ROUNDS_P);
+    let col_2nd_fullround_state_start = col_2nd_fullround_state_start(ROUNDS_F, ROUNDS_P);
+    let round_constants = params.round_constants();
+    let sbox_degree = params.sbox_degree();
+
+    let mut state = matmul_external8_circuit(builder, lv[0..STATE_SIZE].try_into().unwrap());
+
+    // first full rounds
+    for r in 0..ROUNDS_F {
+        for (x, rc) in state.iter_mut().zip(round_constants[r]) {
+            let y = builder.add_const_extension(*x, F::from_canonical_u64(rc));
+            *x = sbox_p_circuit(builder, y, sbox_degree);
+        }
+        state = matmul_external8_circuit(builder, &state);
+        for i in 0..STATE_SIZE {
+            let next = lv[COL_1ST_FULLROUND_STATE_START + r * STATE_SIZE + i];
+            let diff = builder.sub_extension(state[i], next);
+            yield_constr.constraint(builder, diff);
+            state[i] = next;
+        }
+    }
+
+    // partial rounds
+    for i in 0..ROUNDS_P {
+        let r = ROUNDS_F + i;
+        let x = builder.add_const_extension(state[0], F::from_canonical_u64(round_constants[r][0]));
+        state[0] = sbox_p_circuit(builder, x, sbox_degree);
+        state = matmul_internal8_circuit(builder, &state, params.internal_diag_m_1());
+        let next = lv[col_partial_round_state_start + i];
+        let diff = builder.sub_extension(state[0], next);
+        yield_constr.constraint(builder, diff);
+        state[0] = next;
+    }
+
+    // the state before last full rounds
+    for i in 0..STATE_SIZE {
+        let next = lv[col_partial_round_end_state_start + i];
+        let diff = builder.sub_extension(state[i], next);
+        yield_constr.constraint(builder, diff);
+        state[i] = next;
+    }
+
+    // last full rounds
+    for i in 0..ROUNDS_F {
+        let r = ROUNDS_F + ROUNDS_P + i;
+        for (x, rc) in state.iter_mut().zip(round_constants[r]) {
+            let y = builder.add_const_extension(*x, F::from_canonical_u64(rc));
+            *x = sbox_p_circuit(builder, y, sbox_degree);
+        }
+        state = matmul_external8_circuit(builder, &state);
+        for j in 0..STATE_SIZE {
+            let next = lv[col_2nd_fullround_state_start + i * STATE_SIZE + j];
+            let diff = builder.sub_extension(state[j], next);
+            yield_constr.constraint(builder, diff);
+            state[j] = next;
+        }
+    }
+}
+
+/// The Poseidon2 permutation table. The column layout and constraints follow
+/// `params`; the round counts default to the 128-bit preset.
+#[derive(Clone)]
 #[allow(clippy::module_name_repetitions)]
-pub struct Poseidon2Stark<F, const D: usize> {
+pub struct Poseidon2Stark<
+    F,
+    const D: usize,
+    const ROUNDS_F: usize = { crate::columns::ROUNDS_F },
+    const ROUNDS_P: usize = { crate::columns::ROUNDS_P },
+> {
+    pub params: Poseidon2Params<ROUNDS_F, ROUNDS_P>,
     pub _f: PhantomData<F>,
 }
 
-impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Poseidon2Stark<F, D> {
-    const COLUMNS: usize = NUM_COLS;
+impl<F, const D: usize, const ROUNDS_F: usize, const ROUNDS_P: usize>
+    Poseidon2Stark<F, D, ROUNDS_F, ROUNDS_P>
+{
+    #[must_use]
+    pub fn new(params: Poseidon2Params<ROUNDS_F, ROUNDS_P>) -> Self {
+        Self {
+            params,
+            _f: PhantomData,
+        }
+    }
+}
+
+impl<F, const D: usize> Default for Poseidon2Stark<F, D> {
+    fn default() -> Self {
+        Self::new(Poseidon2Params::default())
+    }
+}
+
+impl<
+        F: RichField + Extendable<D>,
+        const D: usize,
+        const ROUNDS_F: usize,
+        const ROUNDS_P: usize,
+    > Stark<F, D> for Poseidon2Stark<F, D, ROUNDS_F, ROUNDS_P>
+{
+    const COLUMNS: usize = num_cols(ROUNDS_F, ROUNDS_P);
     const PUBLIC_INPUTS: usize = 0;
 
     fn eval_packed_generic<FE, P, const D2: usize>(
@@ -161,90 +350,58 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Poseidon2Star
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>,
     {
-        let lv = vars.local_values;
-        let mut state = matmul_external8_constraints(lv[0..STATE_SIZE].try_into().unwrap());
-
-        // first full rounds
-        for r in 0..ROUNDS_F {
-            state = add_rc_constraints(&state, r);
-            #[allow(clippy::needless_range_loop)]
-            for i in 0..STATE_SIZE {
-                state[i] = sbox_p_constraints(&state[i]);
-            }
-            state = matmul_external8_constraints(&state);
-            for i in 0..STATE_SIZE {
-                yield_constr
-                    .constraint(state[i] - lv[COL_1ST_FULLROUND_STATE_START + r * STATE_SIZE + i]);
-                state[i] = lv[COL_1ST_FULLROUND_STATE_START + r * STATE_SIZE + i];
-            }
-        }
-
-        // partial rounds
-        for i in 0..ROUNDS_P {
-            let r = ROUNDS_F + i;
-            state[0] += scalar_to_fe::<F, D, FE, D2, FpGoldiLocks>(RC8[r][0]);
-            state[0] = sbox_p_constraints(&state[0]);
-            state = matmul_internal8_constraints(&state);
-            yield_constr.constraint(state[0] - lv[COL_PARTIAL_ROUND_STATE_START + i]);
-            state[0] = lv[COL_PARTIAL_ROUND_STATE_START + i];
-        }
-
-        // the state before last full rounds
-        for i in 0..STATE_SIZE {
-            yield_constr.constraint(state[i] - lv[COL_PARTIAL_ROUND_END_STATE_START + i]);
-            state[i] = lv[COL_PARTIAL_ROUND_END_STATE_START + i];
-        }
-
-        // last full rounds
-        for i in 0..ROUNDS_F {
-            let r = ROUNDS_F + ROUNDS_P + i;
-            state = add_rc_constraints(&state, r);
-            #[allow(clippy::needless_range_loop)]
-            for j in 0..STATE_SIZE {
-                state[j] = sbox_p_constraints(&state[j]);
-            }
-            state = matmul_external8_constraints(&state);
-            for j in 0..STATE_SIZE {
-                yield_constr
-                    .constraint(state[j] - lv[COL_2ND_FULLROUND_STATE_START + i * STATE_SIZE + j]);
-                state[j] = lv[COL_2ND_FULLROUND_STATE_START + i * STATE_SIZE + j];
-            }
-        }
+        self.eval(vars.local_values, yield_constr);
     }
 
     fn constraint_degree(&self) -> usize {
-        7
+        self.params.sbox_degree()
     }
 
     fn eval_ext_circuit(
         &self,
-        _builder: &mut CircuitBuilder<F, D>,
-        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
-        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     ) {
-        unimplemented!()
+        eval_permutation_circuit(builder, &self.params, vars.local_values, yield_constr);
     }
 }
 
-pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
-    trace: [Vec<F>; COLUMNS],
-) -> Vec<PolynomialValues<F>> {
-    trace.into_iter().map(PolynomialValues::new).collect()
+impl<F: RichField, const D: usize, const ROUNDS_F: usize, const ROUNDS_P: usize>
+    PermutationAir<F, STATE_SIZE> for Poseidon2Stark<F, D, ROUNDS_F, ROUNDS_P>
+{
+    const NUM_COLS: usize = num_cols(ROUNDS_F, ROUNDS_P);
+    const INPUT_START: usize = COL_INPUT_START;
+    const OUTPUT_START: usize = col_output_start(ROUNDS_F, ROUNDS_P);
+
+    fn permute(&self, input: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+        generate_outputs(&self.params, input)
+    }
+
+    fn generate_row_witness(&self, input: &[F; STATE_SIZE]) -> Vec<F> {
+        generate_row_witness(&self.params, input)
+    }
+
+    fn eval<FE, P, const D2: usize>(&self, lv: &[P], yield_constr: &mut ConstraintConsumer<P>)
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_permutation(&self.params, lv, yield_constr);
+    }
 }
 
+pub use permutation_air::generation::trace_to_poly_values;
+
 #[cfg(test)]
 mod tests {
     use crate::columns::STATE_SIZE;
-    use crate::generation::{generate_poseidon2_trace, Row};
-    use crate::stark::{trace_to_poly_values, Poseidon2Stark};
+    use crate::params::{Poseidon2Params, ROUNDS_F_REDUCED, ROUNDS_P_REDUCED};
+    use crate::stark::Poseidon2Stark;
     use anyhow::Result;
-    use plonky2::field::types::Sample;
+    use permutation_air::conformance::test_permutation_air;
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
-    use plonky2::util::timing::TimingTree;
-    use starky::config::StarkConfig;
-    use starky::prover::prove;
-    use starky::stark_testing::test_stark_low_degree;
-    use starky::verifier::verify_stark_proof;
+    use starky::stark_testing::test_stark_circuit_constraints;
 
     const D: usize = 2;
     type C = PoseidonGoldilocksConfig;
@@ -252,37 +409,18 @@ mod tests {
     type S = Poseidon2Stark<F, D>;
 
     #[test]
-    fn poseidon2_constraints() -> Result<()> {
-        let mut config = StarkConfig::standard_fast_config();
-        config.fri_config.cap_height = 0;
-        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
-
-        let num_rows = 12;
-        let mut step_rows = Vec::with_capacity(num_rows);
-        for _ in 0..num_rows {
-            let preimage = (0..STATE_SIZE).map(|_| F::rand()).collect::<Vec<_>>();
-            step_rows.push(Row {
-                preimage: preimage.try_into().unwrap(),
-            });
-        }
+    fn poseidon2_conformance() -> Result<()> {
+        test_permutation_air::<F, C, S, D, STATE_SIZE>(&S::default(), 12)
+    }
 
-        let stark = S::default();
-        let trace = generate_poseidon2_trace(&step_rows);
-        let trace_poly_values = trace_to_poly_values(trace);
-
-        let proof = prove::<F, C, S, D>(
-            stark,
-            &config,
-            trace_poly_values,
-            [],
-            &mut TimingTree::default(),
-        )?;
-        verify_stark_proof(stark, proof, &config)
+    #[test]
+    fn reduced_poseidon2_conformance() -> Result<()> {
+        type S = Poseidon2Stark<F, D, ROUNDS_F_REDUCED, ROUNDS_P_REDUCED>;
+        test_permutation_air::<F, C, S, D, STATE_SIZE>(&S::new(Poseidon2Params::reduced()), 12)
     }
 
     #[test]
-    fn poseidon2_stark_degree() -> Result<()> {
-        let stark = S::default();
-        test_stark_low_degree(stark)
+    fn poseidon2_circuit_constraints() -> Result<()> {
+        test_stark_circuit_constraints::<F, C, S, D>(S::default())
     }
 }
@@ -0,0 +1,307 @@
+//! Export and import of Poseidon2 traces for offline inspection.
+//!
+//! Traces are written either as CSV with one named column per trace column
+//! and canonical decimal values, or in a compact binary format:
+//! the magic `P2TR`, the number of columns and rows as little-endian `u32`s,
+//! followed by the canonical values as little-endian `u64`s, row by row.
+//! Imports only accept traces of the 128-bit preset layout with a power-of-two
+//! number of rows, as returned by `generate_poseidon2_trace`.
+
+use crate::columns::{
+    col_2nd_fullround_state_start, col_output_start, col_partial_round_end_state_start,
+    col_partial_round_state_start, num_cols, COL_1ST_FULLROUND_STATE_START, COL_INPUT_START,
+    NUM_COLS, ROUNDS_F, ROUNDS_P, STATE_SIZE,
+};
+use anyhow::{bail, ensure, Context, Result};
+use plonky2::hash::hash_types::RichField;
+use std::io::{BufRead, Read, Write};
+
+const BINARY_MAGIC: &[u8; 4] = b"P2TR";
+
+/// The names of the trace columns of a layout with `rounds_f` full rounds in
+/// each half and `rounds_p` partial rounds, e.g. `full1_r2_s5` for element 5
+/// of the state after the 3rd first full round, `partial_s0_7` for state[0]
+/// after the 8th partial round and `output_3`.
+#[must_use]
+pub fn column_names(rounds_f: usize, rounds_p: usize) -> Vec<String> {
+    let mut names = vec![String::new(); num_cols(rounds_f, rounds_p)];
+    let mut name = |col: usize, value: String| names[col] = value;
+
+    for s in 0..STATE_SIZE {
+        name(COL_INPUT_START + s, format!("input_{s}"));
+        for r in 0..rounds_f {
+            name(
+                COL_1ST_FULLROUND_STATE_START + r * STATE_SIZE + s,
+                format!("full1_r{r}_s{s}"),
+            );
+        }
+        name(
+            col_partial_round_end_state_start(rounds_f, rounds_p) + s,
+            format!("partial_end_s{s}"),
+        );
+        for r in 0..rounds_f - 1 {
+            name(
+                col_2nd_fullround_state_start(rounds_f, rounds_p) + r * STATE_SIZE + s,
+                format!("full2_r{r}_s{s}"),
+            );
+        }
+        name(
+            col_output_start(rounds_f, rounds_p) + s,
+            format!("output_{s}"),
+        );
+    }
+    // the state[0] after the last partial round is `partial_end_s0`
+    for r in 0..rounds_p - 1 {
+        name(
+            col_partial_round_state_start(rounds_f) + r,
+            format!("partial_s0_{r}"),
+        );
+    }
+
+    names
+}
+
+fn num_rows<F>(trace: &[Vec<F>]) -> usize {
+    trace.first().map_or(0, Vec::len)
+}
+
+fn to_field<F: RichField>(value: u64) -> Result<F> {
+    ensure!(value < F::ORDER, "value {value} is not canonical");
+    Ok(F::from_canonical_u64(value))
+}
+
+fn check_num_rows(num_rows: usize) -> Result<()> {
+    ensure!(
+        num_rows.is_power_of_two(),
+        "trace length {num_rows} is not a power of two"
+    );
+    Ok(())
+}
+
+/// Write `trace` as CSV with a header of [`column_names`].
+///
+/// # Errors
+/// Returns an error if writing fails.
+pub fn write_csv<F: RichField, W: Write>(trace: &[Vec<F>; NUM_COLS], mut writer: W) -> Result<()> {
+    writeln!(writer, "{}", column_names(ROUNDS_F, ROUNDS_P).join(","))?;
+    for row in 0..num_rows(trace) {
+        let values: Vec<String> = trace
+            .iter()
+            .map(|col| col[row].to_canonical_u64().to_string())
+            .collect();
+        writeln!(writer, "{}", values.join(","))?;
+    }
+    Ok(())
+}
+
+/// Read a trace written by [`write_csv`].
+///
+/// # Errors
+/// Returns an error if the header does not match the column layout, a row
+/// has the wrong number of columns, a value is not a canonical field element
+/// or the number of rows is not a power of two.
+pub fn read_csv<F: RichField, R: BufRead>(reader: R) -> Result<[Vec<F>; NUM_COLS]> {
+    let mut lines = reader.lines();
+    let header_line = lines.next().context("missing CSV header")??;
+    let names: Vec<&str> = header_line.trim_end().split(',').collect();
+    ensure!(
+        names.len() == NUM_COLS,
+        "expected {NUM_COLS} columns but the header has {}",
+        names.len()
+    );
+    for (col, (name, expected)) in names
+        .iter()
+        .zip(column_names(ROUNDS_F, ROUNDS_P))
+        .enumerate()
+    {
+        ensure!(
+            *name == expected,
+            "column {col} is named {name}, expected {expected}"
+        );
+    }
+
+    let mut trace: Vec<Vec<F>> = vec![Vec::new(); NUM_COLS];
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let values: Vec<&str> = line.trim_end().split(',').collect();
+        ensure!(
+            values.len() == NUM_COLS,
+            "row {i} has {} columns, expected {NUM_COLS}",
+            values.len()
+        );
+        for (col, value) in trace.iter_mut().zip(values) {
+            let value = value
+                .parse()
+                .with_context(|| format!("invalid value {value} in row {i}"))?;
+            col.push(to_field(value)?);
+        }
+    }
+    check_num_rows(num_rows(&trace))?;
+
+    Ok(trace.try_into().unwrap())
+}
+
+/// Write `trace` in the binary format.
+///
+/// # Errors
+/// Returns an error if writing fails or the trace has more than `u32::MAX`
+/// rows.
+pub fn write_binary<F: RichField, W: Write>(
+    trace: &[Vec<F>; NUM_COLS],
+    mut writer: W,
+) -> Result<()> {
+    let num_rows = num_rows(trace);
+    writer.write_all(BINARY_MAGIC)?;
+    writer.write_all(&u32::try_from(NUM_COLS)?.to_le_bytes())?;
+    writer.write_all(&u32::try_from(num_rows)?.to_le_bytes())?;
+    for row in 0..num_rows {
+        for col in trace {
+            writer.write_all(&col[row].to_canonical_u64().to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Read a trace written by [`write_binary`].
+///
+/// # Errors
+/// Returns an error if the magic or the number of columns does not match,
+/// the data is truncated or has trailing bytes, a value is not a canonical
+/// field element or the number of rows is not a power of two.
+pub fn read_binary<F: RichField, R: Read>(mut reader: R) -> Result<[Vec<F>; NUM_COLS]> {
+    let mut magic = [0u8; 4];
+    reader
+        .read_exact(&mut magic)
+        .context("missing binary trace header")?;
+    ensure!(&magic == BINARY_MAGIC, "not a binary Poseidon2 trace");
+
+    let mut word = [0u8; 4];
+    reader.read_exact(&mut word)?;
+    let num_cols = u32::from_le_bytes(word) as usize;
+    ensure!(
+        num_cols == NUM_COLS,
+        "expected {NUM_COLS} columns but the trace has {num_cols}"
+    );
+    reader.read_exact(&mut word)?;
+    let num_rows = u32::from_le_bytes(word) as usize;
+    check_num_rows(num_rows)?;
+
+    // the header is untrusted, so columns grow with the data actually read
+    let mut trace: Vec<Vec<F>> = vec![Vec::new(); NUM_COLS];
+    let mut value = [0u8; 8];
+    for row in 0..num_rows {
+        for col in &mut trace {
+            reader
+                .read_exact(&mut value)
+                .with_context(|| format!("trace is truncated at row {row}"))?;
+            col.push(to_field(u64::from_le_bytes(value))?);
+        }
+    }
+    if reader.read(&mut value)? != 0 {
+        bail!("trailing bytes after {num_rows} rows");
+    }
+
+    Ok(trace.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::{NUM_COLS, ROUNDS_F, ROUNDS_P, STATE_SIZE};
+    use crate::generation::{generate_poseidon2_trace, Row};
+    use crate::trace_io::{column_names, read_binary, read_csv, write_binary, write_csv};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field64, Sample};
+
+    type F = GoldilocksField;
+
+    fn trace() -> [Vec<F>; NUM_COLS] {
+        let rows = (0..5)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+        generate_poseidon2_trace(&rows)
+    }
+
+    #[test]
+    fn column_names_follow_layout() {
+        let names = column_names(ROUNDS_F, ROUNDS_P);
+        assert_eq!(names.len(), NUM_COLS);
+        assert_eq!(names[0], "input_0");
+        assert_eq!(names[8 + 2 * STATE_SIZE + 5], "full1_r2_s5");
+        assert_eq!(names[8 + ROUNDS_F * STATE_SIZE + 7], "partial_s0_7");
+        assert_eq!(names[NUM_COLS - STATE_SIZE + 3], "output_3");
+        assert!(names.iter().all(|name| !name.is_empty()));
+    }
+
+    #[test]
+    fn csv_roundtrip() {
+        let trace = trace();
+        let mut csv = Vec::new();
+        write_csv(&trace, &mut csv).unwrap();
+        assert_eq!(read_csv::<F, _>(csv.as_slice()).unwrap(), trace);
+    }
+
+    #[test]
+    fn binary_roundtrip() {
+        let trace = trace();
+        let mut bin = Vec::new();
+        write_binary(&trace, &mut bin).unwrap();
+        assert_eq!(bin.len(), 12 + NUM_COLS * 8 * 8);
+        assert_eq!(read_binary::<F, _>(bin.as_slice()).unwrap(), trace);
+    }
+
+    #[test]
+    fn csv_import_is_validated() {
+        let mut csv = Vec::new();
+        write_csv(&trace(), &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        // not a power of two
+        assert!(read_csv::<F, _>(lines[..8].join("\n").as_bytes()).is_err());
+        // missing column
+        let (header, _) = lines[0].rsplit_once(',').unwrap();
+        let truncated = [header].into_iter().chain(lines[1..].iter().copied());
+        assert!(read_csv::<F, _>(truncated.collect::<Vec<_>>().join("\n").as_bytes()).is_err());
+        // non-canonical value
+        let replaced = csv.replacen(
+            &format!("\n{}", lines[1].split(',').next().unwrap()),
+            &format!("\n{}", F::ORDER),
+            1,
+        );
+        assert!(read_csv::<F, _>(replaced.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn binary_import_is_validated() {
+        let mut bin = Vec::new();
+        write_binary(&trace(), &mut bin).unwrap();
+
+        // wrong number of columns
+        let mut wrong_cols = bin.clone();
+        wrong_cols[4] -= 1;
+        assert!(read_binary::<F, _>(wrong_cols.as_slice()).is_err());
+        // not a power of two
+        let mut wrong_rows = bin.clone();
+        wrong_rows[8] = 7;
+        assert!(read_binary::<F, _>(&wrong_rows[..wrong_rows.len() - NUM_COLS * 8]).is_err());
+        // truncated and trailing data
+        assert!(read_binary::<F, _>(&bin[..bin.len() - 1]).is_err());
+        let mut trailing = bin.clone();
+        trailing.push(0);
+        assert!(read_binary::<F, _>(trailing.as_slice()).is_err());
+        // non-canonical value
+        bin[12..20].copy_from_slice(&F::ORDER.to_le_bytes());
+        assert!(read_binary::<F, _>(bin.as_slice()).is_err());
+    }
+
+    #[test]
+    fn binary_header_is_not_trusted() {
+        // a header claiming 2^31 rows with no data is truncated, not allocated
+        let mut bin = b"P2TR".to_vec();
+        bin.extend_from_slice(&u32::try_from(NUM_COLS).unwrap().to_le_bytes());
+        bin.extend_from_slice(&(1u32 << 31).to_le_bytes());
+        assert!(read_binary::<F, _>(bin.as_slice()).is_err());
+    }
+}
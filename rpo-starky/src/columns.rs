@@ -0,0 +1,24 @@
+use crate::constants::{NUM_ROUNDS, STATE_SIZE};
+
+/// The starting point of the column input
+pub(crate) const COL_INPUT_START: usize = 0;
+
+/// The starting point of the state after the forward S-box of round `r`
+#[must_use]
+pub const fn col_sbox_state_start(r: usize) -> usize {
+    COL_INPUT_START + STATE_SIZE + 2 * STATE_SIZE * r
+}
+
+/// The starting point of the state after the inverse S-box of round `r`,
+/// which is the state at the end of the round
+#[must_use]
+pub const fn col_inv_sbox_state_start(r: usize) -> usize {
+    col_sbox_state_start(r) + STATE_SIZE
+}
+
+/// The starting point of the column output
+/// This is the same as the state at the end of the last round
+pub(crate) const COL_OUTPUT_START: usize = col_inv_sbox_state_start(NUM_ROUNDS - 1); // 168
+
+/// The total number of columns
+pub const NUM_COLS: usize = COL_OUTPUT_START + STATE_SIZE; // 180
@@ -0,0 +1,229 @@
+use std::ops::Range;
+
+/// The size of the state: a capacity of 4 and a rate of 8 elements
+pub const STATE_SIZE: usize = 12;
+
+/// The capacity is in elements 0..4 and the rate in elements 4..12
+pub const RATE_RANGE: Range<usize> = 4..12;
+
+/// The number of elements of a digest
+pub const DIGEST_SIZE: usize = 4;
+
+/// The digest is the first half of the rate
+pub const DIGEST_RANGE: Range<usize> = 4..4 + DIGEST_SIZE;
+
+/// The number of rounds; each round is a forward and an inverse S-box step
+pub const NUM_ROUNDS: usize = 7;
+
+/// The S-box degree
+pub const ALPHA: u64 = 7;
+
+/// The inverse S-box exponent, `1 / 7 mod (p - 1)`
+pub const INV_ALPHA: u64 = 10_540_996_611_094_048_183;
+
+/// The first row of the circulant MDS matrix
+pub const MDS: [u64; STATE_SIZE] = [7, 23, 8, 26, 13, 10, 9, 7, 6, 22, 21, 8];
+
+/// The round constants added before the forward S-box
+pub const ARK1: [[u64; STATE_SIZE]; NUM_ROUNDS] = [
+    [
+        5_789_762_306_288_267_392,
+        6_522_564_764_413_701_783,
+        17_809_893_479_458_208_203,
+        107_145_243_989_736_508,
+        6_388_978_042_437_517_382,
+        15_844_067_734_406_016_715,
+        9_975_000_513_555_218_239,
+        3_344_984_123_768_313_364,
+        9_959_189_626_657_347_191,
+        12_960_773_468_763_563_665,
+        9_602_914_297_752_488_475,
+        16_657_542_370_200_465_908,
+    ],
+    [
+        12_987_190_162_843_096_997,
+        653_957_632_802_705_281,
+        4_441_654_670_647_621_225,
+        4_038_207_883_745_915_761,
+        5_613_464_648_874_830_118,
+        13_222_989_726_778_338_773,
+        3_037_761_201_230_264_149,
+        16_683_759_727_265_180_203,
+        8_337_364_536_491_240_715,
+        3_227_397_518_293_416_448,
+        8_110_510_111_539_674_682,
+        2_872_078_294_163_232_137,
+    ],
+    [
+        18_072_785_500_942_327_487,
+        6_200_974_112_677_013_481,
+        17_682_092_219_085_884_187,
+        10_599_526_828_986_756_440,
+        975_003_873_302_957_338,
+        8_264_241_093_196_931_281,
+        10_065_763_900_435_475_170,
+        2_181_131_744_534_710_197,
+        6_317_303_992_309_418_647,
+        1_401_440_938_888_741_532,
+        8_884_468_225_181_997_494,
+        13_066_900_325_715_521_532,
+    ],
+    [
+        5_674_685_213_610_121_970,
+        5_759_084_860_419_474_071,
+        13_943_282_657_648_897_737,
+        1_352_748_651_966_375_394,
+        17_110_913_224_029_905_221,
+        1_003_883_795_902_368_422,
+        4_141_870_621_881_018_291,
+        8_121_410_972_417_424_656,
+        14_300_518_605_864_919_529,
+        13_712_227_150_607_670_181,
+        17_021_852_944_633_065_291,
+        6_252_096_473_787_587_650,
+    ],
+    [
+        4_887_609_836_208_846_458,
+        3_027_115_137_917_284_492,
+        9_595_098_600_469_470_675,
+        10_528_569_829_048_484_079,
+        7_864_689_113_198_939_815,
+        17_533_723_827_845_969_040,
+        5_781_638_039_037_710_951,
+        17_024_078_752_430_719_006,
+        109_659_393_484_013_511,
+        7_158_933_660_534_805_869,
+        2_955_076_958_026_921_730,
+        7_433_723_648_458_773_977,
+    ],
+    [
+        16_308_865_189_192_447_297,
+        11_977_192_855_656_444_890,
+        12_532_242_556_065_780_287,
+        14_594_890_931_430_968_898,
+        7_291_784_239_689_209_784,
+        5_514_718_540_551_361_949,
+        10_025_733_853_830_934_803,
+        7_293_794_580_341_021_693,
+        6_728_552_937_464_861_756,
+        6_332_385_040_983_343_262,
+        13_277_683_694_236_792_804,
+        2_600_778_905_124_452_676,
+    ],
+    [
+        7_123_075_680_859_040_534,
+        1_034_205_548_717_903_090,
+        7_717_824_418_247_931_797,
+        3_019_070_937_878_604_058,
+        11_403_792_746_066_867_460,
+        10_280_580_802_233_112_374,
+        337_153_209_462_421_218,
+        13_333_398_568_519_923_717,
+        3_596_153_696_935_337_464,
+        8_104_208_463_525_993_784,
+        14_345_062_289_456_085_693,
+        17_036_731_477_169_661_256,
+    ],
+];
+
+/// The round constants added before the inverse S-box
+pub const ARK2: [[u64; STATE_SIZE]; NUM_ROUNDS] = [
+    [
+        6_077_062_762_357_204_287,
+        15_277_620_170_502_011_191,
+        5_358_738_125_714_196_705,
+        14_233_283_787_297_595_718,
+        13_792_579_614_346_651_365,
+        11_614_812_331_536_767_105,
+        14_871_063_686_742_261_166,
+        10_148_237_148_793_043_499,
+        4_457_428_952_329_675_767,
+        15_590_786_458_219_172_475,
+        10_063_319_113_072_092_615,
+        14_200_078_843_431_360_086,
+    ],
+    [
+        6_202_948_458_916_099_932,
+        17_690_140_365_333_231_091,
+        3_595_001_575_307_484_651,
+        373_995_945_117_666_487,
+        1_235_734_395_091_296_013,
+        14_172_757_457_833_931_602,
+        707_573_103_686_350_224,
+        15_453_217_512_188_187_135,
+        219_777_875_004_506_018,
+        17_876_696_346_199_469_008,
+        17_731_621_626_449_383_378,
+        2_897_136_237_748_376_248,
+    ],
+    [
+        8_023_374_565_629_191_455,
+        15_013_690_343_205_953_430,
+        4_485_500_052_507_912_973,
+        12_489_737_547_229_155_153,
+        9_500_452_585_969_030_576,
+        2_054_001_340_201_038_870,
+        12_420_704_059_284_934_186,
+        355_990_932_618_543_755,
+        9_071_225_051_243_523_860,
+        12_766_199_826_003_448_536,
+        9_045_979_173_463_556_963,
+        12_934_431_667_190_679_898,
+    ],
+    [
+        18_389_244_934_624_494_276,
+        16_731_736_864_863_925_227,
+        4_440_209_734_760_478_192,
+        17_208_448_209_698_888_938,
+        8_739_495_587_021_565_984,
+        17_000_774_922_218_161_967,
+        13_533_282_547_195_532_087,
+        525_402_848_358_706_231,
+        16_987_541_523_062_161_972,
+        5_466_806_524_462_797_102,
+        14_512_769_585_918_244_983,
+        10_973_956_031_244_051_118,
+    ],
+    [
+        6_982_293_561_042_362_913,
+        14_065_426_295_947_720_331,
+        16_451_845_770_444_974_180,
+        7_139_138_592_091_306_727,
+        9_012_006_439_959_783_127,
+        14_619_614_108_529_063_361,
+        1_394_813_199_588_124_371,
+        4_635_111_139_507_788_575,
+        16_217_473_952_264_203_365,
+        10_782_018_226_466_330_683,
+        6_844_229_992_533_662_050,
+        7_446_486_531_695_178_711,
+    ],
+    [
+        3_736_792_340_494_631_448,
+        577_852_220_195_055_341,
+        6_689_998_335_515_779_805,
+        13_886_063_479_078_013_492,
+        14_358_505_101_923_202_168,
+        7_744_142_531_772_274_164,
+        16_135_070_735_728_404_443,
+        12_290_902_521_256_031_137,
+        12_059_913_662_657_709_804,
+        16_456_018_495_793_751_911,
+        4_571_485_474_751_953_524,
+        17_200_392_109_565_783_176,
+    ],
+    [
+        17_130_398_059_294_018_733,
+        519_782_857_322_261_988,
+        9_625_384_390_925_085_478,
+        1_664_893_052_631_119_222,
+        7_629_576_092_524_553_570,
+        3_485_239_601_103_661_425,
+        9_755_891_797_164_033_838,
+        15_218_148_195_153_269_027,
+        16_460_604_813_734_957_368,
+        9_643_968_136_937_729_763,
+        3_611_348_709_641_382_851,
+        18_256_379_591_337_759_196,
+    ],
+];
@@ -0,0 +1,166 @@
+use crate::columns::{col_inv_sbox_state_start, col_sbox_state_start, COL_INPUT_START, NUM_COLS};
+use crate::constants::{
+    ALPHA, ARK1, ARK2, DIGEST_RANGE, DIGEST_SIZE, INV_ALPHA, MDS, NUM_ROUNDS, RATE_RANGE,
+    STATE_SIZE,
+};
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+
+// Represent a row of the preimage
+#[derive(Debug, Clone, Default)]
+pub struct Row<Field: RichField> {
+    pub preimage: [Field; STATE_SIZE],
+}
+
+/// Pad the trace to a power of 2.
+#[must_use]
+fn pad_trace<F: RichField>(mut trace: Vec<Vec<F>>) -> Vec<Vec<F>> {
+    let ext_trace_len = trace[0].len().next_power_of_two();
+
+    // All columns have their last value duplicated.
+    for row in &mut trace {
+        row.resize(ext_trace_len, *row.last().unwrap());
+    }
+
+    trace
+}
+
+/// Multiply the state by the circulant MDS matrix
+pub(crate) fn mds<F: Field>(state: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        (0..STATE_SIZE).fold(F::ZERO, |acc, j| {
+            acc + state[j] * F::from_canonical_u64(MDS[(j + STATE_SIZE - i) % STATE_SIZE])
+        })
+    })
+}
+
+fn add_constants<F: Field>(state: &mut [F; STATE_SIZE], constants: &[u64; STATE_SIZE]) {
+    for (s, &c) in state.iter_mut().zip(constants) {
+        *s += F::from_canonical_u64(c);
+    }
+}
+
+/// The states after the forward and the inverse S-box of each round
+fn generate_round_states<F: RichField>(
+    preimage: &[F; STATE_SIZE],
+) -> Vec<([F; STATE_SIZE], [F; STATE_SIZE])> {
+    let mut state = *preimage;
+    (0..NUM_ROUNDS)
+        .map(|r| {
+            state = mds(&state);
+            add_constants(&mut state, &ARK1[r]);
+            let sbox_state = state.map(|s| s.exp_u64(ALPHA));
+
+            state = mds(&sbox_state);
+            add_constants(&mut state, &ARK2[r]);
+            state = state.map(|s| s.exp_u64(INV_ALPHA));
+            (sbox_state, state)
+        })
+        .collect()
+}
+
+/// The RPO permutation
+#[must_use]
+pub fn permute<F: RichField>(preimage: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    generate_round_states(preimage).last().unwrap().1
+}
+
+/// The inputs of the permutations made by [`hash_elements`]: the first
+/// capacity element is 1 unless the number of elements is a multiple of the
+/// rate, and the last block is padded with a 1 and zeros.
+#[must_use]
+pub fn hash_elements_rows<F: RichField>(elements: &[F]) -> Vec<Row<F>> {
+    let mut state = [F::ZERO; STATE_SIZE];
+    if elements.len() % RATE_RANGE.len() != 0 {
+        state[0] = F::ONE;
+    }
+
+    let mut rows = Vec::new();
+    for block in elements.chunks(RATE_RANGE.len()) {
+        let rate = &mut state[RATE_RANGE];
+        rate[..block.len()].copy_from_slice(block);
+        if block.len() < rate.len() {
+            rate[block.len()] = F::ONE;
+            rate[block.len() + 1..].fill(F::ZERO);
+        }
+        rows.push(Row { preimage: state });
+        state = permute(&state);
+    }
+    rows
+}
+
+/// Hash field elements with the RPO sponge, as in Miden's `Rpo256`
+#[must_use]
+pub fn hash_elements<F: RichField>(elements: &[F]) -> [F; DIGEST_SIZE] {
+    let state = hash_elements_rows(elements)
+        .last()
+        .map_or([F::ZERO; STATE_SIZE], |row| permute(&row.preimage));
+    state[DIGEST_RANGE].try_into().unwrap()
+}
+
+/// Write the permutation columns of a single row into a column-major trace
+fn fill_permutation_row<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    preimage: &[F; STATE_SIZE],
+) {
+    for (j, &value) in preimage.iter().enumerate() {
+        trace[COL_INPUT_START + j][row] = value;
+    }
+    for (r, (sbox_state, inv_sbox_state)) in generate_round_states(preimage).iter().enumerate() {
+        for j in 0..STATE_SIZE {
+            trace[col_sbox_state_start(r) + j][row] = sbox_state[j];
+            trace[col_inv_sbox_state_start(r) + j][row] = inv_sbox_state[j];
+        }
+    }
+}
+
+/// Function to generate the RPO trace: one row per permutation, padded to a
+/// power of 2 by repeating the last row.
+#[must_use]
+pub fn generate_rpo_trace<F: RichField>(step_rows: &[Row<F>]) -> [Vec<F>; NUM_COLS] {
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; step_rows.len()]; NUM_COLS];
+    for (i, row) in step_rows.iter().enumerate() {
+        fill_permutation_row(&mut trace, i, &row.preimage);
+    }
+
+    pad_trace(trace).try_into().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::columns::COL_OUTPUT_START;
+    use crate::constants::{ALPHA, INV_ALPHA, STATE_SIZE};
+    use crate::generation::{generate_rpo_trace, permute, Row};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn inverse_sbox() {
+        let x = F::rand();
+        assert_eq!(x.exp_u64(ALPHA).exp_u64(INV_ALPHA), x);
+        assert_eq!(x.exp_u64(INV_ALPHA).exp_u64(ALPHA), x);
+    }
+
+    #[test]
+    fn generate_rpo_trace_outputs() {
+        let step_rows: Vec<Row<F>> = (0..5)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let trace = generate_rpo_trace(&step_rows);
+        assert_eq!(trace[0].len(), 8);
+        for (i, row) in step_rows.iter().enumerate() {
+            let expected = permute(&row.preimage);
+            for j in 0..STATE_SIZE {
+                assert_eq!(trace[COL_OUTPUT_START + j][i], expected[j]);
+            }
+        }
+        // the padding rows repeat the last row
+        assert_eq!(trace[COL_OUTPUT_START][7], trace[COL_OUTPUT_START][4]);
+    }
+}
@@ -0,0 +1,182 @@
+//! The published RPO test vectors of Miden's `Rpo256`: entry `i` is the
+//! digest of `hash_elements` over the elements `0, 1, ..., i`.
+
+use crate::columns::COL_OUTPUT_START;
+use crate::constants::{DIGEST_RANGE, DIGEST_SIZE};
+use crate::generation::{generate_rpo_trace, hash_elements, hash_elements_rows};
+use crate::stark::{trace_to_poly_values, RpoStark};
+use anyhow::Result;
+use plonky2::field::types::{Field, PrimeField64};
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::util::timing::TimingTree;
+use starky::config::StarkConfig;
+use starky::prover::prove;
+use starky::verifier::verify_stark_proof;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+type S = RpoStark<F, D>;
+
+const EXPECTED: [[u64; DIGEST_SIZE]; 19] = [
+    [
+        1_502_364_727_743_950_833,
+        5_880_949_717_274_681_448,
+        162_790_463_902_224_431,
+        6_901_340_476_773_664_264,
+    ],
+    [
+        7_478_710_183_745_780_580,
+        3_308_077_307_559_720_969,
+        3_383_561_985_796_182_409,
+        17_205_078_494_700_259_815,
+    ],
+    [
+        17_439_912_364_295_172_999,
+        17_979_156_346_142_712_171,
+        8_280_795_511_427_637_894,
+        9_349_844_417_834_368_814,
+    ],
+    [
+        5_105_868_198_472_766_874,
+        13_090_564_195_691_924_742,
+        1_058_904_296_915_798_891,
+        18_379_501_748_825_152_268,
+    ],
+    [
+        9_133_662_113_608_941_286,
+        12_096_627_591_905_525_991,
+        14_963_426_595_993_304_047,
+        13_290_205_840_019_973_377,
+    ],
+    [
+        3_134_262_397_541_159_485,
+        10_106_105_871_979_362_399,
+        138_768_814_855_329_459,
+        15_044_809_212_457_404_677,
+    ],
+    [
+        162_696_376_578_462_826,
+        4_991_300_494_838_863_586,
+        660_346_084_748_120_605,
+        13_179_389_528_641_752_698,
+    ],
+    [
+        2_242_391_899_857_912_644,
+        12_689_382_052_053_305_418,
+        235_236_990_017_815_546,
+        5_046_143_039_268_215_739,
+    ],
+    [
+        9_585_630_502_158_073_976,
+        1_310_051_013_427_303_477,
+        7_491_921_222_636_097_758,
+        9_417_501_558_995_216_762,
+    ],
+    [
+        1_994_394_001_720_334_744,
+        10_866_209_900_885_216_467,
+        13_836_092_831_163_031_683,
+        10_814_636_682_252_756_697,
+    ],
+    [
+        17_486_854_790_732_826_405,
+        17_376_549_265_955_727_562,
+        2_371_059_831_956_435_003,
+        17_585_704_935_858_006_533,
+    ],
+    [
+        11_368_277_489_137_713_825,
+        3_906_270_146_963_049_287,
+        10_236_262_408_213_059_745,
+        78_552_867_005_814_007,
+    ],
+    [
+        17_899_847_381_280_262_181,
+        14_717_912_805_498_651_446,
+        10_769_146_203_951_775_298,
+        2_774_289_833_490_417_856,
+    ],
+    [
+        3_794_717_687_462_954_368,
+        4_386_865_643_074_822_822,
+        8_854_162_840_275_334_305,
+        7_129_983_987_107_225_269,
+    ],
+    [
+        7_244_773_535_611_633_983,
+        19_359_923_075_859_320,
+        10_898_655_967_774_994_333,
+        9_319_339_563_065_736_480,
+    ],
+    [
+        4_935_426_252_518_736_883,
+        12_584_230_452_580_950_419,
+        8_762_518_969_632_303_998,
+        18_159_875_708_229_758_073,
+    ],
+    [
+        14_871_230_873_837_295_931,
+        11_225_255_908_868_362_971,
+        18_100_987_641_405_432_308,
+        1_559_244_340_089_644_233,
+    ],
+    [
+        8_348_203_744_950_016_968,
+        4_041_411_241_960_726_733,
+        17_584_743_399_305_468_057,
+        16_836_952_610_803_537_051,
+    ],
+    [
+        16_139_797_453_633_030_050,
+        1_090_233_424_040_889_412,
+        10_770_255_347_785_669_036,
+        16_982_398_877_290_254_028,
+    ],
+];
+
+fn elements(len: usize) -> Vec<F> {
+    (0..len).map(F::from_canonical_usize).collect()
+}
+
+#[test]
+fn kats_native() {
+    for (i, expected) in EXPECTED.iter().enumerate() {
+        let digest = hash_elements(&elements(i + 1)).map(|x| x.to_canonical_u64());
+        assert_eq!(&digest, expected, "{} elements", i + 1);
+    }
+}
+
+#[test]
+fn kats_trace_and_stark() -> Result<()> {
+    // every permutation of every vector, with the digest read from the row
+    // of the last permutation
+    let mut step_rows = Vec::new();
+    let mut digest_rows = Vec::new();
+    for i in 0..EXPECTED.len() {
+        step_rows.extend(hash_elements_rows(&elements(i + 1)));
+        digest_rows.push(step_rows.len() - 1);
+    }
+
+    let trace = generate_rpo_trace(&step_rows);
+    for (expected, row) in EXPECTED.iter().zip(digest_rows) {
+        let digest: Vec<u64> = DIGEST_RANGE
+            .map(|j| trace[COL_OUTPUT_START + j][row].to_canonical_u64())
+            .collect();
+        assert_eq!(&digest, expected);
+    }
+
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.cap_height = 0;
+    config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+    let stark = S::default();
+    let proof = prove::<F, C, S, D>(
+        stark,
+        &config,
+        trace_to_poly_values(trace),
+        [],
+        &mut TimingTree::default(),
+    )?;
+    verify_stark_proof(stark, proof, &config)
+}
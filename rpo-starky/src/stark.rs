@@ -0,0 +1,156 @@
+use crate::columns::{col_inv_sbox_state_start, col_sbox_state_start, COL_INPUT_START, NUM_COLS};
+use crate::constants::{ALPHA, ARK1, ARK2, MDS, NUM_ROUNDS, STATE_SIZE};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+// linear layer (degree = 1)
+fn mds_constraints<P: PackedField>(state: &[P; STATE_SIZE]) -> [P; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        (0..STATE_SIZE).fold(P::ZEROS, |acc, j| {
+            acc + state[j] * P::Scalar::from_canonical_u64(MDS[(j + STATE_SIZE - i) % STATE_SIZE])
+        })
+    })
+}
+
+// degree: 1
+fn add_rc_constraints<P: PackedField>(
+    state: &[P; STATE_SIZE],
+    round_constants: &[u64; STATE_SIZE],
+) -> [P; STATE_SIZE] {
+    std::array::from_fn(|i| state[i] + P::Scalar::from_canonical_u64(round_constants[i]))
+}
+
+// degree: 7
+fn sbox_constraints<P: PackedField>(x: P) -> P {
+    (0..ALPHA).fold(P::ONES, |acc, _| acc * x)
+}
+
+fn state_at<P: PackedField>(lv: &[P], start: usize) -> [P; STATE_SIZE] {
+    lv[start..start + STATE_SIZE].try_into().unwrap()
+}
+
+/// Constraints of a single RPO permutation laid out as in `columns.rs`,
+/// starting at the first column of `lv`. The inverse S-box `x^(1/7)` is
+/// checked through its output `y` as `y^7 = x`.
+pub(crate) fn eval_permutation<P: PackedField>(lv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    let mut state = state_at(lv, COL_INPUT_START);
+
+    for r in 0..NUM_ROUNDS {
+        // forward half: MDS, constants and x^7
+        let sbox_input = add_rc_constraints(&mds_constraints(&state), &ARK1[r]);
+        let sbox_state = state_at(lv, col_sbox_state_start(r));
+        for (&input, &output) in sbox_input.iter().zip(&sbox_state) {
+            yield_constr.constraint(output - sbox_constraints(input));
+        }
+
+        // inverse half: MDS, constants and x^(1/7)
+        let inv_sbox_input = add_rc_constraints(&mds_constraints(&sbox_state), &ARK2[r]);
+        let inv_sbox_state = state_at(lv, col_inv_sbox_state_start(r));
+        for (&input, &output) in inv_sbox_input.iter().zip(&inv_sbox_state) {
+            yield_constr.constraint(sbox_constraints(output) - input);
+        }
+
+        state = inv_sbox_state;
+    }
+}
+
+/// The RPO permutation table, one row per permutation.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct RpoStark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for RpoStark<F, D> {
+    const COLUMNS: usize = NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_permutation(vars.local_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::{generate_rpo_trace, Row};
+    use crate::stark::{trace_to_poly_values, RpoStark};
+    use anyhow::Result;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = RpoStark<F, D>;
+
+    #[test]
+    fn rpo_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let step_rows: Vec<Row<F>> = (0..12)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let stark = S::default();
+        let trace = generate_rpo_trace(&step_rows);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn rpo_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
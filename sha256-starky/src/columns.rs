@@ -0,0 +1,50 @@
+use crate::constants::{BLOCK_WORDS, NUM_ROUNDS, STATE_WORDS};
+
+/// The starting point of the round flags: column `COL_STEP_START + i` is set
+/// on the row of round `i`
+pub(crate) const COL_STEP_START: usize = 0;
+
+/// 1 on the rows of the first block of a message, 0 otherwise
+pub(crate) const COL_IS_FIRST_BLOCK: usize = COL_STEP_START + NUM_ROUNDS; // 64
+
+/// The starting point of the chaining value the block starts from, repeated
+/// on each of its rows
+pub(crate) const COL_H_IN_START: usize = COL_IS_FIRST_BLOCK + 1; // 65
+
+/// The starting point of the bits of the message schedule window
+/// `W[i]..W[i + 15]` of round `i`, little-endian per word. In round 0 this is
+/// the block.
+pub(crate) const COL_W_BITS_START: usize = COL_H_IN_START + STATE_WORDS; // 73
+
+/// The starting point of the bits of `W[i + 16]`, which enters the window in
+/// the next round
+pub(crate) const COL_W_NEXT_BITS_START: usize = COL_W_BITS_START + BLOCK_WORDS * 32; // 585
+
+/// The starting point of the 2 bits of the carry of `W[i + 16]`
+pub(crate) const COL_W_NEXT_CARRY_START: usize = COL_W_NEXT_BITS_START + 32; // 617
+
+/// The starting point of the bits of the working variables `a..h` before the
+/// round
+pub(crate) const COL_STATE_BITS_START: usize = COL_W_NEXT_CARRY_START + 2; // 619
+
+/// The starting point of the bits of `a` after the round
+pub(crate) const COL_NEW_A_BITS_START: usize = COL_STATE_BITS_START + STATE_WORDS * 32; // 875
+
+/// The starting point of the 3 bits of the carry of the new `a`
+pub(crate) const COL_NEW_A_CARRY_START: usize = COL_NEW_A_BITS_START + 32; // 907
+
+/// The starting point of the bits of `e` after the round
+pub(crate) const COL_NEW_E_BITS_START: usize = COL_NEW_A_CARRY_START + 3; // 910
+
+/// The starting point of the 3 bits of the carry of the new `e`
+pub(crate) const COL_NEW_E_CARRY_START: usize = COL_NEW_E_BITS_START + 32; // 942
+
+/// The starting point of the bits of the chaining value after the block, in
+/// the row of the last round
+pub(crate) const COL_OUTPUT_BITS_START: usize = COL_NEW_E_CARRY_START + 3; // 945
+
+/// The starting point of the carries of the output words
+pub(crate) const COL_OUTPUT_CARRY_START: usize = COL_OUTPUT_BITS_START + STATE_WORDS * 32; // 1201
+
+/// The total number of columns
+pub const NUM_COLS: usize = COL_OUTPUT_CARRY_START + STATE_WORDS; // 1209
@@ -0,0 +1,273 @@
+use crate::columns::{
+    COL_H_IN_START, COL_IS_FIRST_BLOCK, COL_NEW_A_BITS_START, COL_NEW_A_CARRY_START,
+    COL_NEW_E_BITS_START, COL_NEW_E_CARRY_START, COL_OUTPUT_BITS_START, COL_OUTPUT_CARRY_START,
+    COL_STATE_BITS_START, COL_STEP_START, COL_W_BITS_START, COL_W_NEXT_BITS_START,
+    COL_W_NEXT_CARRY_START, NUM_COLS,
+};
+use crate::constants::{BLOCK_WORDS, IV, K, NUM_ROUNDS, STATE_WORDS};
+use plonky2::hash::hash_types::RichField;
+
+/// A 512-bit block as big-endian 32-bit words
+pub type Block = [u32; BLOCK_WORDS];
+
+pub(crate) fn big_sigma0(x: u32) -> u32 {
+    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+}
+
+pub(crate) fn big_sigma1(x: u32) -> u32 {
+    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+}
+
+pub(crate) fn small_sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+pub(crate) fn small_sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+/// The message schedule of a block, extended to the `W[i + 16]` of every round
+fn message_schedule(block: &Block) -> [u32; NUM_ROUNDS + BLOCK_WORDS] {
+    let mut w = [0; NUM_ROUNDS + BLOCK_WORDS];
+    w[..BLOCK_WORDS].copy_from_slice(block);
+    for i in BLOCK_WORDS..w.len() {
+        w[i] = small_sigma1(w[i - 2])
+            .wrapping_add(w[i - 7])
+            .wrapping_add(small_sigma0(w[i - 15]))
+            .wrapping_add(w[i - 16]);
+    }
+    w
+}
+
+/// The working variables after a round, given the new `a` and `e` before the
+/// reduction modulo 2^32
+#[allow(clippy::cast_possible_truncation, clippy::many_single_char_names)]
+fn next_state(state: &[u32; STATE_WORDS], new_a: u64, new_e: u64) -> [u32; STATE_WORDS] {
+    let [a, b, c, _, e, f, g, _] = *state;
+    [new_a as u32, a, b, c, new_e as u32, e, f, g]
+}
+
+/// The round function, returning the new `a` and `e` before the reduction
+/// modulo 2^32
+#[allow(clippy::many_single_char_names)]
+fn round_sums(round: usize, state: &[u32; STATE_WORDS], w: u32) -> (u64, u64) {
+    let [a, b, c, d, e, f, g, h] = *state;
+    let ch = (e & f) ^ (!e & g);
+    let maj = (a & b) ^ (a & c) ^ (b & c);
+    let t1 = u64::from(h)
+        + u64::from(big_sigma1(e))
+        + u64::from(ch)
+        + u64::from(K[round])
+        + u64::from(w);
+    let t2 = u64::from(big_sigma0(a)) + u64::from(maj);
+    (t1 + t2, u64::from(d) + t1)
+}
+
+/// Pad a message with a `1` bit, zeros and its 64-bit big-endian bit length
+/// to a multiple of 512 bits and split it into blocks.
+#[must_use]
+pub fn pad_message(message: &[u8]) -> Vec<Block> {
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+    padded
+        .chunks(64)
+        .map(|chunk| {
+            let words: Vec<u32> = chunk
+                .chunks(4)
+                .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+                .collect();
+            words.try_into().unwrap()
+        })
+        .collect()
+}
+
+/// The SHA-256 compression function
+pub fn compress(state: &mut [u32; STATE_WORDS], block: &Block) {
+    let w = message_schedule(block);
+    let mut working = *state;
+    for (round, &w) in w.iter().take(NUM_ROUNDS).enumerate() {
+        let (new_a, new_e) = round_sums(round, &working, w);
+        working = next_state(&working, new_a, new_e);
+    }
+    for (s, w) in state.iter_mut().zip(working) {
+        *s = s.wrapping_add(w);
+    }
+}
+
+/// Hash a message natively.
+#[must_use]
+pub fn sha256(message: &[u8]) -> [u8; 32] {
+    let mut state = IV;
+    for block in pad_message(message) {
+        compress(&mut state, &block);
+    }
+    let mut digest = [0; 32];
+    for (chunk, word) in digest.chunks_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+fn fill_bits<F: RichField>(trace: &mut [Vec<F>], col: usize, row: usize, value: u64, bits: usize) {
+    for z in 0..bits {
+        trace[col + z][row] = F::from_bool(value >> z & 1 == 1);
+    }
+}
+
+/// Fill the 64 rows of one block starting at `row` and return the chaining
+/// value after the block.
+fn fill_block_rows<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    h_in: &[u32; STATE_WORDS],
+    block: &Block,
+    is_first: bool,
+) -> [u32; STATE_WORDS] {
+    let w = message_schedule(block);
+    let mut state = *h_in;
+
+    for round in 0..NUM_ROUNDS {
+        let r = row + round;
+        trace[COL_STEP_START + round][r] = F::ONE;
+        trace[COL_IS_FIRST_BLOCK][r] = F::from_bool(is_first);
+        for (k, &h) in h_in.iter().enumerate() {
+            trace[COL_H_IN_START + k][r] = F::from_canonical_u32(h);
+        }
+
+        for k in 0..BLOCK_WORDS {
+            fill_bits(trace, COL_W_BITS_START + 32 * k, r, w[round + k].into(), 32);
+        }
+        let w_next = u64::from(small_sigma1(w[round + 14]))
+            + u64::from(w[round + 9])
+            + u64::from(small_sigma0(w[round + 1]))
+            + u64::from(w[round]);
+        fill_bits(trace, COL_W_NEXT_BITS_START, r, w_next, 32);
+        fill_bits(trace, COL_W_NEXT_CARRY_START, r, w_next >> 32, 2);
+
+        for (k, &s) in state.iter().enumerate() {
+            fill_bits(trace, COL_STATE_BITS_START + 32 * k, r, s.into(), 32);
+        }
+        let (new_a, new_e) = round_sums(round, &state, w[round]);
+        fill_bits(trace, COL_NEW_A_BITS_START, r, new_a, 32);
+        fill_bits(trace, COL_NEW_A_CARRY_START, r, new_a >> 32, 3);
+        fill_bits(trace, COL_NEW_E_BITS_START, r, new_e, 32);
+        fill_bits(trace, COL_NEW_E_CARRY_START, r, new_e >> 32, 3);
+
+        state = next_state(&state, new_a, new_e);
+    }
+
+    // the chaining value after the block, in the row of the last round
+    let r = row + NUM_ROUNDS - 1;
+    let mut output = [0; STATE_WORDS];
+    for k in 0..STATE_WORDS {
+        let sum = u64::from(h_in[k]) + u64::from(state[k]);
+        fill_bits(trace, COL_OUTPUT_BITS_START + 32 * k, r, sum, 32);
+        fill_bits(trace, COL_OUTPUT_CARRY_START + k, r, sum >> 32, 1);
+        output[k] = h_in[k].wrapping_add(state[k]);
+    }
+    output
+}
+
+/// Function to generate the SHA-256 trace of messages given as their padded
+/// blocks: 64 rows per block, padded with all-zero rows to a power of 2.
+#[must_use]
+pub fn generate_sha256_trace<F: RichField>(messages: &[Vec<Block>]) -> [Vec<F>; NUM_COLS] {
+    let num_blocks: usize = messages.iter().map(Vec::len).sum();
+    let trace_len = (num_blocks * NUM_ROUNDS).next_power_of_two();
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; NUM_COLS];
+
+    let mut row = 0;
+    for blocks in messages {
+        let mut state = IV;
+        for (i, block) in blocks.iter().enumerate() {
+            state = fill_block_rows(&mut trace, row, &state, block, i == 0);
+            row += NUM_ROUNDS;
+        }
+    }
+
+    trace.try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::columns::COL_OUTPUT_BITS_START;
+    use crate::constants::NUM_ROUNDS;
+    use crate::generation::{generate_sha256_trace, pad_message, sha256};
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::PrimeField64;
+    use proptest::prelude::*;
+    use sha2::{Digest, Sha256};
+
+    type F = GoldilocksField;
+
+    fn hex(digest: &[u8]) -> String {
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// The vectors of FIPS 180-2, appendix B
+    #[test]
+    fn standard_vectors() {
+        assert_eq!(
+            hex(&sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex(&sha256(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(
+            hex(&sha256(&vec![b'a'; 1_000_000])),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn padding_lengths() {
+        assert_eq!(pad_message(&[]).len(), 1);
+        assert_eq!(pad_message(&[0; 55]).len(), 1);
+        assert_eq!(pad_message(&[0; 56]).len(), 2);
+        assert_eq!(pad_message(&[0; 64]).len(), 2);
+        assert_eq!(pad_message(b"abc")[0][15], 24);
+    }
+
+    #[test]
+    fn generate_sha256_trace_outputs() {
+        let messages: Vec<&[u8]> = vec![b"abc", &[7; 100], b""];
+        let trace = generate_sha256_trace::<F>(
+            &messages.iter().map(|m| pad_message(m)).collect::<Vec<_>>(),
+        );
+
+        // the digest is the output of the last block of each message
+        let mut row = 0;
+        for message in messages {
+            row += pad_message(message).len() * NUM_ROUNDS;
+            let digest: Vec<u8> = (0..8)
+                .flat_map(|k| {
+                    let word = (0..32).rev().fold(0u32, |acc, z| {
+                        let bit = trace[COL_OUTPUT_BITS_START + 32 * k + z][row - 1];
+                        acc << 1 | u32::try_from(bit.to_canonical_u64()).unwrap()
+                    });
+                    word.to_be_bytes()
+                })
+                .collect();
+            assert_eq!(digest, sha256(message));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn sha256_matches_reference(message in prop::collection::vec(any::<u8>(), 0..300)) {
+            prop_assert_eq!(sha256(&message).to_vec(), Sha256::digest(&message).to_vec());
+        }
+    }
+}
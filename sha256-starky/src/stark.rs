@@ -0,0 +1,312 @@
+use crate::columns::{
+    COL_H_IN_START, COL_IS_FIRST_BLOCK, COL_NEW_A_BITS_START, COL_NEW_A_CARRY_START,
+    COL_NEW_E_BITS_START, COL_NEW_E_CARRY_START, COL_OUTPUT_BITS_START, COL_OUTPUT_CARRY_START,
+    COL_STATE_BITS_START, COL_STEP_START, COL_W_BITS_START, COL_W_NEXT_BITS_START,
+    COL_W_NEXT_CARRY_START, NUM_COLS,
+};
+use crate::constants::{BLOCK_WORDS, IV, K, NUM_ROUNDS, STATE_WORDS};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+// degree: 2
+fn xor<P: PackedField>(a: P, b: P) -> P {
+    a + b - a * b * P::Scalar::TWO
+}
+
+// degree: 3
+fn xor3<P: PackedField>(a: P, b: P, c: P) -> P {
+    xor(a, xor(b, c))
+}
+
+/// Recompose the little-endian bits `get_bit(0)..get_bit(31)` into a word
+fn word<P: PackedField>(get_bit: impl Fn(usize) -> P) -> P {
+    (0..32)
+        .rev()
+        .fold(P::ZEROS, |acc, z| acc * P::Scalar::TWO + get_bit(z))
+}
+
+/// The word whose bits start at column `col`
+fn word_at<P: PackedField>(lv: &[P], col: usize) -> P {
+    word(|z| lv[col + z])
+}
+
+/// The carry whose `bits` bits start at column `col`, scaled by 2^32
+fn carry_at<P: PackedField>(lv: &[P], col: usize, bits: usize) -> P {
+    let carry = (0..bits)
+        .rev()
+        .fold(P::ZEROS, |acc, i| acc * P::Scalar::TWO + lv[col + i]);
+    carry * P::Scalar::from_canonical_u64(1 << 32)
+}
+
+fn w_bits(k: usize) -> usize {
+    COL_W_BITS_START + 32 * k
+}
+
+fn state_bits(k: usize) -> usize {
+    COL_STATE_BITS_START + 32 * k
+}
+
+/// The word `rotr(x, r[0]) ^ rotr(x, r[1]) ^ rotr(x, r[2])` of the bits of
+/// `x` starting at column `col`, of degree 3. With `shr` the last term is the
+/// shift `x >> r[2]` instead.
+fn sigma<P: PackedField>(lv: &[P], col: usize, r: [usize; 3], shr: bool) -> P {
+    word(|z| {
+        let last = if !shr {
+            lv[col + (z + r[2]) % 32]
+        } else if z + r[2] < 32 {
+            lv[col + z + r[2]]
+        } else {
+            P::ZEROS
+        };
+        xor3(lv[col + (z + r[0]) % 32], lv[col + (z + r[1]) % 32], last)
+    })
+}
+
+/// Constraints of the round flags and of the chaining value of the block
+fn eval_rounds_and_blocks<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    // the round flags are one-hot or all zero on padding rows, start with
+    // round 0 and step through the rounds in order
+    let mut flag_sum = P::ZEROS;
+    for round in 0..NUM_ROUNDS {
+        let flag = lv[COL_STEP_START + round];
+        yield_constr.constraint(flag * (flag - P::ONES));
+        flag_sum += flag;
+        if round > 0 {
+            yield_constr.constraint_first_row(flag);
+        }
+        if round < NUM_ROUNDS - 1 {
+            yield_constr.constraint_transition(nv[COL_STEP_START + round + 1] - flag);
+        }
+    }
+    yield_constr.constraint(flag_sum * (flag_sum - P::ONES));
+    let not_last_round = flag_sum - lv[COL_STEP_START + NUM_ROUNDS - 1];
+
+    // the first block of a message starts from the IV, the flag and the
+    // chaining value are carried to the last round and the working variables
+    // start from the chaining value
+    let is_first = lv[COL_IS_FIRST_BLOCK];
+    let step0 = lv[COL_STEP_START];
+    yield_constr.constraint(is_first * (is_first - P::ONES));
+    yield_constr.constraint_first_row(step0 * (P::ONES - is_first));
+    yield_constr.constraint_transition(not_last_round * (nv[COL_IS_FIRST_BLOCK] - is_first));
+    for (k, iv) in IV.into_iter().enumerate() {
+        let h_in = lv[COL_H_IN_START + k];
+        yield_constr.constraint(is_first * (h_in - P::Scalar::from_canonical_u32(iv)));
+        yield_constr.constraint(step0 * (word_at(lv, state_bits(k)) - h_in));
+        yield_constr.constraint_transition(not_last_round * (nv[COL_H_IN_START + k] - h_in));
+    }
+
+    // a block that continues a message directly follows the last round of
+    // the previous block and starts from its output
+    let next_continues = nv[COL_STEP_START] * (P::ONES - nv[COL_IS_FIRST_BLOCK]);
+    yield_constr
+        .constraint_transition(next_continues * (P::ONES - lv[COL_STEP_START + NUM_ROUNDS - 1]));
+    for k in 0..STATE_WORDS {
+        let output = word_at(lv, COL_OUTPUT_BITS_START + 32 * k);
+        yield_constr.constraint_transition(next_continues * (nv[COL_H_IN_START + k] - output));
+    }
+}
+
+/// Constraints of the message schedule: the window slides by one word per
+/// round and `W[i + 16] = σ1(W[i + 14]) + W[i + 9] + σ0(W[i + 1]) + W[i]`
+fn eval_message_schedule<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    not_last_round: P,
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let w = |k| word_at(lv, w_bits(k));
+    let sum = sigma(lv, w_bits(14), [17, 19, 10], true)
+        + w(9)
+        + sigma(lv, w_bits(1), [7, 18, 3], true)
+        + w(0);
+    let w_next = word_at(lv, COL_W_NEXT_BITS_START);
+    yield_constr.constraint(w_next + carry_at(lv, COL_W_NEXT_CARRY_START, 2) - sum);
+
+    for k in 0..BLOCK_WORDS - 1 {
+        yield_constr.constraint_transition(not_last_round * (word_at(nv, w_bits(k)) - w(k + 1)));
+    }
+    yield_constr
+        .constraint_transition(not_last_round * (word_at(nv, w_bits(BLOCK_WORDS - 1)) - w_next));
+}
+
+#[allow(clippy::many_single_char_names)]
+/// Constraints of a single round, with the round constant selected by the
+/// round flags
+fn eval_round<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    not_last_round: P,
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    let (a, b, c, e, f, g) = (
+        state_bits(0),
+        state_bits(1),
+        state_bits(2),
+        state_bits(4),
+        state_bits(5),
+        state_bits(6),
+    );
+    let ch = word(|z| lv[e + z] * lv[f + z] + (P::ONES - lv[e + z]) * lv[g + z]);
+    let maj = word(|z| {
+        let (x, y, w) = (lv[a + z], lv[b + z], lv[c + z]);
+        x * y + x * w + y * w - x * y * w * P::Scalar::TWO
+    });
+    let k = (0..NUM_ROUNDS).fold(P::ZEROS, |acc, round| {
+        acc + lv[COL_STEP_START + round] * P::Scalar::from_canonical_u32(K[round])
+    });
+    let t1 = word_at(lv, state_bits(7))
+        + sigma(lv, e, [6, 11, 25], false)
+        + ch
+        + k
+        + word_at(lv, w_bits(0));
+    let t2 = sigma(lv, a, [2, 13, 22], false) + maj;
+
+    let new_a = word_at(lv, COL_NEW_A_BITS_START);
+    let new_e = word_at(lv, COL_NEW_E_BITS_START);
+    yield_constr.constraint(new_a + carry_at(lv, COL_NEW_A_CARRY_START, 3) - t1 - t2);
+    yield_constr.constraint(
+        new_e + carry_at(lv, COL_NEW_E_CARRY_START, 3) - word_at(lv, state_bits(3)) - t1,
+    );
+
+    // the working variables of the next round
+    let next = [
+        new_a,
+        word_at(lv, a),
+        word_at(lv, b),
+        word_at(lv, c),
+        new_e,
+        word_at(lv, e),
+        word_at(lv, f),
+        word_at(lv, g),
+    ];
+    for (k, value) in next.into_iter().enumerate() {
+        yield_constr.constraint_transition(not_last_round * (word_at(nv, state_bits(k)) - value));
+    }
+
+    // the last round adds the working variables to the chaining value
+    let last_round = lv[COL_STEP_START + NUM_ROUNDS - 1];
+    for (k, value) in next.into_iter().enumerate() {
+        let output = word_at(lv, COL_OUTPUT_BITS_START + 32 * k)
+            + carry_at(lv, COL_OUTPUT_CARRY_START + k, 1);
+        yield_constr.constraint(last_round * (output - lv[COL_H_IN_START + k] - value));
+    }
+}
+
+/// Constraints of the SHA-256 compression table laid out as in `columns.rs`
+fn eval_sha256_compression<P: PackedField>(
+    lv: &[P],
+    nv: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+) {
+    // all the columns from the message schedule on are bits
+    for &bit in &lv[COL_W_BITS_START..NUM_COLS] {
+        yield_constr.constraint(bit * (bit - P::ONES));
+    }
+
+    eval_rounds_and_blocks(lv, nv, yield_constr);
+    let not_last_round =
+        (0..NUM_ROUNDS - 1).fold(P::ZEROS, |acc, round| acc + lv[COL_STEP_START + round]);
+    eval_message_schedule(lv, nv, not_last_round, yield_constr);
+    eval_round(lv, nv, not_last_round, yield_constr);
+}
+
+/// The SHA-256 compression table, one row per round and 64 rows per block.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Sha256Stark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Sha256Stark<F, D> {
+    const COLUMNS: usize = NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_sha256_compression(vars.local_values, vars.next_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::{generate_sha256_trace, pad_message};
+    use crate::stark::{trace_to_poly_values, Sha256Stark};
+    use anyhow::Result;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Sha256Stark<F, D>;
+
+    #[test]
+    fn sha256_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+
+        // a single-block and a two-block message
+        let messages = vec![pad_message(b"abc"), pad_message(&[0x5a; 100])];
+
+        let stark = S::default();
+        let trace = generate_sha256_trace(&messages);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn sha256_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}
@@ -0,0 +1,71 @@
+use crate::constants::{NUM_BYTES, NUM_ROUNDS, NUM_SPLIT_AND_LOOKUP, STATE_SIZE};
+
+/// The starting point of the one-hot round flags. Padding rows have no flag
+/// set.
+pub(crate) const COL_STEP_START: usize = 0;
+
+/// The starting point of the state at the start of the round
+pub(crate) const COL_STATE_START: usize = COL_STEP_START + NUM_ROUNDS; // 5
+
+/// The bytes of the Montgomery form of split element `i`, little-endian
+#[must_use]
+pub const fn col_input_bytes(i: usize) -> usize {
+    COL_STATE_START + STATE_SIZE + NUM_BYTES * i
+}
+
+/// The looked-up bytes of split element `i`, little-endian
+#[must_use]
+pub const fn col_output_bytes(i: usize) -> usize {
+    col_input_bytes(NUM_SPLIT_AND_LOOKUP) + NUM_BYTES * i
+}
+
+/// The inverse of `hi - (2^32 - 1)` for the high half of the bytes of split
+/// element `i`, or zero if they are equal. It proves that the bytes are the
+/// canonical decomposition.
+#[must_use]
+pub const fn col_canonical_inv(i: usize) -> usize {
+    col_output_bytes(NUM_SPLIT_AND_LOOKUP) + i
+}
+
+/// The starting point of the state at the end of the round. On the row of
+/// the last round it is the output of the permutation.
+pub const COL_OUTPUT_START: usize = col_canonical_inv(NUM_SPLIT_AND_LOOKUP); // 89
+
+/// The lookup table: every byte and its image, with the last entry repeated
+/// on the rows past 256
+pub(crate) const COL_TABLE_INPUT: usize = COL_OUTPUT_START + STATE_SIZE; // 105
+pub(crate) const COL_TABLE_OUTPUT: usize = COL_TABLE_INPUT + 1; // 106
+
+/// The quotient of `(x + 1)^3 - 1` by 257 for the table input `x`
+pub(crate) const COL_TABLE_QUOTIENT: usize = COL_TABLE_OUTPUT + 1; // 107
+
+/// The starting point of the little-endian bits of the table output, which
+/// prove that it is a byte
+pub(crate) const COL_TABLE_OUTPUT_BITS_START: usize = COL_TABLE_QUOTIENT + 1; // 108
+
+/// The starting point of the little-endian bits of the table quotient
+pub(crate) const COL_TABLE_QUOTIENT_BITS_START: usize = COL_TABLE_OUTPUT_BITS_START + 8; // 116
+
+/// The number of bits of the table quotient, which is below `2^24 / 257`
+pub(crate) const TABLE_QUOTIENT_BITS: usize = 16;
+
+/// The number of lookups per row, one per byte of a split element
+pub(crate) const NUM_LOOKUPS: usize = NUM_SPLIT_AND_LOOKUP * NUM_BYTES;
+
+/// The permuted input byte, permuted output byte, permuted table input and
+/// permuted table output of lookup `j`
+#[must_use]
+pub const fn col_permuted(j: usize) -> [usize; 4] {
+    let start = COL_TABLE_QUOTIENT_BITS_START + TABLE_QUOTIENT_BITS + 4 * j;
+    [start, start + 1, start + 2, start + 3]
+}
+
+/// The total number of columns
+pub const NUM_COLS: usize = col_permuted(NUM_LOOKUPS)[0]; // 260
+
+/// The input byte and output byte of lookup `j`
+#[must_use]
+pub const fn col_lookup(j: usize) -> [usize; 2] {
+    let (i, k) = (j / NUM_BYTES, j % NUM_BYTES);
+    [col_input_bytes(i) + k, col_output_bytes(i) + k]
+}
@@ -0,0 +1,148 @@
+use std::ops::Range;
+
+/// The size of the state: a rate of 10 and a capacity of 6 elements
+pub const STATE_SIZE: usize = 16;
+
+/// The rate is in elements 0..10 and the capacity in elements 10..16
+pub const RATE_RANGE: Range<usize> = 0..10;
+
+/// The number of elements of a digest
+pub const DIGEST_SIZE: usize = 5;
+
+/// The number of rounds
+pub const NUM_ROUNDS: usize = 5;
+
+/// The number of state elements that go through the split-and-lookup S-box;
+/// the others go through the power map
+pub const NUM_SPLIT_AND_LOOKUP: usize = 4;
+
+/// The number of bytes of a split element
+pub const NUM_BYTES: usize = 8;
+
+/// The exponent of the power map
+pub const ALPHA: u64 = 7;
+
+/// The Montgomery radix `2^64 mod p`. The split-and-lookup S-box works on the
+/// bytes of `x * R mod p`, the Montgomery form of the reference implementation.
+pub const MONTGOMERY_R: u64 = 0xFFFF_FFFF;
+
+/// The offset Fermat cube map `(x + 1)^3 - 1 mod 257` of a byte. It fixes 0
+/// and 255, so the looked-up bytes of a canonical element are canonical too.
+#[allow(clippy::cast_possible_truncation)]
+const fn offset_fermat_cube_map(x: u64) -> u8 {
+    let xx = x + 1;
+    ((xx * xx * xx + 256) % 257) as u8
+}
+
+const fn lookup_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = offset_fermat_cube_map(i as u64);
+        i += 1;
+    }
+    table
+}
+
+/// The byte lookup table of the split-and-lookup S-box
+pub const LOOKUP_TABLE: [u8; 256] = lookup_table();
+
+/// The first column of the circulant MDS matrix
+pub const MDS_FIRST_COLUMN: [u64; STATE_SIZE] = [
+    61402, 1108, 28750, 33823, 7454, 43244, 53865, 12034, 56951, 27521, 41351, 40901, 12021, 59689,
+    26798, 17845,
+];
+
+/// The round constants, added at the end of each round
+pub const ROUND_CONSTANTS: [[u64; STATE_SIZE]; NUM_ROUNDS] = [
+    [
+        13_630_775_303_355_457_758,
+        16_896_927_574_093_233_874,
+        10_379_449_653_650_130_495,
+        1_965_408_364_413_093_495,
+        15_232_538_947_090_185_111,
+        15_892_634_398_091_747_074,
+        3_989_134_140_024_871_768,
+        2_851_411_912_127_730_865,
+        8_709_136_439_293_758_776,
+        3_694_858_669_662_939_734,
+        12_692_440_244_315_327_141,
+        10_722_316_166_358_076_749,
+        12_745_429_320_441_639_448,
+        17_932_424_223_723_990_421,
+        7_558_102_534_867_937_463,
+        15_551_047_435_855_531_404,
+    ],
+    [
+        17_532_528_648_579_384_106,
+        5_216_785_850_422_679_555,
+        15_418_071_332_095_031_847,
+        11_921_929_762_955_146_258,
+        9_738_718_993_677_019_874,
+        3_464_580_399_432_997_147,
+        13_408_434_769_117_164_050,
+        264_428_218_649_616_431,
+        4_436_247_869_008_081_381,
+        4_063_129_435_850_804_221,
+        2_865_073_155_741_120_117,
+        5_749_834_437_609_765_994,
+        6_804_196_764_189_408_435,
+        17_060_469_201_292_988_508,
+        9_475_383_556_737_206_708,
+        12_876_344_085_611_465_020,
+    ],
+    [
+        13_835_756_199_368_269_249,
+        1_648_753_455_944_344_172,
+        9_836_124_473_569_258_483,
+        12_867_641_597_107_932_229,
+        11_254_152_636_692_960_595,
+        16_550_832_737_139_861_108,
+        11_861_573_970_480_733_262,
+        1_256_660_473_588_673_495,
+        13_879_506_000_676_455_136,
+        10_564_103_842_682_358_721,
+        16_142_842_524_796_397_521,
+        3_287_098_591_948_630_584,
+        685_911_471_061_284_805,
+        5_285_298_776_918_878_023,
+        18_310_953_571_768_047_354,
+        3_142_266_350_630_002_035,
+    ],
+    [
+        549_990_724_933_663_297,
+        4_901_984_846_118_077_401,
+        11_458_643_033_696_775_769,
+        8_706_785_264_119_212_710,
+        12_521_758_138_015_724_072,
+        11_877_914_062_416_978_196,
+        11_333_318_251_134_523_752,
+        3_933_899_631_278_608_623,
+        16_635_128_972_021_157_924,
+        10_291_337_173_108_950_450,
+        4_142_107_155_024_199_350,
+        16_973_934_533_787_743_537,
+        11_068_111_539_125_175_221,
+        17_546_769_694_830_203_606,
+        5_315_217_744_825_068_993,
+        4_609_594_252_909_613_081,
+    ],
+    [
+        3_350_107_164_315_270_407,
+        17_715_942_834_299_349_177,
+        9_600_609_149_219_873_996,
+        12_894_357_635_820_003_949,
+        4_597_649_658_040_514_631,
+        7_735_563_950_920_491_847,
+        1_663_379_455_870_887_181,
+        13_889_298_103_638_829_706,
+        7_375_530_351_220_884_434,
+        3_502_022_433_285_269_151,
+        9_231_805_330_431_056_952,
+        9_252_272_755_288_523_725,
+        10_014_268_662_326_746_219,
+        15_565_031_632_950_843_234,
+        1_209_725_273_521_819_323,
+        6_024_642_864_597_845_108,
+    ],
+];
@@ -0,0 +1,259 @@
+use crate::columns::{
+    col_canonical_inv, col_input_bytes, col_lookup, col_output_bytes, col_permuted,
+    COL_OUTPUT_START, COL_STATE_START, COL_STEP_START, COL_TABLE_INPUT, COL_TABLE_OUTPUT,
+    COL_TABLE_OUTPUT_BITS_START, COL_TABLE_QUOTIENT, COL_TABLE_QUOTIENT_BITS_START, NUM_COLS,
+    NUM_LOOKUPS, TABLE_QUOTIENT_BITS,
+};
+use crate::constants::{
+    ALPHA, DIGEST_SIZE, LOOKUP_TABLE, MDS_FIRST_COLUMN, MONTGOMERY_R, NUM_ROUNDS,
+    NUM_SPLIT_AND_LOOKUP, RATE_RANGE, ROUND_CONSTANTS, STATE_SIZE,
+};
+use permutation_air::lookup::permuted_cols;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+
+// Represent a row of the preimage
+#[derive(Debug, Clone, Default)]
+pub struct Row<Field: RichField> {
+    pub preimage: [Field; STATE_SIZE],
+}
+
+/// The bytes of the Montgomery form `x * R mod p` of an element,
+/// little-endian
+pub(crate) fn montgomery_bytes<F: RichField>(x: F) -> [u8; 8] {
+    (x * F::from_canonical_u64(MONTGOMERY_R))
+        .to_canonical_u64()
+        .to_le_bytes()
+}
+
+/// The element whose Montgomery form has the little-endian `bytes`
+pub(crate) fn from_montgomery_bytes<F: RichField>(bytes: [u8; 8]) -> F {
+    F::from_noncanonical_u64(u64::from_le_bytes(bytes))
+        * F::from_canonical_u64(MONTGOMERY_R).inverse()
+}
+
+/// The split-and-lookup S-box: every byte of the Montgomery form goes through
+/// the lookup table
+fn split_and_lookup<F: RichField>(x: F, table: &[u8; 256]) -> F {
+    from_montgomery_bytes(montgomery_bytes(x).map(|byte| table[byte as usize]))
+}
+
+/// Multiply the state by the circulant MDS matrix
+pub(crate) fn mds<F: Field>(state: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        (0..STATE_SIZE).fold(F::ZERO, |acc, j| {
+            acc + state[j]
+                * F::from_canonical_u64(MDS_FIRST_COLUMN[(i + STATE_SIZE - j) % STATE_SIZE])
+        })
+    })
+}
+
+/// Round `r` with the lookup table `table`: the S-box layer, the MDS matrix
+/// and the round constants
+fn round<F: RichField>(state: &[F; STATE_SIZE], r: usize, table: &[u8; 256]) -> [F; STATE_SIZE] {
+    let sbox_state: [F; STATE_SIZE] = std::array::from_fn(|i| {
+        if i < NUM_SPLIT_AND_LOOKUP {
+            split_and_lookup(state[i], table)
+        } else {
+            state[i].exp_u64(ALPHA)
+        }
+    });
+    let mut state = mds(&sbox_state);
+    for (s, &c) in state.iter_mut().zip(&ROUND_CONSTANTS[r]) {
+        *s += F::from_canonical_u64(c);
+    }
+    state
+}
+
+/// The Tip5 permutation
+#[must_use]
+pub fn permute<F: RichField>(preimage: &[F; STATE_SIZE]) -> [F; STATE_SIZE] {
+    (0..NUM_ROUNDS).fold(*preimage, |state, r| round(&state, r, &LOOKUP_TABLE))
+}
+
+/// Hash exactly 10 elements, as Triton's `Tip5::hash_10`: the capacity is
+/// set to ones for fixed-length inputs
+#[must_use]
+pub fn hash_10<F: RichField>(input: &[F; RATE_RANGE.end]) -> [F; DIGEST_SIZE] {
+    let mut state = [F::ONE; STATE_SIZE];
+    state[RATE_RANGE].copy_from_slice(input);
+    permute(&state)[..DIGEST_SIZE].try_into().unwrap()
+}
+
+/// The inputs of the permutations made by [`hash_varlen`]: the elements are
+/// padded with a 1 and zeros to a multiple of the rate and each block
+/// overwrites the rate of a sponge whose capacity starts at zero.
+#[must_use]
+pub fn hash_varlen_rows<F: RichField>(elements: &[F]) -> Vec<Row<F>> {
+    let mut padded = elements.to_vec();
+    padded.push(F::ONE);
+    let padded_len = (padded.len() + RATE_RANGE.len() - 1) / RATE_RANGE.len() * RATE_RANGE.len();
+    padded.resize(padded_len, F::ZERO);
+
+    let mut state = [F::ZERO; STATE_SIZE];
+    let mut rows = Vec::new();
+    for block in padded.chunks(RATE_RANGE.len()) {
+        state[RATE_RANGE].copy_from_slice(block);
+        rows.push(Row { preimage: state });
+        state = permute(&state);
+    }
+    rows
+}
+
+/// Hash field elements with the Tip5 sponge, as Triton's `Tip5::hash_varlen`
+#[must_use]
+pub fn hash_varlen<F: RichField>(elements: &[F]) -> [F; DIGEST_SIZE] {
+    let row = hash_varlen_rows(elements).pop().unwrap();
+    permute(&row.preimage)[..DIGEST_SIZE].try_into().unwrap()
+}
+
+/// Fill the rows of the rounds of one permutation starting at `row`
+fn fill_permutation_rows<F: RichField>(
+    trace: &mut [Vec<F>],
+    row: usize,
+    preimage: &[F; STATE_SIZE],
+    table: &[u8; 256],
+) {
+    let mut state = *preimage;
+    for r in 0..NUM_ROUNDS {
+        let row = row + r;
+        trace[COL_STEP_START + r][row] = F::ONE;
+        for (j, &value) in state.iter().enumerate() {
+            trace[COL_STATE_START + j][row] = value;
+        }
+        for i in 0..NUM_SPLIT_AND_LOOKUP {
+            let bytes = montgomery_bytes(state[i]);
+            for (k, &byte) in bytes.iter().enumerate() {
+                trace[col_input_bytes(i) + k][row] = F::from_canonical_u8(byte);
+                trace[col_output_bytes(i) + k][row] = F::from_canonical_u8(table[byte as usize]);
+            }
+            let hi = u64::from_le_bytes(bytes) >> 32;
+            trace[col_canonical_inv(i)][row] = (F::from_canonical_u64(hi)
+                - F::from_canonical_u64(MONTGOMERY_R))
+            .try_inverse()
+            .unwrap_or(F::ZERO);
+        }
+        state = round(&state, r, table);
+        for (j, &value) in state.iter().enumerate() {
+            trace[COL_OUTPUT_START + j][row] = value;
+        }
+    }
+}
+
+/// Fill the lookup table `table` and the permuted columns of every lookup
+fn fill_lookups<F: RichField>(trace: &mut [Vec<F>], table: &[u8; 256]) {
+    let trace_len = trace[0].len();
+    for row in 0..trace_len {
+        let x = row.min(255);
+        let y = table[x];
+        let quotient = ((x + 1).pow(3) - 1).saturating_sub(usize::from(y)) / 257;
+        trace[COL_TABLE_INPUT][row] = F::from_canonical_usize(x);
+        trace[COL_TABLE_OUTPUT][row] = F::from_canonical_u8(y);
+        trace[COL_TABLE_QUOTIENT][row] = F::from_canonical_usize(quotient);
+        for k in 0..8 {
+            trace[COL_TABLE_OUTPUT_BITS_START + k][row] = F::from_bool(y >> k & 1 == 1);
+        }
+        for k in 0..TABLE_QUOTIENT_BITS {
+            trace[COL_TABLE_QUOTIENT_BITS_START + k][row] = F::from_bool(quotient >> k & 1 == 1);
+        }
+    }
+    let table: Vec<(F, F)> = (0..trace_len)
+        .map(|row| (trace[COL_TABLE_INPUT][row], trace[COL_TABLE_OUTPUT][row]))
+        .collect();
+
+    for j in 0..NUM_LOOKUPS {
+        let [input, output] = col_lookup(j);
+        let inputs: Vec<(F, F)> = (0..trace_len)
+            .map(|row| (trace[input][row], trace[output][row]))
+            .collect();
+        let (permuted_inputs, permuted_table) = permuted_cols(&inputs, &table);
+
+        let [permuted_input, permuted_output, permuted_table_input, permuted_table_output] =
+            col_permuted(j);
+        for row in 0..trace_len {
+            trace[permuted_input][row] = permuted_inputs[row].0;
+            trace[permuted_output][row] = permuted_inputs[row].1;
+            trace[permuted_table_input][row] = permuted_table[row].0;
+            trace[permuted_table_output][row] = permuted_table[row].1;
+        }
+    }
+}
+
+/// Function to generate the Tip5 trace: 5 rows per permutation, padded with
+/// all-zero rows to a power of 2 of at least 256 rows, the size of the lookup
+/// table.
+#[must_use]
+pub fn generate_tip5_trace<F: RichField>(step_rows: &[Row<F>]) -> [Vec<F>; NUM_COLS] {
+    generate_tip5_trace_with_table(step_rows, &LOOKUP_TABLE)
+}
+
+/// Generate the trace of a Tip5 variant with the S-box table `table`
+pub(crate) fn generate_tip5_trace_with_table<F: RichField>(
+    step_rows: &[Row<F>],
+    table: &[u8; 256],
+) -> [Vec<F>; NUM_COLS] {
+    let trace_len = (step_rows.len() * NUM_ROUNDS)
+        .next_power_of_two()
+        .max(table.len());
+    let mut trace: Vec<Vec<F>> = vec![vec![F::ZERO; trace_len]; NUM_COLS];
+
+    for (i, row) in step_rows.iter().enumerate() {
+        fill_permutation_rows(&mut trace, i * NUM_ROUNDS, &row.preimage, table);
+    }
+    fill_lookups(&mut trace, table);
+
+    trace.try_into().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::columns::COL_OUTPUT_START;
+    use crate::constants::{LOOKUP_TABLE, NUM_ROUNDS, STATE_SIZE};
+    use crate::generation::{
+        from_montgomery_bytes, generate_tip5_trace, montgomery_bytes, permute, Row,
+    };
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn lookup_table() {
+        // the first and last entries of the reference table
+        assert_eq!(LOOKUP_TABLE[..8], [0, 7, 26, 63, 124, 215, 85, 254]);
+        assert_eq!(LOOKUP_TABLE[248..], [1, 170, 40, 131, 192, 229, 248, 255]);
+
+        let mut sorted = LOOKUP_TABLE;
+        sorted.sort_unstable();
+        assert!(sorted.iter().enumerate().all(|(i, &x)| usize::from(x) == i));
+    }
+
+    #[test]
+    fn montgomery_form() {
+        let x = F::rand();
+        assert_eq!(from_montgomery_bytes::<F>(montgomery_bytes(x)), x);
+        // the Montgomery form of 1 is 2^64 mod p
+        assert_eq!(u64::from_le_bytes(montgomery_bytes(F::ONE)), 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn generate_tip5_trace_outputs() {
+        let step_rows: Vec<Row<F>> = (0..3)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let trace = generate_tip5_trace(&step_rows);
+        assert_eq!(trace[0].len(), 256);
+        for (i, row) in step_rows.iter().enumerate() {
+            let expected = permute(&row.preimage);
+            for j in 0..STATE_SIZE {
+                assert_eq!(
+                    trace[COL_OUTPUT_START + j][i * NUM_ROUNDS + NUM_ROUNDS - 1],
+                    expected[j]
+                );
+            }
+        }
+    }
+}
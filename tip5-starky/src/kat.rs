@@ -0,0 +1,118 @@
+//! The Tip5 test vectors of Triton's `twenty-first` crate:
+//!
+//! - `hash_10` is chained seven times from the zero preimage, each digest
+//!   overwriting the preimage from position `i`, and the last digest is
+//!   checked;
+//! - the `hash_varlen` digests of `0, 1, ..., i - 1` for `i < 20` are summed
+//!   element-wise and the sum is checked.
+
+use crate::columns::COL_OUTPUT_START;
+use crate::constants::{DIGEST_SIZE, NUM_ROUNDS, RATE_RANGE, STATE_SIZE};
+use crate::generation::{generate_tip5_trace, hash_10, hash_varlen, hash_varlen_rows, Row};
+use crate::stark::{trace_to_poly_values, Tip5Stark};
+use anyhow::Result;
+use plonky2::field::types::Field;
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::util::timing::TimingTree;
+use starky::config::StarkConfig;
+use starky::prover::prove;
+use starky::verifier::verify_stark_proof;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+type S = Tip5Stark<F, D>;
+
+const HASH_10_FINAL_DIGEST: [u64; DIGEST_SIZE] = [
+    10_869_784_347_448_351_760,
+    1_853_783_032_222_938_415,
+    6_856_460_589_287_344_822,
+    17_178_399_545_409_290_325,
+    7_650_660_984_651_717_733,
+];
+
+const HASH_VARLEN_DIGEST_SUM: [u64; DIGEST_SIZE] = [
+    7_610_004_073_009_036_015,
+    5_725_198_067_541_094_245,
+    4_721_320_565_792_709_122,
+    1_732_504_843_634_706_218,
+    259_800_783_350_288_362,
+];
+
+/// The permutation inputs of the `hash_10` chain
+fn hash_10_rows() -> Vec<Row<F>> {
+    let mut preimage = [F::ZERO; RATE_RANGE.end];
+    let mut rows = Vec::new();
+    for i in 0..=6 {
+        let mut state = [F::ONE; STATE_SIZE];
+        state[RATE_RANGE].copy_from_slice(&preimage);
+        rows.push(Row { preimage: state });
+        if i < 6 {
+            let digest = hash_10(&preimage);
+            preimage[i..DIGEST_SIZE + i].copy_from_slice(&digest);
+        }
+    }
+    rows
+}
+
+fn varlen_preimage(i: u64) -> Vec<F> {
+    (0..i).map(F::from_canonical_u64).collect()
+}
+
+#[test]
+fn native_vectors() {
+    let rows = hash_10_rows();
+    let last: [F; RATE_RANGE.end] = rows[6].preimage[RATE_RANGE].try_into().unwrap();
+    assert_eq!(
+        hash_10(&last),
+        HASH_10_FINAL_DIGEST.map(F::from_canonical_u64)
+    );
+
+    let mut sum = [F::ZERO; DIGEST_SIZE];
+    for i in 0..20 {
+        for (s, d) in sum.iter_mut().zip(hash_varlen(&varlen_preimage(i))) {
+            *s += d;
+        }
+    }
+    assert_eq!(sum, HASH_VARLEN_DIGEST_SUM.map(F::from_canonical_u64));
+}
+
+#[test]
+fn trace_vectors() -> Result<()> {
+    let mut step_rows = hash_10_rows();
+    let mut varlen_last_rows = Vec::new();
+    for i in 0..20 {
+        step_rows.extend(hash_varlen_rows(&varlen_preimage(i)));
+        varlen_last_rows.push(step_rows.len() - 1);
+    }
+    let trace = generate_tip5_trace(&step_rows);
+
+    let digest_at = |permutation: usize| -> [F; DIGEST_SIZE] {
+        let row = permutation * NUM_ROUNDS + NUM_ROUNDS - 1;
+        std::array::from_fn(|j| trace[COL_OUTPUT_START + j][row])
+    };
+    assert_eq!(
+        digest_at(6),
+        HASH_10_FINAL_DIGEST.map(F::from_canonical_u64)
+    );
+    let mut sum = [F::ZERO; DIGEST_SIZE];
+    for permutation in varlen_last_rows {
+        for (s, d) in sum.iter_mut().zip(digest_at(permutation)) {
+            *s += d;
+        }
+    }
+    assert_eq!(sum, HASH_VARLEN_DIGEST_SUM.map(F::from_canonical_u64));
+
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.cap_height = 0;
+    config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+    let stark = S::default();
+    let proof = prove::<F, C, S, D>(
+        stark,
+        &config,
+        trace_to_poly_values(trace),
+        [],
+        &mut TimingTree::default(),
+    )?;
+    verify_stark_proof(stark, proof, &config)
+}
@@ -0,0 +1,280 @@
+use crate::columns::{
+    col_canonical_inv, col_input_bytes, col_lookup, col_output_bytes, col_permuted,
+    COL_OUTPUT_START, COL_STATE_START, COL_STEP_START, COL_TABLE_INPUT, COL_TABLE_OUTPUT,
+    COL_TABLE_OUTPUT_BITS_START, COL_TABLE_QUOTIENT, COL_TABLE_QUOTIENT_BITS_START, NUM_COLS,
+    NUM_LOOKUPS, TABLE_QUOTIENT_BITS,
+};
+use crate::constants::{
+    ALPHA, MDS_FIRST_COLUMN, MONTGOMERY_R, NUM_BYTES, NUM_ROUNDS, NUM_SPLIT_AND_LOOKUP,
+    ROUND_CONSTANTS, STATE_SIZE,
+};
+use permutation_air::lookup::{eval_lookups, eval_table_input, lookup_permutation_pairs};
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use starky::permutation::PermutationPair;
+use starky::stark::Stark;
+use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+use std::marker::PhantomData;
+
+// linear layer (degree = 1)
+fn mds_constraints<P: PackedField>(state: &[P; STATE_SIZE]) -> [P; STATE_SIZE] {
+    std::array::from_fn(|i| {
+        (0..STATE_SIZE).fold(P::ZEROS, |acc, j| {
+            acc + state[j]
+                * P::Scalar::from_canonical_u64(MDS_FIRST_COLUMN[(i + STATE_SIZE - j) % STATE_SIZE])
+        })
+    })
+}
+
+// degree: 7
+fn sbox_constraints<P: PackedField>(x: P) -> P {
+    (0..ALPHA).fold(P::ONES, |acc, _| acc * x)
+}
+
+/// Recompose the little-endian bytes starting at column `col`
+fn bytes_at<P: PackedField>(lv: &[P], col: usize, num_bytes: usize) -> P {
+    (0..num_bytes).rev().fold(P::ZEROS, |acc, k| {
+        acc * P::Scalar::from_canonical_u16(256) + lv[col + k]
+    })
+}
+
+/// Constraints of the round flags, returning 1 on the rows of every round
+/// but the last one
+fn eval_rounds<P: PackedField>(lv: &[P], nv: &[P], yield_constr: &mut ConstraintConsumer<P>) -> P {
+    // the round flags are one-hot or all zero on padding rows, start with
+    // round 0 and step through the rounds in order
+    let mut flag_sum = P::ZEROS;
+    for round in 0..NUM_ROUNDS {
+        let flag = lv[COL_STEP_START + round];
+        yield_constr.constraint(flag * (flag - P::ONES));
+        flag_sum += flag;
+        if round > 0 {
+            yield_constr.constraint_first_row(flag);
+        }
+        if round < NUM_ROUNDS - 1 {
+            yield_constr.constraint_transition(nv[COL_STEP_START + round + 1] - flag);
+        }
+    }
+    yield_constr.constraint(flag_sum * (flag_sum - P::ONES));
+    flag_sum - lv[COL_STEP_START + NUM_ROUNDS - 1]
+}
+
+/// Constraints of the split-and-lookup S-box of split element `i`, returning
+/// its output. The looked-up bytes are checked by the lookup argument.
+fn eval_split_and_lookup<P: PackedField>(
+    lv: &[P],
+    i: usize,
+    yield_constr: &mut ConstraintConsumer<P>,
+) -> P {
+    let montgomery_r = P::Scalar::from_canonical_u64(MONTGOMERY_R);
+
+    // the input bytes are the Montgomery form `x * R` of the element
+    let input = bytes_at(lv, col_input_bytes(i), NUM_BYTES);
+    yield_constr.constraint(input - lv[COL_STATE_START + i] * montgomery_r);
+
+    // and below p: if the high half is 2^32 - 1, the low half is zero
+    let lo = bytes_at(lv, col_input_bytes(i), NUM_BYTES / 2);
+    let hi = bytes_at(lv, col_input_bytes(i) + NUM_BYTES / 2, NUM_BYTES / 2);
+    let hi_diff = hi - P::from(montgomery_r);
+    yield_constr.constraint(lo * (P::ONES - hi_diff * lv[col_canonical_inv(i)]));
+
+    // the output bytes are the Montgomery form of the output
+    bytes_at(lv, col_output_bytes(i), NUM_BYTES) * montgomery_r.inverse()
+}
+
+/// Constraints of the lookup table: every byte `x` and its offset Fermat cube
+/// map, the byte `y` with `(x + 1)^3 - 1 = y + 257 q` for a range-checked `q`
+fn eval_table<P: PackedField>(lv: &[P], nv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    eval_table_input(lv, nv, COL_TABLE_INPUT, 255, yield_constr);
+
+    // the output and the quotient are the sums of their bits
+    let mut bits_sum = |start: usize, num_bits: usize| {
+        (0..num_bits).rev().fold(P::ZEROS, |acc, k| {
+            let bit = lv[start + k];
+            yield_constr.constraint(bit * (bit - P::ONES));
+            acc * P::Scalar::TWO + bit
+        })
+    };
+    let output = bits_sum(COL_TABLE_OUTPUT_BITS_START, 8);
+    let quotient = bits_sum(COL_TABLE_QUOTIENT_BITS_START, TABLE_QUOTIENT_BITS);
+    yield_constr.constraint(output - lv[COL_TABLE_OUTPUT]);
+    yield_constr.constraint(quotient - lv[COL_TABLE_QUOTIENT]);
+
+    // both sides are below 2^25, so the equation holds over the integers
+    let x_plus_one = lv[COL_TABLE_INPUT] + P::ONES;
+    yield_constr.constraint(
+        x_plus_one * x_plus_one * x_plus_one
+            - P::ONES
+            - lv[COL_TABLE_OUTPUT]
+            - lv[COL_TABLE_QUOTIENT] * P::Scalar::from_canonical_u16(257),
+    );
+}
+
+/// Constraints of the Tip5 table laid out as in `columns.rs`
+fn eval_tip5<P: PackedField>(lv: &[P], nv: &[P], yield_constr: &mut ConstraintConsumer<P>) {
+    let not_last_round = eval_rounds(lv, nv, yield_constr);
+
+    // S-box layer
+    let sbox_state: [P; STATE_SIZE] = std::array::from_fn(|i| {
+        let x = lv[COL_STATE_START + i];
+        if i < NUM_SPLIT_AND_LOOKUP {
+            eval_split_and_lookup(lv, i, yield_constr)
+        } else {
+            sbox_constraints(x)
+        }
+    });
+
+    // MDS matrix and the constants of the round
+    let state = mds_constraints(&sbox_state);
+    for (i, &value) in state.iter().enumerate() {
+        let round_constant = (0..NUM_ROUNDS).fold(P::ZEROS, |acc, r| {
+            acc + lv[COL_STEP_START + r] * P::Scalar::from_canonical_u64(ROUND_CONSTANTS[r][i])
+        });
+        yield_constr.constraint(lv[COL_OUTPUT_START + i] - value - round_constant);
+    }
+
+    // the next round starts from the output of this round
+    for i in 0..STATE_SIZE {
+        yield_constr.constraint_transition(
+            not_last_round * (nv[COL_STATE_START + i] - lv[COL_OUTPUT_START + i]),
+        );
+    }
+
+    eval_table(lv, nv, yield_constr);
+    for j in 0..NUM_LOOKUPS {
+        eval_lookups(lv, nv, col_permuted(j), yield_constr);
+    }
+}
+
+/// The Tip5 permutation table, one row per round and 5 rows per
+/// permutation.
+#[derive(Copy, Clone, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct Tip5Stark<F, const D: usize> {
+    pub _f: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Tip5Stark<F, D> {
+    const COLUMNS: usize = NUM_COLS;
+    const PUBLIC_INPUTS: usize = 0;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        eval_tip5(vars.local_values, vars.next_values, yield_constr);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        7
+    }
+
+    fn permutation_pairs(&self) -> Vec<PermutationPair> {
+        (0..NUM_LOOKUPS)
+            .flat_map(|j| {
+                lookup_permutation_pairs(
+                    col_lookup(j),
+                    [COL_TABLE_INPUT, COL_TABLE_OUTPUT],
+                    col_permuted(j),
+                )
+            })
+            .collect()
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        _builder: &mut CircuitBuilder<F, D>,
+        _vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        unimplemented!()
+    }
+}
+
+pub fn trace_to_poly_values<F: Field, const COLUMNS: usize>(
+    trace: [Vec<F>; COLUMNS],
+) -> Vec<PolynomialValues<F>> {
+    trace.into_iter().map(PolynomialValues::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::LOOKUP_TABLE;
+    use crate::generation::{generate_tip5_trace, generate_tip5_trace_with_table, Row};
+    use crate::stark::{trace_to_poly_values, Tip5Stark};
+    use anyhow::Result;
+    use permutation_air::conformance::check_constraints;
+    use plonky2::field::types::Sample;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+    use starky::config::StarkConfig;
+    use starky::prover::prove;
+    use starky::stark_testing::test_stark_low_degree;
+    use starky::verifier::verify_stark_proof;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Tip5Stark<F, D>;
+
+    #[test]
+    fn tip5_constraints() -> Result<()> {
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.cap_height = 0;
+        config.fri_config.rate_bits = 3; // to meet the constraint degree bound
+
+        let step_rows: Vec<Row<F>> = (0..12)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let stark = S::default();
+        let trace = generate_tip5_trace(&step_rows);
+        let trace_poly_values = trace_to_poly_values(trace);
+
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            [],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn forged_table_fails() {
+        // a table swapping two images gives a trace of another permutation
+        // whose lookups and rounds are consistent, so only the table
+        // constraints reject it
+        let mut table = LOOKUP_TABLE;
+        table.swap(1, 2);
+        let step_rows: Vec<Row<F>> = (0..12)
+            .map(|_| Row {
+                preimage: F::rand_array(),
+            })
+            .collect();
+
+        let stark = S::default();
+        let honest = generate_tip5_trace(&step_rows);
+        assert!(check_constraints(&stark, &honest, &[]).is_ok());
+        let forged = generate_tip5_trace_with_table(&step_rows, &table);
+        assert!(check_constraints(&stark, &forged, &[]).is_err());
+    }
+
+    #[test]
+    fn tip5_stark_degree() -> Result<()> {
+        let stark = S::default();
+        test_stark_low_degree(stark)
+    }
+}